@@ -1,8 +1,9 @@
+use nu_engine::CallExt;
 use nu_protocol::{
     ast::Call,
     engine::{Closure, Command, EngineState, Stack, StateWorkingSet},
     record, Category, Example, IntoPipelineData, PipelineData, PipelineMetadata, Record,
-    ShellError, Signature, Type, Value,
+    ShellError, Signature, SyntaxShape, Type, Value,
 };
 
 #[derive(Clone)]
@@ -34,6 +35,43 @@ impl Command for Describe {
                 Some('d'),
             )
             .switch("collect-lazyrecords", "collect lazy records", Some('l'))
+            .switch(
+                "graph",
+                "emit a Graphviz DOT diagram of the value's structure instead of a record",
+                None,
+            )
+            .switch(
+                "schema",
+                "infer a JSON-Schema-style descriptor, unifying the shapes of list elements",
+                None,
+            )
+            .named(
+                "sample",
+                SyntaxShape::Int,
+                "when describing a stream, only pull this many items to infer its shape (default 100)",
+                None,
+            )
+            .switch(
+                "stats",
+                "augment the detailed description with an estimated size per node and \
+                 aggregate node count / max depth / distinct leaf type counters",
+                None,
+            )
+            .named(
+                "max-depth",
+                SyntaxShape::Int,
+                "stop recursing past this many levels deep and report a truncated placeholder \
+                 instead, to protect against unbounded or self-referential structures (default 50)",
+                None,
+            )
+            .switch(
+                "recursive",
+                "fully descend into nested records, lists, and tables, reporting a deduplicated \
+                 set of element types for lists and a unified column schema for tables, rather \
+                 than describing only the value's own level (implies --detailed; list and \
+                 external streams are described as if it were not given)",
+                Some('r'),
+            )
             .category(Category::Core)
     }
 
@@ -44,11 +82,11 @@ impl Command for Describe {
     fn run(
         &self,
         engine_state: &EngineState,
-        _stack: &mut Stack,
+        stack: &mut Stack,
         call: &Call,
         input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
-        run(Some(engine_state), call, input)
+        run(Some(engine_state), stack, call, input)
     }
 
     fn run_const(
@@ -57,7 +95,8 @@ impl Command for Describe {
         call: &Call,
         input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
-        run(None, call, input)
+        let mut stack = Stack::new();
+        run(None, &mut stack, call, input)
     }
 
     fn examples(&self) -> Vec<Example> {
@@ -175,6 +214,7 @@ impl Command for Describe {
 
 fn run(
     engine_state: Option<&EngineState>,
+    stack: &mut Stack,
     call: &Call,
     input: PipelineData,
 ) -> Result<PipelineData, ShellError> {
@@ -182,6 +222,58 @@ fn run(
     let head = call.head;
     let no_collect: bool = call.has_flag("no-collect");
     let detailed = call.has_flag("detailed");
+    // `--sample` only has a meaningful value on the real (non-const) evaluation path; constant
+    // folding never sees an actual stream, so it isn't worth plumbing a `StateWorkingSet` through
+    // just to read this one flag there.
+    let sample_size: usize = match engine_state {
+        Some(engine_state) => call
+            .get_flag::<i64>(engine_state, stack, "sample")?
+            .map(|n| n.max(0) as usize)
+            .unwrap_or(100),
+        None => 100,
+    };
+    // `--max-depth` bounds the record/list recursion in `describe_value`/`describe_record` so a
+    // self-referential custom value (or just a very deeply nested structure) can't blow the stack.
+    let max_depth: usize = match engine_state {
+        Some(engine_state) => call
+            .get_flag::<i64>(engine_state, stack, "max-depth")?
+            .map(|n| n.max(0) as usize)
+            .unwrap_or(50),
+        None => 50,
+    };
+
+    if call.has_flag("graph") {
+        let value = input.into_value(head);
+        let dot = describe_graph(&value, head, engine_state, call);
+        return Ok(Value::string(dot, head).into_pipeline_data());
+    }
+
+    if call.has_flag("schema") {
+        let value = input.into_value(head);
+        return Ok(value_schema(&value, head).into_pipeline_data());
+    }
+
+    if call.has_flag("stats") {
+        let value = input.into_value(head);
+        let mut stats = SizeStats::default();
+        let described =
+            describe_with_stats(&value, head, engine_state, stack, call, 0, max_depth, &mut stats)?;
+        let mut record = match described {
+            Value::Record { val, .. } => val,
+            other => {
+                let mut record = Record::new();
+                record.push("type", other);
+                record
+            }
+        };
+        record.push("node_count", Value::int(stats.node_count, head));
+        record.push("max_depth", Value::int(stats.max_depth as i64, head));
+        record.push(
+            "distinct_leaf_types",
+            Value::int(stats.leaf_types.len() as i64, head),
+        );
+        return Ok(Value::record(record, head).into_pipeline_data());
+    }
 
     let description: Value = match input {
         PipelineData::ExternalStream {
@@ -236,23 +328,45 @@ fn run(
                 Value::string("raw input", head)
             }
         }
-        PipelineData::ListStream(_, _) => {
+        PipelineData::ListStream(stream, _) => {
             if detailed {
-                Value::record(
-                    record!(
-                        "type" => Value::string("stream", head),
-                        "origin" => Value::string("nushell", head),
-                        "subtype" => {
-                           if no_collect {
-                            Value::string("any", head)
-                           } else {
-                            describe_value(input.into_value(head), head, engine_state, call)?
-                           }
-                        },
-                        "metadata" => metadata_to_value(metadata, head),
-                    ),
-                    head,
-                )
+                if no_collect {
+                    Value::record(
+                        record!(
+                            "type" => Value::string("stream", head),
+                            "origin" => Value::string("nushell", head),
+                            "subtype" => Value::string("any", head),
+                            "metadata" => metadata_to_value(metadata, head),
+                        ),
+                        head,
+                    )
+                } else {
+                    // Only pull the first `sample_size` items so describing an infinite or huge
+                    // stream can't OOM the process - the rest of the stream is dropped either
+                    // way since `describe` always consumes its input.
+                    let sampled: Vec<Value> = stream.take(sample_size).collect();
+                    let examined = sampled.len();
+                    let subtype = sampled
+                        .into_iter()
+                        .map(|v| describe_value(v, head, engine_state, stack, call, 0, max_depth))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .reduce(|a, b| unify_schema(&a, &b, head))
+                        .unwrap_or_else(|| {
+                            Value::record(record!("type" => Value::string("any", head)), head)
+                        });
+                    Value::record(
+                        record!(
+                            "type" => Value::string("stream", head),
+                            "origin" => Value::string("nushell", head),
+                            "subtype" => subtype,
+                            "sampled" => Value::bool(true, head),
+                            "sample_size" => Value::int(examined as i64, head),
+                            "metadata" => metadata_to_value(metadata, head),
+                        ),
+                        head,
+                    )
+                }
             } else if no_collect {
                 Value::string("stream", head)
             } else {
@@ -267,13 +381,16 @@ fn run(
         }
         _ => {
             let value = input.into_value(head);
-            if !detailed {
+            let recursive = call.has_flag("recursive");
+            if !detailed && !recursive {
                 match value {
                     Value::CustomValue { val, .. } => Value::string(val.value_string(), head),
                     _ => Value::string(value.get_type().to_string(), head),
                 }
+            } else if recursive {
+                describe_value_rec(value, head, engine_state, stack, call, 0, max_depth)?
             } else {
-                describe_value(value, head, engine_state, call)?
+                describe_value(value, head, engine_state, stack, call, 0, max_depth)?
             }
         }
     };
@@ -285,13 +402,26 @@ fn describe_value(
     value: Value,
     head: nu_protocol::Span,
     engine_state: Option<&EngineState>,
+    stack: &mut Stack,
     call: &Call,
+    depth: usize,
+    max_depth: usize,
 ) -> Result<Value, ShellError> {
+    if depth >= max_depth {
+        return Ok(Value::record(
+            record!(
+                "type" => Value::string(value.get_type().to_string(), head),
+                "truncated" => Value::bool(true, head),
+            ),
+            head,
+        ));
+    }
+
     Ok(match value {
         Value::CustomValue { val, internal_span } => Value::record(
             record!(
                 "type" => Value::string("custom", head),
-                "subtype" => run(engine_state,call, val.to_base_value(internal_span)?.into_pipeline_data())?.into_value(head),
+                "subtype" => run(engine_state, stack, call, val.to_base_value(internal_span)?.into_pipeline_data())?.into_value(head),
             ),
             head,
         ),
@@ -328,13 +458,13 @@ fn describe_value(
             ),
             head,
         ),
-        Value::Record { val, .. } => describe_record(val, head, engine_state, call, false)?,
+        Value::Record { val, .. } => describe_record(val, head, engine_state, stack, call, false, depth, max_depth)?,
         Value::List { vals, .. } => Value::record(
             record!(
                 "type" => Value::string(Type::List(Box::new(Type::Nothing)).get_non_specified_string(), head),
                 "length" => Value::int(vals.len() as i64, head),
                 "values" => Value::list(vals.iter().map(|v|
-                    match describe_value(v.clone(), head, engine_state, call) {
+                    match describe_value(v.clone(), head, engine_state, stack, call, depth + 1, max_depth) {
                         Ok(Value::Record {val, ..}) => if val.cols.as_slice() == ["type"] {Ok(val.vals[0].clone())} else {Ok(Value::record(val, head))},
                         x => x
                     }
@@ -445,7 +575,7 @@ fn describe_value(
             if collect_lazyrecords {
                 let collected = val.collect()?;
                 if let Value::Record { val, .. } = collected {
-                    describe_record(val, head, engine_state, call, true)?
+                    describe_record(val, head, engine_state, stack, call, true, depth, max_depth)?
                 } else {
                     return Err(ShellError::CantConvert {
                         from_type: collected.get_type().to_string(),
@@ -472,8 +602,11 @@ fn describe_record(
     val: Record,
     head: nu_protocol::Span,
     engine_state: Option<&EngineState>,
+    stack: &mut Stack,
     call: &Call,
     is_lazy: bool,
+    depth: usize,
+    max_depth: usize,
 ) -> Result<Value, ShellError> {
     let mut record = Record::new();
     for i in 0..val.len() {
@@ -481,7 +614,7 @@ fn describe_record(
         let v = val.vals[i].clone();
 
         record.push(k, {
-            if let Value::Record { val, .. } = describe_value(v.clone(), head, engine_state, call)?
+            if let Value::Record { val, .. } = describe_value(v.clone(), head, engine_state, stack, call, depth + 1, max_depth)?
             {
                 if let [Value::String { val: k, .. }] = val.vals.as_slice() {
                     Value::string(k, head)
@@ -489,7 +622,7 @@ fn describe_record(
                     Value::record(val, head)
                 }
             } else {
-                describe_value(v, head, engine_state, call)?
+                describe_value(v, head, engine_state, stack, call, depth + 1, max_depth)?
             }
         });
     }
@@ -503,6 +636,573 @@ fn describe_record(
     ))
 }
 
+/// Like [`describe_value`], but descends fully into nested records, lists, and tables rather than
+/// stopping after the first level: nested records keep recursing through [`describe_record_rec`],
+/// and lists/tables are summarized by [`describe_list_rec`] instead of itemizing every element.
+/// Scalars and the other leaf-ish variants (closures, ranges, etc.) fall back to [`describe_value`]
+/// unchanged, since there's nothing further to recurse into.
+fn describe_value_rec(
+    value: Value,
+    head: nu_protocol::Span,
+    engine_state: Option<&EngineState>,
+    stack: &mut Stack,
+    call: &Call,
+    depth: usize,
+    max_depth: usize,
+) -> Result<Value, ShellError> {
+    if depth >= max_depth {
+        return Ok(Value::record(
+            record!(
+                "type" => Value::string(value.get_type().to_string(), head),
+                "truncated" => Value::bool(true, head),
+            ),
+            head,
+        ));
+    }
+
+    Ok(match value {
+        Value::Record { val, .. } => {
+            describe_record_rec(val, head, engine_state, stack, call, false, depth, max_depth)?
+        }
+        Value::List { vals, .. } => {
+            describe_list_rec(vals, head, engine_state, stack, call, depth, max_depth)?
+        }
+        Value::LazyRecord { val, .. } => {
+            // Pull columns one at a time through the trait's own accessor instead of `collect`ing
+            // the whole record up front, so a branch the caller never looks at twice still only
+            // costs one evaluation.
+            let mut columns = Record::new();
+            for name in val.column_names() {
+                let child = val.get_column_value(name)?;
+                columns.push(
+                    name.to_string(),
+                    describe_value_rec(child, head, engine_state, stack, call, depth + 1, max_depth)?,
+                );
+            }
+            Value::record(
+                record!(
+                    "type" => Value::string("record", head),
+                    "lazy" => Value::bool(true, head),
+                    "columns" => Value::record(columns, head),
+                ),
+                head,
+            )
+        }
+        other => describe_value(other, head, engine_state, stack, call, depth, max_depth)?,
+    })
+}
+
+/// Recursive counterpart to [`describe_record`]: each column's value is described via
+/// [`describe_value_rec`] instead of the one-level [`describe_value`], so nested records/lists
+/// keep expanding all the way down (subject to `max_depth`).
+#[allow(clippy::too_many_arguments)]
+fn describe_record_rec(
+    val: Record,
+    head: nu_protocol::Span,
+    engine_state: Option<&EngineState>,
+    stack: &mut Stack,
+    call: &Call,
+    is_lazy: bool,
+    depth: usize,
+    max_depth: usize,
+) -> Result<Value, ShellError> {
+    let mut columns = Record::new();
+    for (col, child) in val.into_iter() {
+        columns.push(
+            col,
+            describe_value_rec(child, head, engine_state, stack, call, depth + 1, max_depth)?,
+        );
+    }
+    Ok(Value::record(
+        record!(
+            "type" => Value::string("record", head),
+            "lazy" => Value::bool(is_lazy, head),
+            "columns" => Value::record(columns, head),
+        ),
+        head,
+    ))
+}
+
+/// Recursive list/table summary used by [`describe_value_rec`]. If every element describes as a
+/// record, the elements are treated as table rows: their column schemas are unified into one
+/// `columns` record where each column reports its deduplicated set of element types and whether
+/// it's `heterogeneous` across rows. Otherwise the list itself reports a deduplicated
+/// `element_types` set and a `heterogeneous` flag the same way.
+fn describe_list_rec(
+    vals: Vec<Value>,
+    head: nu_protocol::Span,
+    engine_state: Option<&EngineState>,
+    stack: &mut Stack,
+    call: &Call,
+    depth: usize,
+    max_depth: usize,
+) -> Result<Value, ShellError> {
+    let length = vals.len();
+    let descriptions = vals
+        .into_iter()
+        .map(|v| describe_value_rec(v, head, engine_state, stack, call, depth + 1, max_depth))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let description_type = |d: &Value| -> String {
+        match d {
+            Value::Record { val, .. } => val
+                .get("type")
+                .and_then(|t| t.coerce_string().ok())
+                .unwrap_or_default(),
+            other => other.get_type().to_string(),
+        }
+    };
+
+    let is_table = length > 0 && descriptions.iter().all(|d| description_type(d) == "record");
+
+    if is_table {
+        let mut column_types: std::collections::BTreeMap<
+            String,
+            std::collections::BTreeSet<String>,
+        > = Default::default();
+        for d in &descriptions {
+            let Value::Record { val, .. } = d else {
+                continue;
+            };
+            let Some(Value::Record { val: cols, .. }) = val.get("columns") else {
+                continue;
+            };
+            for (col, child) in cols.cols.iter().zip(cols.vals.iter()) {
+                column_types
+                    .entry(col.clone())
+                    .or_default()
+                    .insert(description_type(child));
+            }
+        }
+
+        let mut columns = Record::new();
+        for (name, types) in column_types {
+            let heterogeneous = types.len() > 1;
+            columns.push(
+                name,
+                Value::record(
+                    record!(
+                        "element_types" => Value::list(
+                            types.into_iter().map(|t| Value::string(t, head)).collect(),
+                            head,
+                        ),
+                        "heterogeneous" => Value::bool(heterogeneous, head),
+                    ),
+                    head,
+                ),
+            );
+        }
+
+        return Ok(Value::record(
+            record!(
+                "type" => Value::string("table", head),
+                "length" => Value::int(length as i64, head),
+                "columns" => Value::record(columns, head),
+            ),
+            head,
+        ));
+    }
+
+    let element_types: std::collections::BTreeSet<String> =
+        descriptions.iter().map(description_type).collect();
+    let heterogeneous = element_types.len() > 1;
+
+    Ok(Value::record(
+        record!(
+            "type" => Value::string("list", head),
+            "length" => Value::int(length as i64, head),
+            "element_types" => Value::list(
+                element_types.into_iter().map(|t| Value::string(t, head)).collect(),
+                head,
+            ),
+            "heterogeneous" => Value::bool(heterogeneous, head),
+        ),
+        head,
+    ))
+}
+
+/// Accumulates `node`/`edge` statements for a Graphviz `digraph`, handing out a fresh `n<id>` per
+/// node so callers don't have to track ids themselves.
+struct DotBuilder {
+    buffer: String,
+    next_id: usize,
+}
+
+impl DotBuilder {
+    fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            next_id: 0,
+        }
+    }
+
+    fn alloc_node(&mut self, label: &str) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.buffer
+            .push_str(&format!("  n{id} [label=\"{}\"];\n", escape_dot_label(label)));
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize, label: &str) {
+        self.buffer.push_str(&format!(
+            "  n{from} -> n{to} [label=\"{}\"];\n",
+            escape_dot_label(label)
+        ));
+    }
+}
+
+fn escape_dot_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Renders `value`'s structure as a Graphviz DOT `digraph`: one node per record/list/closure/
+/// range/etc. (labeled with its type, plus a column count or item count for records and lists),
+/// one edge per child keyed by the record's
+/// column name or the list's index, and leaf scalars as terminal nodes labeled with their type.
+fn describe_graph(
+    value: &Value,
+    head: nu_protocol::Span,
+    engine_state: Option<&EngineState>,
+    call: &Call,
+) -> String {
+    let mut dot = DotBuilder::new();
+    graph_node(value, head, engine_state, call, &mut dot);
+    format!("digraph describe {{\n{}}}\n", dot.buffer)
+}
+
+fn graph_node(
+    value: &Value,
+    head: nu_protocol::Span,
+    engine_state: Option<&EngineState>,
+    call: &Call,
+    dot: &mut DotBuilder,
+) -> usize {
+    match value {
+        Value::Record { val, .. } => {
+            let id = dot.alloc_node(&format!("record ({} cols)", val.len()));
+            for (col, child) in val.cols.iter().zip(val.vals.iter()) {
+                let child_id = graph_node(child, head, engine_state, call, dot);
+                dot.edge(id, child_id, col);
+            }
+            id
+        }
+        Value::List { vals, .. } => {
+            let id = dot.alloc_node(&format!("list ({} items)", vals.len()));
+            for (i, child) in vals.iter().enumerate() {
+                let child_id = graph_node(child, head, engine_state, call, dot);
+                dot.edge(id, child_id, &i.to_string());
+            }
+            id
+        }
+        Value::CustomValue { val, internal_span } => {
+            let id = dot.alloc_node("custom");
+            if let Ok(base) = val.to_base_value(*internal_span) {
+                let child_id = graph_node(&base, head, engine_state, call, dot);
+                dot.edge(id, child_id, "base");
+            }
+            id
+        }
+        Value::LazyRecord { val, .. } => {
+            dot.alloc_node(&format!("record (lazy, {} cols)", val.column_names().len()))
+        }
+        Value::Range { .. } => dot.alloc_node("range"),
+        Value::Block { .. } | Value::Closure { .. } => dot.alloc_node("closure"),
+        other => dot.alloc_node(&other.get_type().to_string()),
+    }
+}
+
+/// Infers a JSON-Schema-style descriptor for `value`: records become `{type: "object", properties,
+/// required}`, lists become `{type: "array", items: <unify of every element's schema>}`, and
+/// scalars become `{type: "<type name>"}`. The result is a plain nu record meant to be piped into
+/// `to json`.
+fn value_schema(value: &Value, head: nu_protocol::Span) -> Value {
+    match value {
+        Value::Record { val, .. } => {
+            let mut properties = Record::new();
+            let mut required = Vec::new();
+            for (col, child) in val.cols.iter().zip(val.vals.iter()) {
+                properties.push(col.clone(), value_schema(child, head));
+                required.push(Value::string(col.clone(), head));
+            }
+            Value::record(
+                record!(
+                    "type" => Value::string("object", head),
+                    "properties" => Value::record(properties, head),
+                    "required" => Value::list(required, head),
+                ),
+                head,
+            )
+        }
+        Value::List { vals, .. } => {
+            let items = vals
+                .iter()
+                .map(|v| value_schema(v, head))
+                .reduce(|a, b| unify_schema(&a, &b, head))
+                .unwrap_or_else(|| {
+                    Value::record(record!("type" => Value::string("any", head)), head)
+                });
+            Value::record(
+                record!(
+                    "type" => Value::string("array", head),
+                    "items" => items,
+                ),
+                head,
+            )
+        }
+        _ => Value::record(
+            record!("type" => Value::string(value.get_type().to_string(), head)),
+            head,
+        ),
+    }
+}
+
+/// Merges two value schemas produced by [`value_schema`]. Identical schemas collapse to one;
+/// `int`/`float` unify to `number`; two `object` schemas union their properties (recursively
+/// unifying shared keys) and keep only the keys both sides required; anything else irreconcilable
+/// collapses to `any`.
+fn unify_schema(a: &Value, b: &Value, head: nu_protocol::Span) -> Value {
+    if a == b {
+        return a.clone();
+    }
+
+    let schema_type = |v: &Value| -> String {
+        match v {
+            Value::Record { val, .. } => val
+                .get("type")
+                .and_then(|t| t.coerce_string().ok())
+                .unwrap_or_else(|| "any".to_string()),
+            _ => "any".to_string(),
+        }
+    };
+    let ta = schema_type(a);
+    let tb = schema_type(b);
+
+    // `number` comes from an earlier int/float merge, so it absorbs further ints and floats.
+    let is_numeric = |t: &str| matches!(t, "int" | "float" | "number");
+    if is_numeric(&ta) && is_numeric(&tb) {
+        return Value::record(record!("type" => Value::string("number", head)), head);
+    }
+
+    if ta == "object" && tb == "object" {
+        let (Value::Record { val: ra, .. }, Value::Record { val: rb, .. }) = (a, b) else {
+            return Value::record(record!("type" => Value::string("any", head)), head);
+        };
+        let props_of = |r: &Record| -> std::collections::BTreeMap<String, Value> {
+            match r.get("properties") {
+                Some(Value::Record { val, .. }) => val
+                    .cols
+                    .iter()
+                    .cloned()
+                    .zip(val.vals.iter().cloned())
+                    .collect(),
+                _ => Default::default(),
+            }
+        };
+        let required_of = |r: &Record| -> std::collections::BTreeSet<String> {
+            match r.get("required") {
+                Some(Value::List { vals, .. }) => vals
+                    .iter()
+                    .filter_map(|v| v.coerce_string().ok())
+                    .collect(),
+                _ => Default::default(),
+            }
+        };
+        let props_a = props_of(ra);
+        let props_b = props_of(rb);
+        let required_a = required_of(ra);
+        let required_b = required_of(rb);
+
+        let mut properties = Record::new();
+        let mut all_keys: std::collections::BTreeSet<&String> =
+            props_a.keys().chain(props_b.keys()).collect();
+        while let Some(key) = all_keys.pop_first() {
+            let merged = match (props_a.get(key), props_b.get(key)) {
+                (Some(sa), Some(sb)) => unify_schema(sa, sb, head),
+                (Some(s), None) | (None, Some(s)) => s.clone(),
+                (None, None) => unreachable!("key came from one of the two maps"),
+            };
+            properties.push(key.clone(), merged);
+        }
+
+        let required: Vec<Value> = required_a
+            .intersection(&required_b)
+            .map(|k| Value::string(k.clone(), head))
+            .collect();
+
+        return Value::record(
+            record!(
+                "type" => Value::string("object", head),
+                "properties" => Value::record(properties, head),
+                "required" => Value::list(required, head),
+            ),
+            head,
+        );
+    }
+
+    if ta == "array" && tb == "array" {
+        let items = match (a, b) {
+            (Value::Record { val: ra, .. }, Value::Record { val: rb, .. }) => {
+                match (ra.get("items"), rb.get("items")) {
+                    (Some(ia), Some(ib)) => unify_schema(ia, ib, head),
+                    _ => Value::record(record!("type" => Value::string("any", head)), head),
+                }
+            }
+            _ => Value::record(record!("type" => Value::string("any", head)), head),
+        };
+        return Value::record(
+            record!(
+                "type" => Value::string("array", head),
+                "items" => items,
+            ),
+            head,
+        );
+    }
+
+    Value::record(record!("type" => Value::string("any", head)), head)
+}
+
+/// Aggregate counters `describe_with_stats` accumulates as it walks a value: how many nodes it
+/// visited in total, the deepest nesting it saw, and how many distinct leaf (scalar) type names
+/// showed up.
+#[derive(Default)]
+struct SizeStats {
+    node_count: i64,
+    max_depth: usize,
+    leaf_types: std::collections::BTreeSet<String>,
+}
+
+/// Like [`describe_value`], but augments every record/list node with an estimated `size`
+/// (a [`Type::Filesize`]) and records it in `stats` as it goes, so `--stats` can report both a
+/// per-node size and root-level aggregates (total nodes, max depth, distinct leaf types) in one
+/// pass.
+#[allow(clippy::too_many_arguments)]
+fn describe_with_stats(
+    value: &Value,
+    head: nu_protocol::Span,
+    engine_state: Option<&EngineState>,
+    stack: &mut Stack,
+    call: &Call,
+    depth: usize,
+    max_depth: usize,
+    stats: &mut SizeStats,
+) -> Result<Value, ShellError> {
+    stats.node_count += 1;
+    stats.max_depth = stats.max_depth.max(depth);
+
+    let size = Value::filesize(estimate_size(value), head);
+
+    if depth >= max_depth {
+        return Ok(Value::record(
+            record!(
+                "type" => Value::string(value.get_type().to_string(), head),
+                "size" => size,
+                "truncated" => Value::bool(true, head),
+            ),
+            head,
+        ));
+    }
+
+    match value {
+        Value::Record { val, .. } => {
+            let mut columns = Record::new();
+            for (col, child) in val.cols.iter().zip(val.vals.iter()) {
+                columns.push(
+                    col.clone(),
+                    describe_with_stats(
+                        child,
+                        head,
+                        engine_state,
+                        stack,
+                        call,
+                        depth + 1,
+                        max_depth,
+                        stats,
+                    )?,
+                );
+            }
+            Ok(Value::record(
+                record!(
+                    "type" => Value::string("record", head),
+                    "size" => size,
+                    "columns" => Value::record(columns, head),
+                ),
+                head,
+            ))
+        }
+        Value::List { vals, .. } => {
+            let values = vals
+                .iter()
+                .map(|v| {
+                    describe_with_stats(
+                        v,
+                        head,
+                        engine_state,
+                        stack,
+                        call,
+                        depth + 1,
+                        max_depth,
+                        stats,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::record(
+                record!(
+                    "type" => Value::string("list", head),
+                    "size" => size,
+                    "length" => Value::int(vals.len() as i64, head),
+                    "values" => Value::list(values, head),
+                ),
+                head,
+            ))
+        }
+        _ => {
+            stats.leaf_types.insert(value.get_type().to_string());
+            let described = describe_value(value.clone(), head, engine_state, stack, call, depth, max_depth)?;
+            match described {
+                Value::Record { mut val, .. } => {
+                    val.push("size", size);
+                    Ok(Value::record(val, head))
+                }
+                other => Ok(Value::record(
+                    record!(
+                        "type" => other,
+                        "size" => size,
+                    ),
+                    head,
+                )),
+            }
+        }
+    }
+}
+
+/// A rough estimate of `value`'s in-memory footprint: the byte length of strings/binaries, a
+/// fixed 8 bytes for numeric/date/duration scalars, and the recursive sum of keys plus children
+/// for records and lists. Not meant to be exact - just enough to gauge whether a structure is
+/// kilobytes or gigabytes.
+fn estimate_size(value: &Value) -> i64 {
+    match value {
+        Value::String { val, .. } => val.len() as i64,
+        Value::Binary { val, .. } => val.len() as i64,
+        Value::Bool { .. } => 1,
+        Value::Int { .. }
+        | Value::Float { .. }
+        | Value::Filesize { .. }
+        | Value::Duration { .. }
+        | Value::Date { .. } => 8,
+        Value::Record { val, .. } => val
+            .cols
+            .iter()
+            .map(|c| c.len() as i64)
+            .chain(val.vals.iter().map(estimate_size))
+            .sum(),
+        Value::List { vals, .. } => vals.iter().map(estimate_size).sum(),
+        Value::CellPath { val, .. } => val.members.len() as i64 * 8,
+        Value::Nothing { .. } => 0,
+        _ => 0,
+    }
+}
+
 fn metadata_to_value(metadata: Option<Box<PipelineMetadata>>, head: nu_protocol::Span) -> Value {
     match metadata {
         Some(metadata) => Value::record(
@@ -625,6 +1325,7 @@ mod test {
             None,
         ));
 
+        let mut stack = nu_protocol::engine::Stack::new();
         let described = super::describe_value(
             Value::LazyRecord {
                 val: Box::new(record),
@@ -632,7 +1333,10 @@ mod test {
             },
             Span::test_data(),
             None,
+            &mut stack,
             &call,
+            0,
+            50,
         )
         .unwrap();
 
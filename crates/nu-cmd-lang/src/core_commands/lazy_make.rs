@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use nu_engine::{eval_block_with_early_return, CallExt};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Closure, Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, LazyRecord, PipelineData, ShellError, Signature, Span, SyntaxShape, Type,
+    Value,
+};
+
+#[derive(Clone)]
+pub struct LazyMake;
+
+impl Command for LazyMake {
+    fn name(&self) -> &str {
+        "lazy make"
+    }
+
+    fn usage(&self) -> &str {
+        "Create a lazy record from a list of column names and a closure that computes each column's value on demand."
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("lazy make")
+            .input_output_types(vec![(Type::Nothing, Type::Record(vec![]))])
+            .required_named(
+                "columns",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "the column names the lazy record exposes",
+                None,
+            )
+            .required_named(
+                "get-value",
+                SyntaxShape::Closure(Some(vec![SyntaxShape::String])),
+                "a closure taking a column name and returning that column's value",
+                None,
+            )
+            .switch(
+                "cache",
+                "memoize each column's first computed value (behind a mutex) so repeated \
+                 access, including `describe --collect-lazyrecords`, doesn't re-run the closure",
+                None,
+            )
+            .category(Category::Core)
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let head = call.head;
+
+        let columns: Vec<String> = call
+            .get_flag(engine_state, stack, "columns")?
+            .ok_or_else(|| ShellError::MissingParameter {
+                param_name: "columns".into(),
+                span: head,
+            })?;
+
+        let closure: Closure = call
+            .get_flag(engine_state, stack, "get-value")?
+            .ok_or_else(|| ShellError::MissingParameter {
+                param_name: "get-value".into(),
+                span: head,
+            })?;
+
+        let record = ClosureLazyRecord {
+            engine_state: engine_state.clone(),
+            stack: stack.clone(),
+            closure,
+            columns,
+            span: head,
+        };
+
+        if call.has_flag("cache") {
+            return Ok(Value::LazyRecord {
+                val: Box::new(MemoizingLazyRecord {
+                    inner: record,
+                    cache: Mutex::new(HashMap::new()),
+                }),
+                internal_span: head,
+            }
+            .into_pipeline_data());
+        }
+
+        Ok(Value::LazyRecord {
+            val: Box::new(record),
+            internal_span: head,
+        }
+        .into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Build a lazy record whose columns are only computed when accessed",
+                example: "lazy make --columns [name size] --get-value {|col| if $col == 'name' { 'report.csv' } else { 1024 } }",
+                result: None,
+            },
+            Example {
+                description: "Cache each column's value so an expensive closure only runs once per column",
+                example: "lazy make --columns [result] --get-value {|col| http get https://example.com } --cache",
+                result: None,
+            },
+        ]
+    }
+
+    fn search_terms(&self) -> Vec<&str> {
+        vec!["lazy", "record", "closure"]
+    }
+}
+
+/// A [`LazyRecord`] whose columns are fixed up front but whose values are computed on each
+/// access by calling `closure` with the column name, so columns the caller never touches (e.g. via
+/// `describe` without `--collect-lazyrecords`) never run at all.
+#[derive(Clone)]
+struct ClosureLazyRecord {
+    engine_state: EngineState,
+    stack: Stack,
+    closure: Closure,
+    columns: Vec<String>,
+    span: Span,
+}
+
+impl<'a> LazyRecord<'a> for ClosureLazyRecord {
+    fn column_names(&'a self) -> Vec<&'a str> {
+        self.columns.iter().map(|s| s.as_str()).collect()
+    }
+
+    fn get_column_value(&self, column: &str) -> Result<Value, ShellError> {
+        if !self.columns.iter().any(|c| c == column) {
+            return Err(ShellError::CantFindColumn {
+                col_name: column.to_string(),
+                span: self.span,
+                src_span: self.span,
+            });
+        }
+
+        let block = self.engine_state.get_block(self.closure.block_id);
+        let mut stack = self.stack.captures_to_stack(&self.closure.captures);
+
+        if let Some(var_id) = block
+            .signature
+            .get_positional(0)
+            .and_then(|positional| positional.var_id)
+        {
+            stack.add_var(var_id, Value::string(column, self.span));
+        }
+
+        let result = eval_block_with_early_return(
+            &self.engine_state,
+            &mut stack,
+            block,
+            PipelineData::empty(),
+            false,
+            false,
+        )?;
+
+        result.into_value(self.span)
+    }
+
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn clone_value(&self, span: Span) -> Value {
+        Value::LazyRecord {
+            val: Box::new(Self {
+                span,
+                ..self.clone()
+            }),
+            internal_span: span,
+        }
+    }
+}
+
+/// Wraps a [`ClosureLazyRecord`] with a `Mutex<HashMap<...>>` cache keyed by
+/// column name: the first `get_column_value` for a column runs `inner`'s closure and stores the
+/// result, every subsequent access for that column returns the stored clone instead of recomputing
+/// it. Opt in via `lazy make --cache` for columns whose closures are pure and expensive.
+struct MemoizingLazyRecord {
+    inner: ClosureLazyRecord,
+    cache: Mutex<HashMap<String, Value>>,
+}
+
+impl Clone for MemoizingLazyRecord {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            cache: Mutex::new(
+                self.cache
+                    .lock()
+                    .expect("lazy record cache mutex poisoned")
+                    .clone(),
+            ),
+        }
+    }
+}
+
+impl<'a> LazyRecord<'a> for MemoizingLazyRecord {
+    fn column_names(&'a self) -> Vec<&'a str> {
+        self.inner.column_names()
+    }
+
+    fn get_column_value(&self, column: &str) -> Result<Value, ShellError> {
+        if let Some(cached) = self
+            .cache
+            .lock()
+            .expect("lazy record cache mutex poisoned")
+            .get(column)
+        {
+            return Ok(cached.clone());
+        }
+
+        let value = self.inner.get_column_value(column)?;
+        self.cache
+            .lock()
+            .expect("lazy record cache mutex poisoned")
+            .insert(column.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn span(&self) -> Span {
+        self.inner.span()
+    }
+
+    fn clone_value(&self, span: Span) -> Value {
+        let mut inner = self.inner.clone();
+        inner.span = span;
+        Value::LazyRecord {
+            val: Box::new(Self {
+                inner,
+                cache: Mutex::new(
+                    self.cache
+                        .lock()
+                        .expect("lazy record cache mutex poisoned")
+                        .clone(),
+                ),
+            }),
+            internal_span: span,
+        }
+    }
+}
+
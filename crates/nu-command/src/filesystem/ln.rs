@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::util::try_interaction;
 use nu_engine::env::current_dir;
@@ -38,7 +38,13 @@ impl Command for Ln {
             .required(
                 "link_name",
                 SyntaxShape::String,
-                "The name of the link to create.",
+                "The name of the link to create, or (when more sources follow) another source.",
+            )
+            .rest(
+                "rest",
+                SyntaxShape::String,
+                "Additional sources; with more than one source the last argument is the \
+                 directory to create the links in.",
             )
             .switch(
                 "verbose",
@@ -65,7 +71,7 @@ impl Command for Ln {
             .named(
                 "target-directory",
                 SyntaxShape::Filepath,
-                "move all source arguments into directory",
+                "create all links inside directory, treating every positional as a source",
                 Some('t'),
             )
             .switch(
@@ -73,6 +79,37 @@ impl Command for Ln {
                 "treat link name as a normal file if it is a symbolic link to a directory",
                 Some('T'),
             )
+            .switch(
+                "backup",
+                "when an existing destination file is replaced (with --force or a confirmed \
+                 --interactive), rename it to a backup instead of deleting it; without either, \
+                 an existing destination is still an error (see --backup-control and the \
+                 VERSION_CONTROL environment variable)",
+                Some('b'),
+            )
+            .named(
+                "backup-control",
+                SyntaxShape::String,
+                "the method used to make backups (none/off, simple/never, numbered/t, \
+                 existing/nil); equivalent to GNU's --backup=CONTROL",
+                None,
+            )
+            .named(
+                "suffix",
+                SyntaxShape::String,
+                "override the usual backup suffix",
+                Some('S'),
+            )
+            .switch(
+                "logical",
+                "hard-link to the file a symbolic link target references, not the link itself",
+                Some('L'),
+            )
+            .switch(
+                "physical",
+                "hard-link to a symbolic link target as it is, never dereferencing it (default)",
+                Some('P'),
+            )
             .category(Category::FileSystem)
     }
 
@@ -83,40 +120,38 @@ impl Command for Ln {
         call: &Call,
         _input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
-        let Some(LnParsedArgs {
-            verbose,
-            directory,
-            symbolic,
-            linkname,
-            target,
-        }) = setup_paths(call, engine_state, stack, try_interaction)?
-        else {
+        let Some(links) = setup_paths(call, engine_state, stack, try_interaction)? else {
             return Ok(PipelineData::empty());
         };
 
-        if symbolic {
-            if directory {
-                #[cfg(unix)]
-                std::os::unix::fs::symlink(&target, &linkname)?;
-                #[cfg(windows)]
-                std::os::windows::fs::symlink_dir(target, linkname)?;
-            } else {
-                #[cfg(unix)]
-                std::os::unix::fs::symlink(&target, &linkname)?;
-                #[cfg(windows)]
-                std::os::windows::fs::symlink_file(target, linkname)?;
+        let total = links.len();
+        let mut errors = Vec::new();
+
+        for link in links {
+            if let Err(err) = create_link(&link) {
+                errors.push(err);
+                continue;
+            }
+
+            if link.verbose {
+                println!(
+                    "'{}' -> '{}'{}",
+                    link.linkname.to_string_lossy(),
+                    link.target.to_string_lossy(),
+                    if link.symbolic { " (symbolic link)" } else { "" }
+                );
             }
-        } else {
-            std::fs::hard_link(&target, &linkname)?;
         }
 
-        if verbose {
-            println!(
-                "'{}' -> '{}'{}",
-                linkname.to_string_lossy(),
-                target.to_string_lossy(),
-                if symbolic { " (symbolic link)" } else { "" }
-            );
+        if !errors.is_empty() {
+            let first = errors[0].to_string();
+            return Err(ShellError::GenericError {
+                error: format!("failed to create {} of {total} link(s)", errors.len()),
+                msg: first,
+                span: None,
+                help: None,
+                inner: errors,
+            });
         }
 
         Ok(PipelineData::empty())
@@ -143,47 +178,312 @@ impl Command for Ln {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Which, if any, backup `make_backup` should make of an existing `linkname` before it's
+/// overwritten - mirrors GNU's `--backup[=CONTROL]`/`VERSION_CONTROL` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BackupMode {
+    /// Overwrite without keeping a copy (the default when `--backup` isn't given).
+    None,
+    /// Always rename to `name<suffix>`.
+    Simple,
+    /// Always rename to `name.~N~`, using the next unused `N`.
+    Numbered,
+    /// Numbered if a `name.~N~` backup already exists, simple otherwise.
+    Existing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct LnParsedArgs {
     verbose: bool,
     directory: bool,
     symbolic: bool,
+    /// Only consulted for hard links: whether `-P` (physical, the default) was requested, in
+    /// which case a symlink target is linked as-is rather than dereferenced.
+    physical: bool,
     linkname: std::path::PathBuf,
     target: std::path::PathBuf,
 }
 
+/// Actually creates the link `plan` describes. Kept separate from `setup_paths` so a failure on
+/// one source (in the multi-source forms) can be reported without losing the links already made.
+fn create_link(plan: &LnParsedArgs) -> Result<(), ShellError> {
+    if plan.symbolic {
+        if plan.directory {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&plan.target, &plan.linkname)?;
+            #[cfg(windows)]
+            std::os::windows::fs::symlink_dir(&plan.target, &plan.linkname)?;
+        } else {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&plan.target, &plan.linkname)?;
+            #[cfg(windows)]
+            std::os::windows::fs::symlink_file(&plan.target, &plan.linkname)?;
+        }
+    } else {
+        hard_link(&plan.target, &plan.linkname, plan.physical)?;
+    }
+    Ok(())
+}
+
+/// Creates a hard link at `linkname` pointing at `target`. `std::fs::hard_link` dereferences a
+/// symlink `target` on every platform we support, which is right for `-L`/`--logical`; `-P`
+/// (the GNU default) instead has to link to the symlink entry itself, which on Unix means
+/// calling `linkat` with `AT_SYMLINK_NOFOLLOW` directly since the standard library has no way to
+/// express that.
+fn hard_link(target: &Path, linkname: &Path, physical: bool) -> Result<(), ShellError> {
+    #[cfg(unix)]
+    {
+        if physical && target.symlink_metadata().map(|m| m.file_type().is_symlink()) == Ok(true) {
+            return unix_link_no_follow(target, linkname);
+        }
+    }
+    let _ = physical;
+    std::fs::hard_link(target, linkname)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn unix_link_no_follow(target: &Path, linkname: &Path) -> Result<(), ShellError> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let target = CString::new(target.as_os_str().as_bytes()).map_err(|_| ShellError::GenericError {
+        error: "path contains an embedded NUL byte".to_string(),
+        msg: "cannot link this target".to_string(),
+        span: None,
+        help: None,
+        inner: vec![],
+    })?;
+    let linkname = CString::new(linkname.as_os_str().as_bytes()).map_err(|_| {
+        ShellError::GenericError {
+            error: "path contains an embedded NUL byte".to_string(),
+            msg: "cannot link to this name".to_string(),
+            span: None,
+            help: None,
+            inner: vec![],
+        }
+    })?;
+
+    // SAFETY: `target`/`linkname` are valid, NUL-terminated C strings for the lifetime of the
+    // call; `AT_FDCWD` makes the relative-path arguments resolve against the process cwd.
+    let result = unsafe {
+        libc::linkat(
+            libc::AT_FDCWD,
+            target.as_ptr(),
+            libc::AT_FDCWD,
+            linkname.as_ptr(),
+            libc::AT_SYMLINK_NOFOLLOW,
+        )
+    };
+    if result != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Where the links planned from the command line should end up: either one explicit link name
+/// (the 1st form, `ln target link_name`) or a directory each source's link is named after (the
+/// 3rd form, `ln target... directory`, and the 4th form, `ln -t directory target...`).
+enum Destination {
+    Explicit(Spanned<String>),
+    Directory(PathBuf),
+}
+
+/// Splits the positional `target`/`link_name`/`rest` arguments into the sources to link and where
+/// their links should go, implementing GNU `ln`'s 1st, 3rd and 4th forms (the 2nd form, a single
+/// TARGET with no link name, isn't supported here since `link_name` stays a required argument).
+fn split_sources_and_destination(
+    mut sources: Vec<Spanned<String>>,
+    target_directory: Option<Spanned<String>>,
+    no_target_directory: bool,
+    cwd: &Path,
+) -> (Vec<Spanned<String>>, Destination) {
+    if let Some(dir) = target_directory {
+        return (sources, Destination::Directory(cwd.join(dir.item)));
+    }
+
+    if sources.len() > 2 {
+        let dir_arg = sources.pop().expect("just checked len > 2");
+        return (sources, Destination::Directory(cwd.join(dir_arg.item)));
+    }
+
+    if sources.len() == 2 && !no_target_directory && cwd.join(&sources[1].item).is_dir() {
+        let dir_arg = sources.pop().expect("just checked len == 2");
+        return (sources, Destination::Directory(cwd.join(dir_arg.item)));
+    }
+
+    let link_name = sources.pop().expect("target/link_name are both required");
+    (sources, Destination::Explicit(link_name))
+}
+
 fn setup_paths(
     call: &Call,
     engine_state: &EngineState,
     stack: &mut Stack,
     // pub fn try_interaction(interactive: bool, prompt: String) -> (Result<Option<bool>, Box<dyn Error>>, bool)
     interact: impl Fn(bool, String) -> (Result<Option<bool>, Box<dyn std::error::Error>>, bool),
-) -> Result<Option<LnParsedArgs>, ShellError> {
+) -> Result<Option<Vec<LnParsedArgs>>, ShellError> {
     let spanned_target: Spanned<String> = call.req(engine_state, stack, 0)?;
     let spanned_linkname: Spanned<String> = call.req(engine_state, stack, 1)?;
+    let rest: Vec<Spanned<String>> = call.rest(engine_state, stack, 2)?;
     let verbose = call.has_flag("verbose");
     let interactive = call.has_flag("interactive");
     let force = call.has_flag("force");
     let directory = call.has_flag("directory");
     let symbolic = call.has_flag("symbolic");
     let relative = call.has_flag("relative");
-    let target_directory: Option<String> =
+    let logical = call.has_flag("logical");
+    let physical_flag = call.has_flag("physical");
+    let target_directory: Option<Spanned<String>> =
         call.get_flag(engine_state, stack, "target-directory")?;
     let no_target_directory = call.has_flag("no-target-directory");
+    let backup = call.has_flag("backup");
+    let backup_control: Option<Spanned<String>> =
+        call.get_flag(engine_state, stack, "backup-control")?;
+    let suffix: Option<String> = call.get_flag(engine_state, stack, "suffix")?;
+    if relative && !symbolic {
+        return Err(ShellError::GenericError {
+            error: "--relative only makes sense with --symbolic".to_string(),
+            msg: "pass -s/--symbolic to create a relative symlink".to_string(),
+            span: Some(spanned_target.span),
+            help: None,
+            inner: vec![],
+        });
+    }
+    if target_directory.is_some() && no_target_directory {
+        return Err(ShellError::GenericError {
+            error: "--target-directory and --no-target-directory are mutually exclusive"
+                .to_string(),
+            msg: "pass only one of -t/-T".to_string(),
+            span: Some(spanned_target.span),
+            help: None,
+            inner: vec![],
+        });
+    }
+    if logical && physical_flag {
+        return Err(ShellError::GenericError {
+            error: "--logical and --physical are mutually exclusive".to_string(),
+            msg: "pass only one of -L/-P".to_string(),
+            span: Some(spanned_target.span),
+            help: None,
+            inner: vec![],
+        });
+    }
+    let physical = !logical;
+    let backup_mode = if backup || backup_control.is_some() {
+        let control = match backup_control {
+            Some(control) => control,
+            None => match env_var(engine_state, stack, "VERSION_CONTROL") {
+                Some(control) => Spanned {
+                    item: control,
+                    span: spanned_linkname.span,
+                },
+                None => Spanned {
+                    item: "existing".to_string(),
+                    span: spanned_linkname.span,
+                },
+            },
+        };
+        parse_backup_mode(&control)?
+    } else {
+        BackupMode::None
+    };
+    let backup_suffix = suffix
+        .or_else(|| env_var(engine_state, stack, "SIMPLE_BACKUP_SUFFIX"))
+        .unwrap_or_else(|| "~".to_string());
+
     let cwd = current_dir(engine_state, stack)?;
-    let mut linkname = cwd.join(spanned_linkname.item.as_str());
-    println!("target : {}", spanned_target.item.as_str());
+
+    let mut sources = vec![spanned_target, spanned_linkname];
+    sources.extend(rest);
+
+    let (sources, destination) =
+        split_sources_and_destination(sources, target_directory, no_target_directory, &cwd);
+
+    if let Destination::Directory(ref dir) = destination {
+        if !dir.is_dir() {
+            return Err(ShellError::DirectoryNotFound {
+                dir: dir.to_string_lossy().to_string(),
+                span: sources[0].span,
+            });
+        }
+    }
+
+    let mut plans = Vec::with_capacity(sources.len());
+    for source in sources {
+        let linkname = match &destination {
+            Destination::Explicit(link_name) => cwd.join(&link_name.item),
+            Destination::Directory(dir) => {
+                let file_name = Path::new(source.item.as_str()).file_name().ok_or_else(|| {
+                    ShellError::GenericError {
+                        error: format!("cannot determine a link name for '{}'", source.item),
+                        msg: "source has no final path component".to_string(),
+                        span: Some(source.span),
+                        help: None,
+                        inner: vec![],
+                    }
+                })?;
+                dir.join(file_name)
+            }
+        };
+        let treat_linkname_literally =
+            no_target_directory || matches!(destination, Destination::Directory(_));
+
+        match plan_single_link(
+            &source,
+            linkname,
+            directory,
+            symbolic,
+            relative,
+            interactive,
+            force,
+            backup_mode,
+            &backup_suffix,
+            treat_linkname_literally,
+            physical,
+            &cwd,
+            &interact,
+        )? {
+            Some(mut plan) => {
+                plan.verbose = verbose;
+                plans.push(plan);
+            }
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(plans))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plan_single_link(
+    spanned_target: &Spanned<String>,
+    mut linkname: PathBuf,
+    directory: bool,
+    symbolic: bool,
+    relative: bool,
+    interactive: bool,
+    force: bool,
+    backup_mode: BackupMode,
+    backup_suffix: &str,
+    no_target_directory: bool,
+    physical: bool,
+    cwd: &Path,
+    interact: &impl Fn(bool, String) -> (Result<Option<bool>, Box<dyn std::error::Error>>, bool),
+) -> Result<Option<LnParsedArgs>, ShellError> {
     let target = PathBuf::from(spanned_target.item.as_str());
     let target = if target.is_relative() {
-        if relative {
-            target
-        } else {
-            cwd.join(target)
-        }
+        cwd.join(target)
+    } else {
+        target
+    };
+    // `-L`/`--logical` dereferences a symlink target before linking to it; `-P` (the default)
+    // leaves it to `hard_link`/`create_link` to decide how to treat the symlink node itself.
+    let target = if !symbolic && !physical {
+        std::fs::canonicalize(&target).unwrap_or(target)
     } else {
         target
     };
-    println!("target 2 : {}", target.display());
     if !no_target_directory && linkname.is_dir() {
         linkname.push(target.file_name().unwrap());
     };
@@ -212,29 +512,158 @@ fn setup_paths(
             }
         } else if !force {
             return Err(ShellError::FileAlreadyExists {
-                span: spanned_linkname.span,
+                span: spanned_target.span,
             });
         }
 
-        // rm the existing file
+        make_backup(&linkname, backup_mode, backup_suffix)?;
         std::fs::remove_file(&linkname)?;
     }
-    if let Some(target_directory) = target_directory {
-        let target_directory = cwd.join(target_directory);
-        if !target_directory.exists() {
-            return Err(ShellError::DirectoryNotFound {
-                dir: target_directory.to_string_lossy().to_string(),
-                span: spanned_target.span,
-            });
-        }
-        linkname = target_directory.join(linkname.file_name().unwrap());
-    }
+
+    let target = if relative {
+        // Canonicalize the link's parent directory (which has to exist - `linkname` is going in
+        // it) and the target's parent directory, rather than the target itself: the target may
+        // be a dangling or about-to-be-overwritten link, so resolving its own final component
+        // could fail or resolve through a symlink we don't want to follow.
+        let link_dir = linkname.parent().unwrap_or(cwd);
+        let link_dir = std::fs::canonicalize(link_dir).unwrap_or_else(|_| link_dir.to_path_buf());
+        let canonical_target = match (target.parent(), target.file_name()) {
+            (Some(parent), Some(file_name)) => std::fs::canonicalize(parent)
+                .map(|p| p.join(file_name))
+                .unwrap_or_else(|_| target.clone()),
+            _ => target.clone(),
+        };
+        relative_target(&link_dir, &canonical_target)
+    } else {
+        target
+    };
 
     Ok(Some(LnParsedArgs {
-        verbose,
+        verbose: false,
         directory,
         symbolic,
+        physical,
         linkname,
         target,
     }))
 }
+
+/// Computes the relative path from `base` (the directory the link itself will live in) to
+/// `target`, for `--relative` symlinks - e.g. a target next to the link becomes just its file
+/// name, and a target under a sibling directory becomes `../sibling/target`. Both paths are
+/// expected to already be absolute (and, where possible, canonicalized by the caller).
+fn relative_target(base: &Path, target: &Path) -> PathBuf {
+    let mut base_components = base.components().peekable();
+    let mut target_components = target.components().peekable();
+
+    // Drop whatever prefix the two paths have in common - there's no need to climb out of a
+    // shared ancestor and back in.
+    while let (Some(b), Some(t)) = (base_components.peek(), target_components.peek()) {
+        if b == t {
+            base_components.next();
+            target_components.next();
+        } else {
+            break;
+        }
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in base_components {
+        relative.push("..");
+    }
+    for component in target_components {
+        relative.push(component.as_os_str());
+    }
+
+    if relative.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        relative
+    }
+}
+
+/// Reads an environment variable through `stack`/`engine_state`, coercing it to a plain string.
+/// Used for the `VERSION_CONTROL`/`SIMPLE_BACKUP_SUFFIX` fallbacks `--backup-control`/`--suffix`
+/// take over from when they're not passed explicitly.
+fn env_var(engine_state: &EngineState, stack: &mut Stack, name: &str) -> Option<String> {
+    stack
+        .get_env_var(engine_state, name)
+        .and_then(|value| value.as_string().ok())
+}
+
+/// Parses a `--backup-control`/`VERSION_CONTROL` value into a [`BackupMode`], accepting the same
+/// aliases GNU does (`never` for simple, `t`/`nil` for numbered/existing, ...).
+fn parse_backup_mode(control: &Spanned<String>) -> Result<BackupMode, ShellError> {
+    match control.item.to_ascii_lowercase().as_str() {
+        "none" | "off" => Ok(BackupMode::None),
+        "simple" | "never" => Ok(BackupMode::Simple),
+        "numbered" | "t" => Ok(BackupMode::Numbered),
+        "existing" | "nil" => Ok(BackupMode::Existing),
+        _ => Err(ShellError::IncorrectValue {
+            msg: format!(
+                "`--backup-control`/`VERSION_CONTROL` must be one of none, off, simple, never, \
+                 numbered, t, existing, nil; got `{}`",
+                control.item
+            ),
+            val_span: control.span,
+            call_span: control.span,
+        }),
+    }
+}
+
+/// Renames `path` - an existing file `setup_paths` is about to overwrite - to a backup name
+/// chosen by `mode`, so the caller can safely remove the original afterwards. A no-op when `mode`
+/// is [`BackupMode::None`].
+fn make_backup(path: &Path, mode: BackupMode, suffix: &str) -> Result<(), ShellError> {
+    let backup_path = match mode {
+        BackupMode::None => return Ok(()),
+        BackupMode::Simple => simple_backup_path(path, suffix),
+        BackupMode::Numbered => numbered_backup_path(path),
+        BackupMode::Existing => {
+            if existing_numbered_backups(path).is_empty() {
+                simple_backup_path(path, suffix)
+            } else {
+                numbered_backup_path(path)
+            }
+        }
+    };
+    std::fs::rename(path, backup_path)?;
+    Ok(())
+}
+
+fn simple_backup_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn numbered_backup_path(path: &Path) -> PathBuf {
+    let next = existing_numbered_backups(path).into_iter().max().unwrap_or(0) + 1;
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".~{next}~"));
+    PathBuf::from(name)
+}
+
+/// The `N`s of every `path.~N~` backup that already exists next to `path`.
+fn existing_numbered_backups(path: &Path) -> Vec<u64> {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Vec::new();
+    };
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let prefix = format!("{file_name}.~");
+
+    let Ok(entries) = std::fs::read_dir(parent) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            name.to_str()?
+                .strip_prefix(&prefix)?
+                .strip_suffix('~')?
+                .parse::<u64>()
+                .ok()
+        })
+        .collect()
+}
@@ -0,0 +1,406 @@
+use nu_engine::command_prelude::*;
+
+#[derive(Clone)]
+pub struct FromDot;
+
+impl Command for FromDot {
+    fn name(&self) -> &str {
+        "from dot"
+    }
+
+    fn description(&self) -> &str {
+        "Parse a Graphviz DOT string into structured graph data."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("from dot")
+            .input_output_types(vec![(
+                Type::String,
+                Type::Record(
+                    [
+                        ("kind".to_string(), Type::String),
+                        ("nodes".to_string(), Type::List(Box::new(Type::Any))),
+                        ("edges".to_string(), Type::List(Box::new(Type::Any))),
+                    ]
+                    .into(),
+                ),
+            )])
+            .category(Category::Formats)
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![Example {
+            example: r#"'digraph { a -> b [label="x"] }' | from dot"#,
+            description: "Parses a DOT digraph into a {kind, nodes, edges} record",
+            result: Some(Value::test_record(record! {
+                "kind" => Value::test_string("directed"),
+                "nodes" => Value::test_list(vec![
+                    Value::test_record(record! { "name" => Value::test_string("a") }),
+                    Value::test_record(record! { "name" => Value::test_string("b") }),
+                ]),
+                "edges" => Value::test_list(vec![
+                    Value::test_record(record! {
+                        "from" => Value::test_string("a"),
+                        "to" => Value::test_string("b"),
+                        "label" => Value::test_string("x"),
+                    }),
+                ]),
+            })),
+        }]
+    }
+
+    fn run(
+        &self,
+        _engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let (string_input, span, ..) = input.collect_string_strict(span)?;
+
+        if string_input.is_empty() {
+            return Ok(Value::nothing(span).into_pipeline_data());
+        }
+
+        parse_dot(&string_input, span).map(|value| value.into_pipeline_data())
+    }
+}
+
+fn parse_dot(input: &str, span: Span) -> Result<Value, ShellError> {
+    let tokens = tokenize(input, span)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        span,
+    };
+    parser.parse_graph()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    /// A quoted string; kept separate from [`Token::Ident`] so a quoted `"strict"`, `"graph"` or
+    /// `"digraph"` is never taken for the graph header keyword.
+    QuotedIdent(String),
+    Symbol(char),
+    Arrow,
+    Edge,
+}
+
+fn tokenize(input: &str, span: Span) -> Result<Vec<Token>, ShellError> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '/' => {
+                chars.next();
+                match chars.peek() {
+                    Some('/') => {
+                        for c in chars.by_ref() {
+                            if c == '\n' {
+                                break;
+                            }
+                        }
+                    }
+                    Some('*') => {
+                        chars.next();
+                        let mut prev = ' ';
+                        for c in chars.by_ref() {
+                            if prev == '*' && c == '/' {
+                                break;
+                            }
+                            prev = c;
+                        }
+                    }
+                    _ => {
+                        return Err(ShellError::CantConvert {
+                            to_type: "structured dot data".into(),
+                            from_type: "string".into(),
+                            span,
+                            help: Some("unexpected `/` outside of a comment".into()),
+                        })
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    value.push(c);
+                }
+                tokens.push(Token::QuotedIdent(value));
+            }
+            '{' | '}' | '[' | ']' | ',' | ';' | '=' => {
+                chars.next();
+                tokens.push(Token::Symbol(c));
+            }
+            '-' => {
+                chars.next();
+                match chars.next() {
+                    Some('>') => tokens.push(Token::Arrow),
+                    Some('-') => tokens.push(Token::Edge),
+                    Some(d) if d.is_ascii_digit() || d == '.' => {
+                        // A negative numeral, e.g. the attribute value in `weight=-1.5`.
+                        let mut value = format!("-{d}");
+                        while let Some(&c) = chars.peek() {
+                            if !(c.is_ascii_digit() || c == '.') {
+                                break;
+                            }
+                            value.push(c);
+                            chars.next();
+                        }
+                        tokens.push(Token::Ident(value));
+                    }
+                    _ => {
+                        return Err(ShellError::CantConvert {
+                            to_type: "structured dot data".into(),
+                            from_type: "string".into(),
+                            span,
+                            help: Some("expected `->` or `--`".into()),
+                        })
+                    }
+                }
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "{}[],;=\"".contains(c) {
+                        break;
+                    }
+                    if c == '-' {
+                        // Only break on `-` if it starts an edge operator (`->` or `--`);
+                        // any other `-` stays part of the identifier.
+                        let mut lookahead = chars.clone();
+                        lookahead.next();
+                        if matches!(lookahead.peek(), Some('>') | Some('-')) {
+                            break;
+                        }
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(value));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    span: Span,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        let tok = self.tokens.get(self.pos);
+        self.pos += 1;
+        tok
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), ShellError> {
+        match self.next() {
+            Some(Token::Symbol(c)) if *c == symbol => Ok(()),
+            other => Err(self.unexpected(format!("`{symbol}`"), other.cloned())),
+        }
+    }
+
+    fn unexpected(&self, expected: String, found: Option<Token>) -> ShellError {
+        ShellError::CantConvert {
+            to_type: "structured dot data".into(),
+            from_type: "string".into(),
+            span: self.span,
+            help: Some(format!("expected {expected}, found {found:?}")),
+        }
+    }
+
+    fn ident_value(tok: &Token) -> Option<String> {
+        match tok {
+            Token::Ident(s) | Token::QuotedIdent(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn parse_graph(&mut self) -> Result<Value, ShellError> {
+        if matches!(self.peek(), Some(Token::Ident(s)) if s.eq_ignore_ascii_case("strict")) {
+            self.next();
+        }
+
+        let kind = match self.next() {
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("digraph") => "directed",
+            Some(Token::Ident(s)) if s.eq_ignore_ascii_case("graph") => "undirected",
+            other => return Err(self.unexpected("`graph` or `digraph`".into(), other.cloned())),
+        };
+
+        // Optional graph id.
+        if matches!(self.peek(), Some(t) if Self::ident_value(t).is_some()) {
+            self.next();
+        }
+
+        self.expect_symbol('{')?;
+
+        let mut nodes: indexmap::IndexMap<String, indexmap::IndexMap<String, Value>> =
+            indexmap::IndexMap::new();
+        let mut edges = vec![];
+
+        loop {
+            match self.peek() {
+                Some(Token::Symbol('}')) => {
+                    self.next();
+                    break;
+                }
+                Some(Token::Symbol(';')) => {
+                    self.next();
+                }
+                Some(t) if Self::ident_value(t).is_some() => {
+                    self.parse_statement(&mut nodes, &mut edges)?;
+                }
+                other => return Err(self.unexpected("a statement or `}`".into(), other.cloned())),
+            }
+        }
+
+        let nodes: Vec<Value> = nodes
+            .into_iter()
+            .map(|(name, attrs)| {
+                let mut record = indexmap::IndexMap::new();
+                record.insert("name".to_string(), Value::string(name, self.span));
+                record.extend(attrs);
+                Value::record(record.into_iter().collect(), self.span)
+            })
+            .collect();
+
+        Ok(Value::record(
+            record! {
+                "kind" => Value::string(kind, self.span),
+                "nodes" => Value::list(nodes, self.span),
+                "edges" => Value::list(edges, self.span),
+            },
+            self.span,
+        ))
+    }
+
+    fn parse_statement(
+        &mut self,
+        nodes: &mut indexmap::IndexMap<String, indexmap::IndexMap<String, Value>>,
+        edges: &mut Vec<Value>,
+    ) -> Result<(), ShellError> {
+        let first = Self::ident_value(self.next().expect("checked by caller")).expect("checked");
+
+        // `node [...]`, `edge [...]`, and `graph [...]` set defaults for everything that
+        // follows; we don't track defaults, so parse and discard their attribute list.
+        if matches!(first.as_str(), "node" | "edge" | "graph")
+            && matches!(self.peek(), Some(Token::Symbol('[')))
+        {
+            self.parse_attr_list()?;
+            return Ok(());
+        }
+
+        if matches!(self.peek(), Some(Token::Arrow) | Some(Token::Edge)) {
+            let mut chain = vec![first];
+            while matches!(self.peek(), Some(Token::Arrow) | Some(Token::Edge)) {
+                self.next();
+                let next_id = Self::ident_value(self.next().ok_or_else(|| {
+                    self.unexpected("an identifier after an edge operator".into(), None)
+                })?)
+                .ok_or_else(|| self.unexpected("an identifier".into(), None))?;
+                chain.push(next_id);
+            }
+
+            let attrs = if matches!(self.peek(), Some(Token::Symbol('['))) {
+                self.parse_attr_list()?
+            } else {
+                indexmap::IndexMap::new()
+            };
+
+            for pair in chain.windows(2) {
+                nodes.entry(pair[0].clone()).or_default();
+                nodes.entry(pair[1].clone()).or_default();
+
+                let mut record = indexmap::IndexMap::new();
+                record.insert(
+                    "from".to_string(),
+                    Value::string(pair[0].clone(), self.span),
+                );
+                record.insert("to".to_string(), Value::string(pair[1].clone(), self.span));
+                record.extend(attrs.clone());
+                edges.push(Value::record(record.into_iter().collect(), self.span));
+            }
+        } else {
+            let attrs = if matches!(self.peek(), Some(Token::Symbol('['))) {
+                self.parse_attr_list()?
+            } else {
+                indexmap::IndexMap::new()
+            };
+            let entry = nodes.entry(first).or_default();
+            entry.extend(attrs);
+        }
+
+        if matches!(self.peek(), Some(Token::Symbol(';'))) {
+            self.next();
+        }
+
+        Ok(())
+    }
+
+    fn parse_attr_list(&mut self) -> Result<indexmap::IndexMap<String, Value>, ShellError> {
+        let mut attrs = indexmap::IndexMap::new();
+
+        // DOT allows multiple bracketed attribute groups back to back: `[a=1][b=2]`.
+        while matches!(self.peek(), Some(Token::Symbol('['))) {
+            self.next();
+            loop {
+                match self.peek() {
+                    Some(Token::Symbol(']')) => {
+                        self.next();
+                        break;
+                    }
+                    Some(Token::Symbol(',')) | Some(Token::Symbol(';')) => {
+                        self.next();
+                    }
+                    Some(t) if Self::ident_value(t).is_some() => {
+                        let key =
+                            Self::ident_value(self.next().expect("checked")).expect("checked");
+                        self.expect_symbol('=')?;
+                        let value =
+                            Self::ident_value(self.next().ok_or_else(|| {
+                                self.unexpected("an attribute value".into(), None)
+                            })?)
+                            .ok_or_else(|| self.unexpected("an attribute value".into(), None))?;
+                        attrs.insert(key, Value::string(value, self.span));
+                    }
+                    other => {
+                        return Err(self.unexpected("an attribute or `]`".into(), other.cloned()))
+                    }
+                }
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(FromDot {})
+    }
+}
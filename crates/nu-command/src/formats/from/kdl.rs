@@ -1,6 +1,16 @@
-use kdl::{KdlDocument, KdlNode, KdlValue};
+use base64::{engine::general_purpose, Engine};
+use chrono::DateTime;
+use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
 use nu_engine::command_prelude::*;
 
+/// Reserved key under which node/argument ordering and KDL type annotations are recorded
+/// when `--preserve` is used, so that `to kdl --preserve` can replay them exactly.
+pub(crate) const PRESERVE_META_KEY: &str = "_kdl_meta";
+
+/// Inverts the `to kdl` conventions: top-level nodes become record fields, a node's properties
+/// become a nested record, and `set_children` subdocuments become a `children` record. A node
+/// with nothing but arguments becomes its single argument or a list of them; otherwise its bare
+/// arguments go in an `_args` list.
 #[derive(Clone)]
 pub struct FromKdl;
 
@@ -16,6 +26,11 @@ impl Command for FromKdl {
     fn signature(&self) -> nu_protocol::Signature {
         Signature::build("from kdl")
             .input_output_types(vec![(Type::String, Type::Any)])
+            .switch(
+                "preserve",
+                "record entry ordering and type annotations so `to kdl --preserve` can round-trip losslessly",
+                Some('p'),
+            )
             .category(Category::Formats)
     }
 
@@ -50,30 +65,41 @@ impl Command for FromKdl {
                     }),
                 })),
             },
+            Example {
+                example: r#"'node (u8)1 key="value"' | from kdl --preserve | to kdl --preserve"#,
+                description: "Round-trips a kdl document losslessly, keeping type annotations and entry order",
+                result: None,
+            },
         ]
     }
 
     fn run(
         &self,
-        _engine_state: &EngineState,
-        _stack: &mut Stack,
+        engine_state: &EngineState,
+        stack: &mut Stack,
         call: &Call,
         input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
         let span = call.head;
+        let preserve = call.has_flag(engine_state, stack, "preserve")?;
         let (string_input, span, ..) = input.collect_string_strict(span)?;
 
         if string_input.is_empty() {
             return Ok(Value::nothing(span).into_pipeline_data());
         }
 
-        convert_string_to_value(&string_input, span).map(|value| value.into_pipeline_data())
+        convert_string_to_value(&string_input, span, preserve)
+            .map(|value| value.into_pipeline_data())
     }
 }
 
-fn convert_string_to_value(string_input: &str, span: Span) -> Result<Value, ShellError> {
+fn convert_string_to_value(
+    string_input: &str,
+    span: Span,
+    preserve: bool,
+) -> Result<Value, ShellError> {
     match string_input.parse::<KdlDocument>() {
-        Ok(document) => Ok(convert_kdl_document_to_value(document, span)),
+        Ok(document) => Ok(convert_kdl_document_to_value(document, span, preserve)),
         Err(err) => Err(ShellError::CantConvert {
             to_type: format!("structured kdl data ({err})"),
             from_type: "string".into(),
@@ -83,12 +109,14 @@ fn convert_string_to_value(string_input: &str, span: Span) -> Result<Value, Shel
     }
 }
 
-fn convert_kdl_document_to_value(document: KdlDocument, span: Span) -> Value {
+fn convert_kdl_document_to_value(document: KdlDocument, span: Span, preserve: bool) -> Value {
     let mut record = indexmap::IndexMap::new();
+    let mut node_order = vec![];
 
     for node in document.nodes() {
         let node_name = node.name().value();
-        let node_value = convert_kdl_node_to_value(node, span);
+        let node_value = convert_kdl_node_to_value(node, span, preserve);
+        node_order.push(node_name.to_string());
 
         // Handle multiple nodes with the same name by creating a list
         match record.get_mut(node_name) {
@@ -107,10 +135,39 @@ fn convert_kdl_document_to_value(document: KdlDocument, span: Span) -> Value {
         }
     }
 
+    if preserve {
+        record.insert(
+            PRESERVE_META_KEY.to_string(),
+            Value::record(
+                record! {
+                    "node_order" => Value::list(
+                        node_order.into_iter().map(|n| Value::string(n, span)).collect(),
+                        span,
+                    ),
+                },
+                span,
+            ),
+        );
+    }
+
     Value::record(record.into_iter().collect(), span)
 }
 
-fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
+/// Describes one KDL entry (positional argument or property) so it can be replayed in its
+/// original position with its original type annotation by `to kdl --preserve`.
+fn entry_meta(entry: &KdlEntry, index: usize) -> Value {
+    let span = Span::unknown();
+    Value::record(
+        record! {
+            "index" => Value::int(index as i64, span),
+            "name" => entry.name().map_or(Value::nothing(span), |n| Value::string(n.value(), span)),
+            "ty" => entry.ty().map_or(Value::nothing(span), |t| Value::string(t.value(), span)),
+        },
+        span,
+    )
+}
+
+fn convert_kdl_node_to_value(node: &KdlNode, span: Span, preserve: bool) -> Value {
     let has_properties = node.entries().iter().any(|entry| entry.name().is_some());
     let has_children = node.children().is_some();
     let has_arguments = node.entries().iter().any(|entry| entry.name().is_none());
@@ -122,7 +179,7 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
                 .entries()
                 .iter()
                 .filter(|entry| entry.name().is_none())
-                .map(|entry| convert_kdl_value_to_value(entry.value(), span))
+                .map(|entry| convert_kdl_entry_to_value(entry, span))
                 .collect();
 
             if args.len() == 1 {
@@ -144,7 +201,7 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
             if let Some(name) = entry.name() {
                 record.insert(
                     name.value().to_string(),
-                    convert_kdl_value_to_value(entry.value(), span),
+                    convert_kdl_entry_to_value(entry, span),
                 );
             }
         }
@@ -154,13 +211,17 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
             .entries()
             .iter()
             .filter(|entry| entry.name().is_none())
-            .map(|entry| convert_kdl_value_to_value(entry.value(), span))
+            .map(|entry| convert_kdl_entry_to_value(entry, span))
             .collect();
 
         if !args.is_empty() {
             record.insert("_args".to_string(), Value::list(args, span));
         }
 
+        if preserve {
+            insert_entries_meta(&mut record, node, span);
+        }
+
         Value::record(record.into_iter().collect(), span)
     } else if !has_properties && has_children {
         // Children only
@@ -170,7 +231,7 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
         let args: Vec<Value> = node
             .entries()
             .iter()
-            .map(|entry| convert_kdl_value_to_value(entry.value(), span))
+            .map(|entry| convert_kdl_entry_to_value(entry, span))
             .collect();
 
         if !args.is_empty() {
@@ -180,10 +241,14 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
         if let Some(children) = node.children() {
             record.insert(
                 "children".to_string(),
-                convert_kdl_document_to_value(children.clone(), span),
+                convert_kdl_document_to_value(children.clone(), span, preserve),
             );
         }
 
+        if preserve {
+            insert_entries_meta(&mut record, node, span);
+        }
+
         Value::record(record.into_iter().collect(), span)
     } else {
         // Both properties and children
@@ -194,7 +259,7 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
             if let Some(name) = entry.name() {
                 record.insert(
                     name.value().to_string(),
-                    convert_kdl_value_to_value(entry.value(), span),
+                    convert_kdl_entry_to_value(entry, span),
                 );
             }
         }
@@ -204,7 +269,7 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
             .entries()
             .iter()
             .filter(|entry| entry.name().is_none())
-            .map(|entry| convert_kdl_value_to_value(entry.value(), span))
+            .map(|entry| convert_kdl_entry_to_value(entry, span))
             .collect();
 
         if !args.is_empty() {
@@ -214,14 +279,83 @@ fn convert_kdl_node_to_value(node: &KdlNode, span: Span) -> Value {
         if let Some(children) = node.children() {
             record.insert(
                 "children".to_string(),
-                convert_kdl_document_to_value(children.clone(), span),
+                convert_kdl_document_to_value(children.clone(), span, preserve),
             );
         }
 
+        if preserve {
+            insert_entries_meta(&mut record, node, span);
+        }
+
         Value::record(record.into_iter().collect(), span)
     }
 }
 
+/// Records each entry's original position, property name (`None` for positional arguments)
+/// and KDL type annotation (e.g. the `u8` in `(u8)123`) so `to kdl --preserve` can replay the
+/// node exactly instead of collapsing it back through the lossy property/argument heuristics.
+/// A node with children gets the metadata even without entries, so that its children are
+/// replayed in their recorded order too.
+fn insert_entries_meta(record: &mut indexmap::IndexMap<String, Value>, node: &KdlNode, span: Span) {
+    let entries: Vec<Value> = node
+        .entries()
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| entry_meta(entry, index))
+        .collect();
+
+    if !entries.is_empty() || node.children().is_some() {
+        record.insert(
+            PRESERVE_META_KEY.to_string(),
+            Value::record(
+                record! {
+                    "entries" => Value::list(entries, span),
+                },
+                span,
+            ),
+        );
+    }
+}
+
+/// Converts an entry's value, first checking its KDL type annotation (e.g. the `base64` in
+/// `(base64)"..."`) against [`convert_typed_kdl_value`] so the `(base64)`/`(date)`/`(duration)`/
+/// `(filesize)` tags `to kdl` emits reconstruct the original `Value` variant instead of a bare
+/// string or integer. Falls back to the untyped conversion for unrecognized or absent tags.
+fn convert_kdl_entry_to_value(entry: &KdlEntry, span: Span) -> Value {
+    if let Some(ty) = entry.ty() {
+        if let Some(value) = convert_typed_kdl_value(ty.value(), entry.value(), span) {
+            return value;
+        }
+    }
+    convert_kdl_value_to_value(entry.value(), span)
+}
+
+/// Reconstructs the `Value` variant that `to kdl` tagged with `ty`, for the type-annotated
+/// scalars it emits to stay lossless. Returns `None` for any other tag (including user-written
+/// annotations it doesn't recognize, or a shape the tag doesn't actually match), so the caller
+/// can fall back to the untyped conversion.
+fn convert_typed_kdl_value(ty: &str, value: &KdlValue, span: Span) -> Option<Value> {
+    match (ty, value) {
+        ("base64", KdlValue::String(s)) => general_purpose::STANDARD
+            .decode(s)
+            .ok()
+            .map(|bytes| Value::binary(bytes, span)),
+        // `to kdl` writes dates in their display form (`2019-05-10 00:00:00 +00:00`), which
+        // isn't RFC 3339, so accept that form as well.
+        ("date", KdlValue::String(s)) => DateTime::parse_from_rfc3339(s)
+            .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f %:z"))
+            .ok()
+            .map(|dt| Value::date(dt, span)),
+        ("duration", KdlValue::Integer(n)) => {
+            i64::try_from(*n).ok().map(|n| Value::duration(n, span))
+        }
+        ("filesize", KdlValue::Integer(n)) => {
+            i64::try_from(*n).ok().map(|n| Value::filesize(n, span))
+        }
+        _ => None,
+    }
+}
+
 fn convert_kdl_value_to_value(value: &KdlValue, span: Span) -> Value {
     match value {
         KdlValue::String(s) => Value::string(s, span),
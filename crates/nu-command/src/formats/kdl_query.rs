@@ -0,0 +1,308 @@
+use kdl::{KdlDocument, KdlNode};
+use nu_engine::command_prelude::*;
+
+use super::to::kdl::value_to_kdl_document;
+
+#[derive(Clone)]
+pub struct KdlQuery;
+
+impl Command for KdlQuery {
+    fn name(&self) -> &str {
+        "kdl query"
+    }
+
+    fn description(&self) -> &str {
+        "Select nodes out of a kdl document using a compact path-selector expression."
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("kdl query")
+            .input_output_types(vec![(Type::Any, Type::List(Box::new(Type::Any)))])
+            .required(
+                "selector",
+                SyntaxShape::String,
+                "The selector expression, e.g. `package//dependencies *[version]`.",
+            )
+            .category(Category::Formats)
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                example: r#"'package {
+    dependencies {
+        kdl version="4.0.0"
+        serde version="1.0.0"
+    }
+}' | kdl query 'package dependencies *[version]'"#,
+                description: "Select every dependency node carrying a `version` property",
+                result: None,
+            },
+            Example {
+                example: r#"open Cargo.kdl | kdl query 'package//dependencies *[version~"^1"]'"#,
+                description:
+                    "Select dependency nodes anywhere under `package` pinned to a 1.x version",
+                result: None,
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        _engine_state: &EngineState,
+        _stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let selector: Spanned<String> = call.req(0)?;
+        let steps = Selector::parse(&selector.item).map_err(|msg| ShellError::IncorrectValue {
+            msg,
+            val_span: selector.span,
+            call_span: span,
+        })?;
+
+        let document = input_to_kdl_document(_engine_state, input, span)?;
+
+        // Start from a synthetic root whose children are the top-level nodes, so the first
+        // step (e.g. `package`) matches a top-level node by name.
+        let mut root = KdlNode::new("root");
+        root.set_children(document);
+        let mut current: Vec<&KdlNode> = vec![&root];
+        for step in &steps {
+            current = step.apply(&current);
+        }
+
+        let results = current
+            .into_iter()
+            .map(|node| node_to_value(node, span))
+            .collect();
+
+        Ok(Value::list(results, span).into_pipeline_data())
+    }
+}
+
+/// Accepts either a kdl string (parsed fresh) or the record shape produced by `from kdl`
+/// (re-serialized through `to kdl` so the query always walks a real `KdlDocument`).
+fn input_to_kdl_document(
+    engine_state: &EngineState,
+    input: PipelineData,
+    span: Span,
+) -> Result<KdlDocument, ShellError> {
+    let value = input.into_value(span)?;
+    if let Value::String { val, .. } = &value {
+        return val
+            .parse::<KdlDocument>()
+            .map_err(|err| ShellError::CantConvert {
+                to_type: format!("structured kdl data ({err})"),
+                from_type: "string".into(),
+                span,
+                help: None,
+            });
+    }
+    value_to_kdl_document(engine_state, &value, span, false)
+}
+
+fn node_to_value(node: &KdlNode, span: Span) -> Value {
+    Value::string(node.to_string().trim().to_string(), span)
+}
+
+/// One step of a compiled selector: which children to descend into, and the predicates
+/// (if any) that must hold for a candidate node to survive the step.
+#[derive(Debug, Clone)]
+enum Step {
+    /// A literal child name, e.g. `name`.
+    Child(String, Vec<Predicate>),
+    /// `*`: any direct child.
+    AnyChild(Vec<Predicate>),
+    /// `//name`: any descendant named `name`.
+    Descendant(String, Vec<Predicate>),
+    /// `[index]` applied on its own, without a name.
+    Index(usize),
+}
+
+/// A predicate tests a candidate node's arguments/properties.
+#[derive(Debug, Clone)]
+enum Predicate {
+    /// `key="value"`: the node has a property `key` equal to `value`.
+    Eq(String, String),
+    /// `key ~ "regex"`: the node's property `key` has a value matching the regex.
+    Matches(String, String),
+    /// `key`: the node merely carries a property named `key`.
+    HasKey(String),
+    /// The union (`|`) of two predicates - true if either matches.
+    Or(Box<Predicate>, Box<Predicate>),
+    /// The intersection (`&`) of two predicates - true if both match.
+    And(Box<Predicate>, Box<Predicate>),
+}
+
+impl Predicate {
+    fn matches(&self, node: &KdlNode) -> bool {
+        match self {
+            Predicate::Eq(key, value) => node
+                .entries()
+                .iter()
+                .find(|e| e.name().map(|n| n.value()) == Some(key.as_str()))
+                .map(|e| e.value().to_string().trim_matches('"') == value)
+                .unwrap_or(false),
+            Predicate::Matches(key, pattern) => {
+                let Ok(re) = regex::Regex::new(pattern) else {
+                    return false;
+                };
+                node.entries().iter().any(|e| {
+                    let matches_name = e.name().map(|n| n.value()) == Some(key.as_str());
+                    matches_name && re.is_match(e.value().to_string().trim_matches('"'))
+                })
+            }
+            Predicate::HasKey(key) => node
+                .entries()
+                .iter()
+                .any(|e| e.name().map(|n| n.value()) == Some(key.as_str())),
+            Predicate::Or(a, b) => a.matches(node) || b.matches(node),
+            Predicate::And(a, b) => a.matches(node) && b.matches(node),
+        }
+    }
+}
+
+impl Step {
+    fn apply<'a>(&self, candidates: &[&'a KdlNode]) -> Vec<&'a KdlNode> {
+        match self {
+            Step::Child(name, preds) => candidates
+                .iter()
+                .flat_map(|n| n.children().map(|c| c.nodes().iter()).into_iter().flatten())
+                .filter(|n| n.name().value() == name)
+                .filter(|n| preds.iter().all(|p| p.matches(n)))
+                .collect(),
+            Step::AnyChild(preds) => candidates
+                .iter()
+                .flat_map(|n| n.children().map(|c| c.nodes().iter()).into_iter().flatten())
+                .filter(|n| preds.iter().all(|p| p.matches(n)))
+                .collect(),
+            Step::Descendant(name, preds) => candidates
+                .iter()
+                .flat_map(|n| descendants(n))
+                .filter(|n| n.name().value() == name)
+                .filter(|n| preds.iter().all(|p| p.matches(n)))
+                .collect(),
+            Step::Index(index) => candidates.get(*index).copied().into_iter().collect(),
+        }
+    }
+}
+
+fn descendants(node: &KdlNode) -> Vec<&KdlNode> {
+    let mut out = vec![];
+    if let Some(children) = node.children() {
+        for child in children.nodes() {
+            out.push(child);
+            out.extend(descendants(child));
+        }
+    }
+    out
+}
+
+struct Selector;
+
+impl Selector {
+    /// Compiles a selector expression into a list of [`Step`]s.
+    ///
+    /// Grammar (informal): a selector is whitespace-separated segments, each one of
+    /// `name`, `*`, `//name`, optionally suffixed by one or more `[predicate]` brackets;
+    /// `a//b` is shorthand for the two segments `a` and `//b`.
+    /// Predicates combine with `|` (union) and `&` (intersection).
+    fn parse(input: &str) -> Result<Vec<Step>, String> {
+        let mut steps = vec![];
+        for word in input.split_whitespace() {
+            let mut rest = word;
+            loop {
+                let head_end = rest.find('[').unwrap_or(rest.len());
+                match rest[..head_end].get(1..).and_then(|h| h.find("//")) {
+                    Some(idx) => {
+                        steps.push(Self::parse_segment(&rest[..idx + 1])?);
+                        rest = &rest[idx + 1..];
+                    }
+                    None => break,
+                }
+            }
+            steps.push(Self::parse_segment(rest)?);
+        }
+        Ok(steps)
+    }
+
+    fn parse_segment(segment: &str) -> Result<Step, String> {
+        let (head, preds_str) = match segment.find('[') {
+            Some(idx) => {
+                let Some(end) = segment.rfind(']') else {
+                    return Err(format!("unterminated predicate in `{segment}`"));
+                };
+                (&segment[..idx], Some(&segment[idx + 1..end]))
+            }
+            None => (segment, None),
+        };
+
+        let preds = preds_str
+            .map(Self::parse_predicates)
+            .unwrap_or(Ok(vec![]))?;
+
+        if head.is_empty() {
+            if let Some(preds_str) = preds_str {
+                if let Ok(index) = preds_str.trim().parse::<usize>() {
+                    return Ok(Step::Index(index));
+                }
+            }
+            return Err(format!("empty selector segment in `{segment}`"));
+        }
+
+        if head == "*" {
+            Ok(Step::AnyChild(preds))
+        } else if let Some(name) = head.strip_prefix("//") {
+            Ok(Step::Descendant(name.to_string(), preds))
+        } else {
+            Ok(Step::Child(head.to_string(), preds))
+        }
+    }
+
+    fn parse_predicates(input: &str) -> Result<Vec<Predicate>, String> {
+        // Union binds loosest, so split on `|` first, then `&`.
+        let union = input
+            .split('|')
+            .map(|clause| {
+                clause
+                    .split('&')
+                    .map(Self::parse_predicate)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(|preds| {
+                        preds
+                            .into_iter()
+                            .reduce(|a, b| Predicate::And(Box::new(a), Box::new(b)))
+                    })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let combined = union
+            .into_iter()
+            .flatten()
+            .reduce(|a, b| Predicate::Or(Box::new(a), Box::new(b)));
+
+        Ok(combined.into_iter().collect())
+    }
+
+    fn parse_predicate(clause: &str) -> Result<Predicate, String> {
+        let clause = clause.trim();
+        if let Some((key, value)) = clause.split_once('=') {
+            return Ok(Predicate::Eq(
+                key.trim().to_string(),
+                value.trim().trim_matches('"').to_string(),
+            ));
+        }
+        if let Some((key, pattern)) = clause.split_once('~') {
+            return Ok(Predicate::Matches(
+                key.trim().to_string(),
+                pattern.trim().trim_matches('"').to_string(),
+            ));
+        }
+        if clause.is_empty() {
+            return Err("empty predicate".to_string());
+        }
+        Ok(Predicate::HasKey(clause.to_string()))
+    }
+}
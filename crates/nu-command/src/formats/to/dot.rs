@@ -0,0 +1,308 @@
+use nu_engine::command_prelude::*;
+
+#[derive(Clone)]
+pub struct ToDot;
+
+impl Command for ToDot {
+    fn name(&self) -> &str {
+        "to dot"
+    }
+
+    fn description(&self) -> &str {
+        "Converts structured graph data into a Graphviz DOT string."
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("to dot")
+            .input_output_types(vec![(Type::Any, Type::String)])
+            .named(
+                "graph-kind",
+                SyntaxShape::String,
+                "`directed` (the default, using `digraph`/`->`) or `undirected` (using `graph`/`--`). Overrides the input's own `kind` field, if any.",
+                Some('k'),
+            )
+            .category(Category::Formats)
+    }
+
+    fn examples(&self) -> Vec<Example<'_>> {
+        vec![
+            Example {
+                description: "Renders a list of edges as a directed graph",
+                example: r#"[{from: a, to: b, label: "x"}] | to dot"#,
+                result: Some(Value::test_string(
+                    "digraph {\n  a\n  b\n  a -> b [label=\"x\"]\n}\n",
+                )),
+            },
+            Example {
+                description: "Renders an explicit {nodes, edges} record as an undirected graph",
+                example: r#"{nodes: [{name: a}, {name: b}], edges: [{from: a, to: b}]} | to dot --graph-kind undirected"#,
+                result: Some(Value::test_string("graph {\n  a\n  b\n  a -- b\n}\n")),
+            },
+        ]
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let span = call.head;
+        let graph_kind: Option<Spanned<String>> =
+            call.get_flag(engine_state, stack, "graph-kind")?;
+
+        let input = input.try_expand_range()?;
+        let value = input.into_value(span)?;
+
+        let dot = value_to_dot(&value, graph_kind, span)?;
+
+        Ok(Value::string(dot, span).into_pipeline_data())
+    }
+}
+
+struct Graph {
+    directed: bool,
+    nodes: Vec<(String, Vec<(String, String)>)>,
+    edges: Vec<(String, String, Vec<(String, String)>)>,
+}
+
+fn value_to_dot(
+    value: &Value,
+    graph_kind: Option<Spanned<String>>,
+    span: Span,
+) -> Result<String, ShellError> {
+    let graph = value_to_graph(value, span)?;
+
+    let directed = match graph_kind {
+        Some(kind) => match kind.item.as_str() {
+            "directed" => true,
+            "undirected" => false,
+            other => {
+                return Err(ShellError::IncorrectValue {
+                    msg: format!(
+                        "`--graph-kind` must be `directed` or `undirected`, got `{other}`"
+                    ),
+                    val_span: kind.span,
+                    call_span: span,
+                })
+            }
+        },
+        None => graph.directed,
+    };
+
+    let mut out = String::new();
+    out.push_str(if directed { "digraph {\n" } else { "graph {\n" });
+
+    for (name, attrs) in &graph.nodes {
+        out.push_str("  ");
+        out.push_str(&quote_id(name));
+        push_attrs(&mut out, attrs);
+        out.push('\n');
+    }
+
+    let edge_op = if directed { "->" } else { "--" };
+    for (from, to, attrs) in &graph.edges {
+        out.push_str("  ");
+        out.push_str(&quote_id(from));
+        out.push(' ');
+        out.push_str(edge_op);
+        out.push(' ');
+        out.push_str(&quote_id(to));
+        push_attrs(&mut out, attrs);
+        out.push('\n');
+    }
+
+    out.push_str("}\n");
+    Ok(out)
+}
+
+fn value_to_graph(value: &Value, span: Span) -> Result<Graph, ShellError> {
+    match value {
+        Value::Record { val, .. } => {
+            let directed = val
+                .get("kind")
+                .and_then(|v| v.as_str().ok())
+                .map(|kind| kind != "undirected")
+                .unwrap_or(true);
+
+            let nodes = val
+                .get("nodes")
+                .map(|v| v.as_list())
+                .transpose()?
+                .map(|list| {
+                    list.iter()
+                        .map(|node| record_to_named_attrs(node, "name", span))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+
+            let edges = val
+                .get("edges")
+                .map(|v| v.as_list())
+                .transpose()?
+                .map(|list| {
+                    list.iter()
+                        .map(|edge| record_to_edge(edge, span))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?
+                .unwrap_or_default();
+
+            Ok(Graph {
+                directed,
+                nodes,
+                edges,
+            })
+        }
+        Value::List { vals, .. } => {
+            // A bare list is treated as an edge list; nodes are inferred from the endpoints.
+            let mut node_names = vec![];
+            let mut edges = vec![];
+
+            for edge in vals {
+                let (from, to, attrs) = record_to_edge(edge, span)?;
+                if !node_names.contains(&from) {
+                    node_names.push(from.clone());
+                }
+                if !node_names.contains(&to) {
+                    node_names.push(to.clone());
+                }
+                edges.push((from, to, attrs));
+            }
+
+            let nodes = node_names.into_iter().map(|name| (name, vec![])).collect();
+
+            Ok(Graph {
+                directed: true,
+                nodes,
+                edges,
+            })
+        }
+        other => Err(ShellError::CantConvert {
+            to_type: "DOT graph".into(),
+            from_type: other.get_type().to_string(),
+            span,
+            help: Some(
+                "expected a {nodes, edges} record or a list of {from, to, ...attrs} edges".into(),
+            ),
+        }),
+    }
+}
+
+fn record_to_named_attrs(
+    value: &Value,
+    name_key: &str,
+    span: Span,
+) -> Result<(String, Vec<(String, String)>), ShellError> {
+    let record = value.as_record()?;
+    let name = record
+        .get(name_key)
+        .ok_or_else(|| ShellError::IncorrectValue {
+            msg: format!("node record is missing a `{name_key}` field"),
+            val_span: value.span(),
+            call_span: span,
+        })?
+        .as_str()?
+        .to_string();
+
+    let attrs = record
+        .iter()
+        .filter(|(key, _)| key.as_str() != name_key)
+        .map(|(key, val)| Ok((key.clone(), attr_value_to_string(val)?)))
+        .collect::<Result<Vec<_>, ShellError>>()?;
+
+    Ok((name, attrs))
+}
+
+fn record_to_edge(
+    value: &Value,
+    span: Span,
+) -> Result<(String, String, Vec<(String, String)>), ShellError> {
+    let record = value.as_record()?;
+    let from = record
+        .get("from")
+        .ok_or_else(|| ShellError::IncorrectValue {
+            msg: "edge record is missing a `from` field".into(),
+            val_span: value.span(),
+            call_span: span,
+        })?
+        .as_str()?
+        .to_string();
+    let to = record
+        .get("to")
+        .ok_or_else(|| ShellError::IncorrectValue {
+            msg: "edge record is missing a `to` field".into(),
+            val_span: value.span(),
+            call_span: span,
+        })?
+        .as_str()?
+        .to_string();
+
+    let attrs = record
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "from" | "to"))
+        .map(|(key, val)| Ok((key.clone(), attr_value_to_string(val)?)))
+        .collect::<Result<Vec<_>, ShellError>>()?;
+
+    Ok((from, to, attrs))
+}
+
+fn attr_value_to_string(value: &Value) -> Result<String, ShellError> {
+    match value {
+        Value::String { val, .. } => Ok(val.clone()),
+        Value::Int { val, .. } => Ok(val.to_string()),
+        Value::Float { val, .. } => Ok(val.to_string()),
+        Value::Bool { val, .. } => Ok(val.to_string()),
+        _ => value.as_string(),
+    }
+}
+
+fn push_attrs(out: &mut String, attrs: &[(String, String)]) {
+    if attrs.is_empty() {
+        return;
+    }
+
+    out.push_str(" [");
+    for (index, (key, val)) in attrs.iter().enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(key);
+        out.push('=');
+        out.push('"');
+        out.push_str(&val.replace('\\', "\\\\").replace('"', "\\\""));
+        out.push('"');
+    }
+    out.push(']');
+}
+
+/// Quotes a DOT identifier if it isn't already a valid bare identifier (letters, digits and
+/// underscores, not starting with a digit).
+fn quote_id(id: &str) -> String {
+    let is_plain = !id.is_empty()
+        && id
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_plain {
+        id.to_string()
+    } else {
+        format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(ToDot {})
+    }
+}
@@ -3,6 +3,8 @@ use kdl::{KdlDocument, KdlEntry, KdlIdentifier, KdlNode, KdlValue};
 use nu_engine::command_prelude::*;
 use nu_protocol::{PipelineMetadata, ast::PathMember};
 
+use crate::formats::from::kdl::PRESERVE_META_KEY;
+
 #[derive(Clone)]
 pub struct ToKdl;
 
@@ -19,6 +21,11 @@ impl Command for ToKdl {
                 "remove all of the whitespace and trailing line ending",
                 Some('r'),
             )
+            .switch(
+                "preserve",
+                "replay the entry ordering and type annotations recorded by `from kdl --preserve`",
+                Some('p'),
+            )
             .category(Category::Formats)
     }
 
@@ -34,13 +41,14 @@ impl Command for ToKdl {
         input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
         let raw = call.has_flag(engine_state, stack, "raw")?;
+        let preserve = call.has_flag(engine_state, stack, "preserve")?;
         let span = call.head;
 
         // allow ranges to expand and turn into array
         let input = input.try_expand_range()?;
         let value = input.into_value(span)?;
 
-        let kdl_document = value_to_kdl_document(engine_state, &value, span)?;
+        let kdl_document = value_to_kdl_document(engine_state, &value, span, preserve)?;
 
         let kdl_result = if raw {
             kdl_document.to_string().trim().to_string()
@@ -81,26 +89,58 @@ pub fn value_to_kdl_document(
     engine_state: &EngineState,
     v: &Value,
     call_span: Span,
+    preserve: bool,
 ) -> Result<KdlDocument, ShellError> {
     let mut document = KdlDocument::new();
 
     match v {
         Value::Record { val, .. } => {
-            for (key, value) in &**val {
-                let node = value_to_kdl_node(engine_state, key, value, call_span)?;
-                document.nodes_mut().push(node);
+            // When `--preserve` recorded the original node order, emit nodes in that order
+            // (including repeated node names) instead of the record's (deduplicated) key order.
+            let node_order = preserve.then(|| preserved_node_order(val)).flatten();
+
+            if let Some(node_order) = node_order {
+                let mut cursors: std::collections::HashMap<&str, usize> =
+                    std::collections::HashMap::new();
+                for key in &node_order {
+                    // Only a name that occurs more than once was folded into a list of nodes;
+                    // a single node whose value is a list keeps all of its arguments.
+                    let repeated = node_order.iter().filter(|k| *k == key).count() > 1;
+                    let value = match val.get(key) {
+                        Some(Value::List { vals, .. }) if repeated => {
+                            let cursor = cursors.entry(key).or_insert(0);
+                            let picked = vals.get(*cursor);
+                            *cursor += 1;
+                            picked
+                        }
+                        other => other,
+                    };
+                    if let Some(value) = value {
+                        let node =
+                            value_to_kdl_node(engine_state, key, value, call_span, preserve)?;
+                        document.nodes_mut().push(node);
+                    }
+                }
+            } else {
+                for (key, value) in &**val {
+                    if key == PRESERVE_META_KEY {
+                        continue;
+                    }
+                    let node = value_to_kdl_node(engine_state, key, value, call_span, preserve)?;
+                    document.nodes_mut().push(node);
+                }
             }
         }
         Value::List { vals, .. } => {
             for (index, value) in vals.iter().enumerate() {
                 let node_name = format!("item_{index}");
-                let node = value_to_kdl_node(engine_state, &node_name, value, call_span)?;
+                let node = value_to_kdl_node(engine_state, &node_name, value, call_span, preserve)?;
                 document.nodes_mut().push(node);
             }
         }
         _ => {
             // For scalar values, create a single node named "value"
-            let node = value_to_kdl_node(engine_state, "value", v, call_span)?;
+            let node = value_to_kdl_node(engine_state, "value", v, call_span, preserve)?;
             document.nodes_mut().push(node);
         }
     }
@@ -108,16 +148,32 @@ pub fn value_to_kdl_document(
     Ok(document)
 }
 
+/// Reads back the `node_order` list that `from kdl --preserve` stored under [`PRESERVE_META_KEY`].
+fn preserved_node_order(record: &nu_protocol::Record) -> Option<Vec<String>> {
+    let meta = record.get(PRESERVE_META_KEY)?.as_record().ok()?;
+    let order = meta.get("node_order")?.as_list().ok()?;
+    Some(
+        order
+            .iter()
+            .filter_map(|v| v.as_str().ok().map(str::to_string))
+            .collect(),
+    )
+}
+
 fn value_to_kdl_node(
     engine_state: &EngineState,
     name: &str,
     value: &Value,
     call_span: Span,
+    preserve: bool,
 ) -> Result<KdlNode, ShellError> {
     let node_name = KdlIdentifier::from(name);
     let mut node = KdlNode::new(node_name);
 
     match value {
+        Value::Record { val, .. } if preserve && val.contains(PRESERVE_META_KEY) => {
+            build_node_from_preserved_entries(engine_state, &mut node, val, call_span)?;
+        }
         Value::Record { val, .. } => {
             for (key, val) in &**val {
                 if key == "children" {
@@ -129,11 +185,15 @@ fn value_to_kdl_node(
                         } => {
                             let mut children_doc = KdlDocument::new();
                             for (child_key, child_val) in &**children_record {
+                                if child_key == PRESERVE_META_KEY {
+                                    continue;
+                                }
                                 let child_node = value_to_kdl_node(
                                     engine_state,
                                     child_key,
                                     child_val,
                                     call_span,
+                                    preserve,
                                 )?;
                                 children_doc.nodes_mut().push(child_node);
                             }
@@ -148,27 +208,27 @@ fn value_to_kdl_node(
                             });
                         }
                     }
+                } else if key == PRESERVE_META_KEY {
+                    // Ordering/annotation metadata; not a KDL property of its own.
                 } else if key == "_args" {
                     // Handle arguments specially
                     match val {
                         Value::List { vals, .. } => {
                             for arg_val in vals {
-                                let kdl_val = value_to_kdl_value(engine_state, arg_val, call_span)?;
-                                let entry = KdlEntry::new(kdl_val);
+                                let entry =
+                                    value_to_kdl_entry(engine_state, arg_val, None, call_span)?;
                                 node.entries_mut().push(entry);
                             }
                         }
                         _ => {
-                            let kdl_val = value_to_kdl_value(engine_state, val, call_span)?;
-                            let entry = KdlEntry::new(kdl_val);
+                            let entry = value_to_kdl_entry(engine_state, val, None, call_span)?;
                             node.entries_mut().push(entry);
                         }
                     }
                 } else {
                     // Regular property
-                    let kdl_val = value_to_kdl_value(engine_state, val, call_span)?;
                     let prop_name = KdlIdentifier::from(key.as_str());
-                    let entry = KdlEntry::new_prop(prop_name, kdl_val);
+                    let entry = value_to_kdl_entry(engine_state, val, Some(prop_name), call_span)?;
                     node.entries_mut().push(entry);
                 }
             }
@@ -176,15 +236,13 @@ fn value_to_kdl_node(
         Value::List { vals, .. } => {
             // List becomes arguments
             for val in vals {
-                let kdl_val = value_to_kdl_value(engine_state, val, call_span)?;
-                let entry = KdlEntry::new(kdl_val);
+                let entry = value_to_kdl_entry(engine_state, val, None, call_span)?;
                 node.entries_mut().push(entry);
             }
         }
         _ => {
             // Scalar value becomes an argument
-            let kdl_val = value_to_kdl_value(engine_state, value, call_span)?;
-            let entry = KdlEntry::new(kdl_val);
+            let entry = value_to_kdl_entry(engine_state, value, None, call_span)?;
             node.entries_mut().push(entry);
         }
     }
@@ -192,6 +250,74 @@ fn value_to_kdl_node(
     Ok(node)
 }
 
+/// Rebuilds a node's entries in their original order and type annotations, using the metadata
+/// `from kdl --preserve` stored under [`PRESERVE_META_KEY`], instead of the lossy
+/// properties-then-arguments layout `value_to_kdl_node` otherwise reconstructs. Its `children`
+/// record, if any, is rebuilt with its own recorded node order.
+fn build_node_from_preserved_entries(
+    engine_state: &EngineState,
+    node: &mut KdlNode,
+    record: &nu_protocol::Record,
+    call_span: Span,
+) -> Result<(), ShellError> {
+    if let Some(children @ Value::Record { .. }) = record.get("children") {
+        node.set_children(value_to_kdl_document(engine_state, children, call_span, true)?);
+    }
+
+    let meta = record
+        .get(PRESERVE_META_KEY)
+        .and_then(|v| v.as_record().ok());
+    let Some(entries_meta) = meta
+        .and_then(|m| m.get("entries"))
+        .and_then(|v| v.as_list().ok())
+    else {
+        return Ok(());
+    };
+
+    let args = record.get("_args").and_then(|v| v.as_list().ok());
+    let mut next_arg = 0usize;
+
+    for entry_meta in entries_meta {
+        let Ok(entry_meta) = entry_meta.as_record() else {
+            continue;
+        };
+        let name = entry_meta
+            .get("name")
+            .and_then(|v| v.as_str().ok())
+            .map(str::to_string);
+        let ty = entry_meta
+            .get("ty")
+            .and_then(|v| v.as_str().ok())
+            .map(str::to_string);
+
+        let value = match &name {
+            Some(name) => record.get(name),
+            None => {
+                let picked = args.and_then(|a| a.get(next_arg));
+                next_arg += 1;
+                picked
+            }
+        };
+
+        let Some(value) = value else { continue };
+
+        let kdl_val = value_to_kdl_value(engine_state, value, call_span)?;
+        let mut entry = match &name {
+            Some(name) => KdlEntry::new_prop(KdlIdentifier::from(name.as_str()), kdl_val),
+            None => KdlEntry::new(kdl_val),
+        };
+        // Prefer the originally-recorded annotation; fall back to the intrinsic tag so a value
+        // that didn't carry one in the source (e.g. a field replaced after `from kdl --preserve`)
+        // still round-trips losslessly.
+        if let Some(tag) = ty.as_deref().or_else(|| kdl_type_tag(value)) {
+            entry.set_ty(KdlIdentifier::from(tag));
+        }
+        node.entries_mut().push(entry);
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::used_underscore_binding)]
 fn value_to_kdl_value(
     _engine_state: &EngineState,
@@ -257,6 +383,41 @@ fn value_to_kdl_value(
     })
 }
 
+/// The KDL type annotation that makes a value of this variant round-trip losslessly through
+/// `value_to_kdl_value`'s lossy encoding - `None` for variants `value_to_kdl_value` already
+/// encodes exactly (e.g. `Int`, `Bool`).
+fn kdl_type_tag(v: &Value) -> Option<&'static str> {
+    match v {
+        Value::Binary { .. } => Some("base64"),
+        Value::Date { .. } => Some("date"),
+        Value::Duration { .. } => Some("duration"),
+        Value::Filesize { .. } => Some("filesize"),
+        _ => None,
+    }
+}
+
+/// Builds a [`KdlEntry`] for `value`, tagging it with [`kdl_type_tag`] when the encoding would
+/// otherwise be lossy (`(base64)"..."`, `(date)"..."`, `(duration)3600`, `(filesize)1024`), so
+/// `from kdl` can reconstruct the original `Value` variant instead of falling back to a bare
+/// string or integer.
+fn value_to_kdl_entry(
+    engine_state: &EngineState,
+    value: &Value,
+    prop_name: Option<KdlIdentifier>,
+    call_span: Span,
+) -> Result<KdlEntry, ShellError> {
+    let tag = kdl_type_tag(value);
+    let kdl_val = value_to_kdl_value(engine_state, value, call_span)?;
+    let mut entry = match prop_name {
+        Some(name) => KdlEntry::new_prop(name, kdl_val),
+        None => KdlEntry::new(kdl_val),
+    };
+    if let Some(tag) = tag {
+        entry.set_ty(KdlIdentifier::from(tag));
+    }
+    Ok(entry)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
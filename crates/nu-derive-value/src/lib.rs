@@ -0,0 +1,170 @@
+//! `#[derive(IntoValue)]` for `nu_protocol::IntoValue`.
+//!
+//! Structs with named fields derive to a `Value::Record` whose columns are the field names (or
+//! the name given via `#[nu_value(rename = "...")]`), each field's value converted through its own
+//! `IntoValue` impl; fields marked `#[nu_value(skip)]` are omitted. Enums derive to a tagged record
+//! `{type: "<variant>", value: <payload>}` via `nu_protocol::tagged_enum_value`, built from the
+//! variant's single field for a newtype variant or a nested record for named-field variants; unit
+//! variants carry `Value::nothing` as their payload.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+#[proc_macro_derive(IntoValue, attributes(nu_value))]
+pub fn derive_into_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let body = match &input.data {
+        Data::Struct(data) => derive_struct_body(&data.fields),
+        Data::Enum(data) => derive_enum_body(name, data),
+        Data::Union(_) => {
+            return syn::Error::new_spanned(
+                &input,
+                "IntoValue cannot be derived for unions",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+
+    let expanded = quote! {
+        #[automatically_derived]
+        impl ::nu_protocol::IntoValue for #name {
+            fn into_value(self, span: ::nu_protocol::Span) -> ::nu_protocol::Value {
+                #body
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+fn field_name_and_column(field: &syn::Field) -> Option<(syn::Ident, String)> {
+    let ident = field.ident.clone()?;
+    if field_has_skip(field) {
+        return None;
+    }
+    let column = field_rename(field).unwrap_or_else(|| ident.to_string());
+    Some((ident, column))
+}
+
+fn field_has_skip(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.path().is_ident("nu_value")
+            && attr
+                .parse_args::<syn::Meta>()
+                .map(|meta| meta.path().is_ident("skip"))
+                .unwrap_or(false)
+    })
+}
+
+fn field_rename(field: &syn::Field) -> Option<String> {
+    field.attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("nu_value") {
+            return None;
+        }
+        let meta = attr.parse_args::<syn::MetaNameValue>().ok()?;
+        if !meta.path.is_ident("rename") {
+            return None;
+        }
+        match meta.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        }
+    })
+}
+
+fn derive_struct_body(fields: &Fields) -> TokenStream2 {
+    match fields {
+        Fields::Named(named) => {
+            let pushes = named.named.iter().filter_map(|field| {
+                let (ident, column) = field_name_and_column(field)?;
+                Some(quote! {
+                    record.push(#column, ::nu_protocol::IntoValue::into_value(self.#ident, span));
+                })
+            });
+            quote! {
+                let mut record = ::nu_protocol::Record::new();
+                #(#pushes)*
+                ::nu_protocol::Value::record(record, span)
+            }
+        }
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            quote! {
+                ::nu_protocol::IntoValue::into_value(self.0, span)
+            }
+        }
+        Fields::Unnamed(_) => quote! {
+            ::nu_protocol::Value::nothing(span)
+        },
+        Fields::Unit => quote! {
+            ::nu_protocol::Value::nothing(span)
+        },
+    }
+}
+
+fn derive_enum_body(name: &syn::Ident, data: &syn::DataEnum) -> TokenStream2 {
+    let arms = data.variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let variant_name = variant_ident.to_string();
+        match &variant.fields {
+            Fields::Unit => quote! {
+                #name::#variant_ident => ::nu_protocol::tagged_enum_value(
+                    #variant_name,
+                    ::nu_protocol::Value::nothing(span),
+                    span,
+                ),
+            },
+            Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => quote! {
+                #name::#variant_ident(payload) => ::nu_protocol::tagged_enum_value(
+                    #variant_name,
+                    ::nu_protocol::IntoValue::into_value(payload, span),
+                    span,
+                ),
+            },
+            Fields::Unnamed(_) => quote! {
+                #name::#variant_ident(..) => ::nu_protocol::tagged_enum_value(
+                    #variant_name,
+                    ::nu_protocol::Value::nothing(span),
+                    span,
+                ),
+            },
+            Fields::Named(named) => {
+                let field_idents: Vec<_> = named
+                    .named
+                    .iter()
+                    .filter_map(|f| f.ident.clone())
+                    .collect();
+                let pushes = named.named.iter().filter_map(|field| {
+                    let (ident, column) = field_name_and_column(field)?;
+                    Some(quote! {
+                        record.push(#column, ::nu_protocol::IntoValue::into_value(#ident, span));
+                    })
+                });
+                quote! {
+                    #name::#variant_ident { #(#field_idents),* } => {
+                        let mut record = ::nu_protocol::Record::new();
+                        #(#pushes)*
+                        ::nu_protocol::tagged_enum_value(
+                            #variant_name,
+                            ::nu_protocol::Value::record(record, span),
+                            span,
+                        )
+                    }
+                }
+            }
+        }
+    });
+
+    quote! {
+        match self {
+            #(#arms)*
+        }
+    }
+}
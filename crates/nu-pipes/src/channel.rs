@@ -0,0 +1,264 @@
+//! A typed message channel layered over a raw byte pipe.
+//!
+//! [`unidirectional::pipe`](crate::unidirectional::pipe) only moves raw bytes; [`Sender`]/
+//! [`Receiver`] add framing and (de)serialization on top, so higher layers can exchange
+//! structured messages - e.g. `nu_protocol` values or control headers - instead of parsing an
+//! ad-hoc byte format themselves. Each message is framed as a little-endian `u32` length prefix
+//! followed by its bincode-encoded payload.
+//!
+//! Both halves are themselves `Serialize`/`Deserialize` (via the underlying [`crate::PipeFd`]),
+//! so a `Sender<T>`/`Receiver<T>` can be embedded in a larger message - e.g. a plugin call - sent
+//! to a spawned process and reconstructed there, the same way a bare pipe end already crosses
+//! that boundary.
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::thread;
+
+use nu_protocol::ShellError;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::io::{CloseOwningError, OwningPipeReader, OwningPipeWriter};
+use crate::unidirectional::{pipe, PipeRead, PipeWrite};
+use crate::utils::NamedScopedThreadSpawn;
+use crate::{trace_pipe, PipeError, PipeFd};
+
+/// Creates a connected [`Sender`]/[`Receiver`] pair backed by a fresh unidirectional pipe. See
+/// the module documentation for the framing format.
+pub fn channel<T: Serialize + DeserializeOwned>() -> Result<(Sender<T>, Receiver<T>), PipeError> {
+    let (read_fd, write_fd) = pipe()?;
+
+    Ok((
+        Sender {
+            writer: write_fd.into_writer(),
+            _marker: PhantomData,
+        },
+        Receiver {
+            reader: read_fd.into_reader(),
+            _marker: PhantomData,
+        },
+    ))
+}
+
+/// The sending half of a typed [`channel`].
+pub struct Sender<T> {
+    writer: OwningPipeWriter,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize> Sender<T> {
+    /// Serializes `message` and writes it to the pipe as a single length-prefixed frame.
+    pub fn send(&mut self, message: &T) -> Result<(), ChannelError> {
+        let payload = bincode::serialize(message).map_err(ChannelError::Serialize)?;
+        let len = u32::try_from(payload.len())
+            .map_err(|_| ChannelError::MessageTooLong(payload.len()))?;
+
+        trace_pipe!("sending {} byte message", payload.len());
+
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(&payload)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Closes the underlying pipe, signalling EOF to the [`Receiver`].
+    pub fn close(self) -> Result<(), CloseOwningError<OwningPipeWriter, std::io::Error>> {
+        self.writer.close()
+    }
+}
+
+/// Serializes to just the underlying [`PipeFd`] - the same representation a bare pipe end
+/// already round-trips through a spawned plugin with (see `PipeFd`'s own `Serialize` impl) - so
+/// a `Sender<T>` can be embedded directly in a message like `PluginCall` and reconstructed on the
+/// other side with [`Sender::deserialize`].
+impl<T> Serialize for Sender<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.writer.fd().serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Sender<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fd = PipeFd::<PipeWrite>::deserialize(deserializer)?;
+
+        Ok(Self {
+            writer: fd.into_writer(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// The receiving half of a typed [`channel`].
+pub struct Receiver<T> {
+    reader: OwningPipeReader,
+    _marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Receiver<T> {
+    /// Reads the next length-prefixed frame off the pipe and deserializes it.
+    ///
+    /// Returns `Ok(None)` once the sender has closed its end cleanly, i.e. EOF lands exactly on
+    /// a frame boundary. A sender that closes mid-frame - after the length prefix or part of the
+    /// payload, but before the rest - is reported as [`ChannelError::UnexpectedEof`] rather than
+    /// treated as a clean end, since it means a message never finished writing.
+    pub fn recv(&mut self) -> Result<Option<T>, ChannelError> {
+        let mut len_buf = [0u8; 4];
+        let mut filled = 0;
+        while filled < len_buf.len() {
+            match self.reader.read(&mut len_buf[filled..])? {
+                0 if filled == 0 => return Ok(None),
+                0 => return Err(ChannelError::UnexpectedEof),
+                n => filled += n,
+            }
+        }
+        let len = u32::from_le_bytes(len_buf) as usize;
+
+        trace_pipe!("receiving {} byte message", len);
+
+        let mut payload = vec![0u8; len];
+        self.reader.read_exact(&mut payload).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                ChannelError::UnexpectedEof
+            } else {
+                ChannelError::Io(e)
+            }
+        })?;
+
+        bincode::deserialize(&payload)
+            .map(Some)
+            .map_err(ChannelError::Deserialize)
+    }
+
+    /// Closes the underlying pipe.
+    pub fn close(self) -> Result<(), CloseOwningError<OwningPipeReader, PipeError>> {
+        self.reader.close()
+    }
+}
+
+/// Serializes to just the underlying [`PipeFd`], mirroring [`Sender`]'s `Serialize` impl - see
+/// its docs for why.
+impl<T> Serialize for Receiver<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.reader.fd().serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Receiver<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fd = PipeFd::<PipeRead>::deserialize(deserializer)?;
+
+        Ok(Self {
+            reader: fd.into_reader(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T: DeserializeOwned + Send> Receiver<T> {
+    /// Spawns a scoped background thread that calls `on_message` for every message received on
+    /// the channel, until the [`Sender`] closes its end or a [`ChannelError`] is hit. Mirrors the
+    /// scoped-thread pattern `StreamWriter::send_stream_scoped` uses to serve a pipe for the
+    /// lifetime of a thread scope, so a channel can likewise be served from a background thread.
+    pub fn recv_scoped<'scope, 'env: 'scope>(
+        mut self,
+        scope: &'scope thread::Scope<'scope, 'env>,
+        mut on_message: impl FnMut(T) + Send + 'scope,
+    ) -> Result<thread::ScopedJoinHandle<'scope, Result<(), ChannelError>>, std::io::Error>
+    where
+        T: 'scope,
+    {
+        scope.spawn_named("serve_channel", move || loop {
+            match self.recv() {
+                Ok(Some(message)) => on_message(message),
+                Ok(None) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        })
+    }
+}
+
+/// An error that can occur while sending or receiving framed messages over a [`channel`].
+#[derive(Debug)]
+pub enum ChannelError {
+    /// The pipe was closed in the middle of a frame, i.e. after a partial length prefix or
+    /// payload rather than cleanly between frames.
+    UnexpectedEof,
+    /// A message's length prefix overflowed `u32` - the payload was over 4 GiB.
+    MessageTooLong(usize),
+    /// Encoding the message with bincode failed.
+    Serialize(bincode::Error),
+    /// Decoding the received bytes with bincode failed.
+    Deserialize(bincode::Error),
+    /// The underlying pipe returned an I/O error.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelError::UnexpectedEof => write!(f, "pipe closed in the middle of a frame"),
+            ChannelError::MessageTooLong(len) => write!(
+                f,
+                "message of {len} bytes is too long to frame (max {} bytes)",
+                u32::MAX
+            ),
+            ChannelError::Serialize(e) => write!(f, "failed to serialize channel message: {e}"),
+            ChannelError::Deserialize(e) => {
+                write!(f, "failed to deserialize channel message: {e}")
+            }
+            ChannelError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ChannelError {}
+
+impl From<std::io::Error> for ChannelError {
+    fn from(e: std::io::Error) -> Self {
+        ChannelError::Io(e)
+    }
+}
+
+impl From<ChannelError> for ShellError {
+    fn from(e: ChannelError) -> Self {
+        ShellError::IOError { msg: e.to_string() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recv_returns_none_on_clean_close() {
+        let (tx, mut rx) = channel::<u32>().unwrap();
+        tx.close().unwrap();
+
+        assert!(matches!(rx.recv(), Ok(None)));
+    }
+
+    #[test]
+    fn recv_reports_unexpected_eof_on_partial_frame() {
+        let (read_fd, write_fd) = pipe().unwrap();
+        let mut writer = write_fd.into_writer();
+        // Claim a 3-byte payload, but only ever write 1 byte of it before closing.
+        writer.write_all(&3u32.to_le_bytes()).unwrap();
+        writer.write_all(&[1]).unwrap();
+        writer.close().unwrap();
+
+        let mut rx: Receiver<u32> = Receiver {
+            reader: read_fd.into_reader(),
+            _marker: PhantomData,
+        };
+
+        assert!(matches!(rx.recv(), Err(ChannelError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn send_recv_round_trips_a_value() {
+        let (mut tx, mut rx) = channel::<String>().unwrap();
+        tx.send(&"hello".to_string()).unwrap();
+
+        assert_eq!(rx.recv().unwrap(), Some("hello".to_string()));
+    }
+}
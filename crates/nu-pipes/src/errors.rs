@@ -17,6 +17,67 @@ impl PipeError {
             message: message.into(),
         }
     }
+
+    /// Creates a `PipeError` from the current `errno`, tagged with `context` (typically the libc
+    /// call that just failed, e.g. via [`crate::libc_call_error`]). This is what
+    /// [`crate::libc_call`] builds its error from, so every OS call a pipe backend makes -
+    /// including `create_pipe`'s `pipe2`/`fcntl` calls - surfaces a failure carrying the actual
+    /// `OSError` rather than a bare "something went wrong".
+    pub fn last_os_error<S: Into<String>>(context: S) -> Self {
+        Self::os_error(context)
+    }
+
+    /// Creates a `PipeError` representing a read/write that was aborted because it didn't
+    /// complete within its caller-supplied deadline.
+    pub fn timed_out() -> Self {
+        Self {
+            kind: OSErrorKind::TimedOut,
+            message: "operation timed out".to_string(),
+        }
+    }
+
+    /// Creates a `PipeError` representing a read that was aborted by a [`crate::CancelToken`]
+    /// before any data (or EOF) arrived.
+    pub fn cancelled() -> Self {
+        Self {
+            kind: OSErrorKind::Cancelled,
+            message: "operation was cancelled".to_string(),
+        }
+    }
+
+    /// Creates a `PipeError` representing a read/write on a pipe put into non-blocking mode
+    /// (see [`crate::CreatePipeOptions::nonblocking_read`]) that would otherwise have blocked
+    /// (`EAGAIN`/`EWOULDBLOCK`).
+    pub fn would_block() -> Self {
+        Self {
+            kind: OSErrorKind::WouldBlock,
+            message: "operation would block".to_string(),
+        }
+    }
+
+    /// Creates a `PipeError` representing a write into a pipe whose read end has already been
+    /// closed (`EPIPE` on unix, `ERROR_BROKEN_PIPE`/`ERROR_NO_DATA` on Windows). Callers that
+    /// drive a producer (e.g. an external command writing its stdout into nushell) can match on
+    /// [`Self::is_broken_pipe`] to treat this as "the downstream consumer is done" and stop
+    /// producing, rather than a fatal I/O failure - matching how a shell silently stops a
+    /// producer when e.g. `... | first 10` closes the read side early.
+    pub fn broken_pipe() -> Self {
+        Self {
+            kind: OSErrorKind::BrokenPipe,
+            message: "the reading end of the pipe was closed".to_string(),
+        }
+    }
+
+    /// Whether this error represents the read end of a pipe having been closed while writing to
+    /// it. See [`Self::broken_pipe`].
+    pub fn is_broken_pipe(&self) -> bool {
+        self.kind == OSErrorKind::BrokenPipe
+    }
+
+    /// Whether this error is transient and worth retrying. See [`OSErrorKind::is_transient`].
+    pub fn is_transient(&self) -> bool {
+        self.kind.is_transient()
+    }
 }
 
 impl std::error::Error for PipeError {}
@@ -35,10 +96,54 @@ impl From<PipeError> for std::io::Error {
     }
 }
 
+impl From<std::io::Error> for PipeError {
+    fn from(error: std::io::Error) -> Self {
+        Self {
+            kind: error
+                .raw_os_error()
+                .map(OSErrorKind::from)
+                .unwrap_or(OSErrorKind::None),
+            message: error.to_string(),
+        }
+    }
+}
+
 impl From<PipeError> for ShellError {
     fn from(error: PipeError) -> Self {
-        ShellError::IOError {
-            msg: error.to_string(),
+        let msg = error.to_string();
+        match error.kind {
+            OSErrorKind::FileNotFound => ShellError::GenericError {
+                error: "File not found".to_string(),
+                msg,
+                span: None,
+                help: Some("check that the path exists and is spelled correctly".to_string()),
+                inner: vec![],
+            },
+            OSErrorKind::AccessDenied => ShellError::GenericError {
+                error: "Permission denied".to_string(),
+                msg,
+                span: None,
+                help: Some(
+                    "check that the current user has permission to access this resource"
+                        .to_string(),
+                ),
+                inner: vec![],
+            },
+            OSErrorKind::BrokenPipe
+            | OSErrorKind::ConnectionReset
+            | OSErrorKind::ConnectionAborted
+            | OSErrorKind::ConnectionRefused
+            | OSErrorKind::NotConnected
+            | OSErrorKind::HostUnreachable => ShellError::GenericError {
+                error: "Connection error".to_string(),
+                msg,
+                span: None,
+                help: Some(
+                    "the other end of this pipe or connection is closed or unreachable".to_string(),
+                ),
+                inner: vec![],
+            },
+            _ => ShellError::IOError { msg },
         }
     }
 }
@@ -68,7 +173,23 @@ pub enum OSErrorKind {
     DestinationAddressRequired,
     HostUnreachable,
     MessageTooLong,
-    Unknown(i32),
+    /// A read/write was aborted because it didn't complete within its deadline. See
+    /// [`PipeError::timed_out`].
+    TimedOut,
+    /// A read was aborted by a [`crate::CancelToken`] before any data (or EOF) arrived. See
+    /// [`PipeError::cancelled`].
+    Cancelled,
+    /// A read/write on a non-blocking pipe would have blocked. See [`PipeError::would_block`].
+    WouldBlock,
+    /// A syscall was interrupted by a delivered signal before it could make progress (`EINTR`).
+    /// Short-lived local IPC calls hit this routinely on signal-heavy systems; it means nothing
+    /// about the call itself, just that it has to be re-issued.
+    Interrupted,
+    /// An OS error code that doesn't map to any of the kinds above. Always wildcard-match this
+    /// (`_ => ...`) rather than depend on which raw codes land here - new kinds get pulled out of
+    /// it over time as callers need to distinguish them, following `std::io::ErrorKind::Other`'s
+    /// own "don't rely on the exact meaning" convention.
+    Uncategorized(i32),
 }
 
 impl OSErrorKind {
@@ -79,6 +200,17 @@ impl OSErrorKind {
             OSErrorKind::None
         }
     }
+
+    /// Whether this error is worth retrying as-is, rather than a real failure: the call was
+    /// interrupted by a signal, would have blocked on a non-blocking pipe, or timed out waiting
+    /// for a peer that may still show up. Callers driving a bounded retry loop should consult
+    /// this instead of hand-matching specific kinds.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            OSErrorKind::Interrupted | OSErrorKind::WouldBlock | OSErrorKind::TimedOut
+        )
+    }
 }
 
 #[cfg(windows)]
@@ -129,7 +261,9 @@ impl From<windows::Win32::Foundation::WIN32_ERROR> for OSErrorKind {
             }
             windows::Win32::Foundation::ERROR_CONNECTION_INVALID => OSErrorKind::NotSocket,
             windows::Win32::Foundation::ERROR_CONNECTION_ACTIVE => OSErrorKind::AlreadyConnected,
-            _ => OSErrorKind::Unknown(error.0 as i32),
+            windows::Win32::Foundation::ERROR_TIMEOUT => OSErrorKind::TimedOut,
+            windows::Win32::Foundation::ERROR_SEM_TIMEOUT => OSErrorKind::TimedOut,
+            _ => OSErrorKind::Uncategorized(error.0 as i32),
         }
     }
 }
@@ -159,7 +293,10 @@ impl From<i32> for OSErrorKind {
             libc::EDESTADDRREQ => OSErrorKind::DestinationAddressRequired,
             libc::EHOSTUNREACH => OSErrorKind::HostUnreachable,
             libc::EMSGSIZE => OSErrorKind::MessageTooLong,
-            e => OSErrorKind::Unknown(e),
+            libc::ETIMEDOUT => OSErrorKind::TimedOut,
+            libc::EAGAIN => OSErrorKind::WouldBlock,
+            libc::EINTR => OSErrorKind::Interrupted,
+            e => OSErrorKind::Uncategorized(e),
         }
         #[cfg(windows)]
         windows::Win32::Foundation::WIN32_ERROR(code as u32).into()
@@ -191,7 +328,11 @@ impl From<OSErrorKind> for std::io::ErrorKind {
             OSErrorKind::HostUnreachable => std::io::ErrorKind::AddrNotAvailable,
             OSErrorKind::MessageTooLong => std::io::ErrorKind::InvalidInput,
             OSErrorKind::AddressFamilyNotSupported => std::io::ErrorKind::AddrNotAvailable,
-            OSErrorKind::Unknown(_) => std::io::ErrorKind::Other,
+            OSErrorKind::TimedOut => std::io::ErrorKind::TimedOut,
+            OSErrorKind::Cancelled => std::io::ErrorKind::Interrupted,
+            OSErrorKind::WouldBlock => std::io::ErrorKind::WouldBlock,
+            OSErrorKind::Interrupted => std::io::ErrorKind::Interrupted,
+            OSErrorKind::Uncategorized(_) => std::io::ErrorKind::Other,
         }
     }
 }
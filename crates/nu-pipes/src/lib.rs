@@ -2,12 +2,15 @@
 
 //! Nu-pipes is a library for working with pipes in a cross-platform way.
 //! It utilizes pipe(2) on Unix and CreatePipe on Windows.
+pub mod channel;
 mod errors;
 mod os_pipes;
+mod stream_reader;
 mod stream_writer;
 pub mod utils;
 
 use errors::*;
 pub use io::{PipeReader, PipeWriter};
 pub use os_pipes::*;
+pub use stream_reader::StreamReceiver;
 pub use stream_writer::StreamWriter;
@@ -0,0 +1,135 @@
+//! Cooperative cancellation for an otherwise-blocking pipe read.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::errors::PipeError;
+
+#[cfg(unix)]
+use crate::{
+    os_pipes::sys,
+    unidirectional::{pipe, PipeRead, PipeWrite},
+    PipeFd,
+};
+
+/// A handle that can interrupt a blocked [`PipeImplBase::read_cancellable`]/
+/// [`PipeImplBase::read_cancellable_timeout`] read from another thread, using the self-pipe
+/// trick: [`cancel`](CancelToken::cancel) writes a byte to an internal control pipe (an event on
+/// Windows) that the blocked read's `poll`/`WaitForMultipleObjects` is also waiting on, waking it
+/// immediately instead of waiting for data or EOF on the pipe it was actually reading.
+///
+/// `Clone`able and `Send`, so the same token can be handed to both the read and whatever later
+/// decides to cancel it (e.g. the engine's Ctrl-C interrupt signal) without coordinating a single
+/// owner. Cancelling is idempotent and never touches the data pipe, so it's always safe to call
+/// more than once, from more than one thread, or after the read it was meant for has already
+/// finished.
+///
+/// [`PipeImplBase::read_cancellable`]: super::PipeImplBase::read_cancellable
+/// [`PipeImplBase::read_cancellable_timeout`]: super::PipeImplBase::read_cancellable_timeout
+#[derive(Clone)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    #[cfg(unix)]
+    control_read: PipeFd<PipeRead>,
+    #[cfg(unix)]
+    control_write: PipeFd<PipeWrite>,
+    #[cfg(windows)]
+    event: windows::Win32::Foundation::HANDLE,
+    cancelled: AtomicBool,
+}
+
+// A Win32 event handle has no thread affinity - waiting on it or signalling it from any thread is
+// sound, which is exactly what sharing `Inner` across `CancelToken` clones requires.
+#[cfg(windows)]
+unsafe impl Send for Inner {}
+#[cfg(windows)]
+unsafe impl Sync for Inner {}
+
+impl CancelToken {
+    /// Creates a new, not-yet-cancelled token, setting up whatever OS primitive backs it (a
+    /// control pipe on unix, a manual-reset event on Windows).
+    pub fn new() -> Result<Self, PipeError> {
+        #[cfg(unix)]
+        let inner = {
+            let (control_read, control_write) = pipe()?;
+            Inner {
+                control_read,
+                control_write,
+                cancelled: AtomicBool::new(false),
+            }
+        };
+
+        #[cfg(windows)]
+        let inner = {
+            use windows::core::{BOOL, PCWSTR};
+            use windows::Win32::System::Threading::CreateEventW;
+
+            let event =
+                unsafe { CreateEventW(None, BOOL::from(true), BOOL::from(false), PCWSTR::null()) }?;
+            Inner {
+                event,
+                cancelled: AtomicBool::new(false),
+            }
+        };
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Requests cancellation of whatever read this token is associated with. Idempotent -
+    /// calling this more than once, or after the read it was meant for has already finished, is
+    /// harmless.
+    pub fn cancel(&self) {
+        if self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        #[cfg(unix)]
+        {
+            // Best-effort: if this write fails (e.g. a full buffer from some earlier,
+            // not-yet-observed cancellation) the reader is going to wake up anyway.
+            let _ = sys::PipeImpl::write(&self.inner.control_write, &[0u8]);
+        }
+
+        #[cfg(windows)]
+        unsafe {
+            let _ = windows::Win32::System::Threading::SetEvent(self.inner.event);
+        }
+    }
+
+    /// Whether [`cancel`](CancelToken::cancel) has already been called on this token (or any of
+    /// its clones).
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// The read end of the internal control pipe a [`PipeImplBase::read_cancellable`]
+    /// implementation polls alongside the data fd.
+    ///
+    /// [`PipeImplBase::read_cancellable`]: super::PipeImplBase::read_cancellable
+    #[cfg(unix)]
+    pub(crate) fn control_fd(&self) -> &PipeFd<PipeRead> {
+        &self.inner.control_read
+    }
+
+    /// The event a [`PipeImplBase::read_cancellable`] implementation waits on alongside the
+    /// overlapped read.
+    ///
+    /// [`PipeImplBase::read_cancellable`]: super::PipeImplBase::read_cancellable
+    #[cfg(windows)]
+    pub(crate) fn event_handle(&self) -> windows::Win32::Foundation::HANDLE {
+        self.inner.event
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::Foundation::CloseHandle(self.event);
+        }
+    }
+}
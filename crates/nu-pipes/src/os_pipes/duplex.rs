@@ -0,0 +1,77 @@
+//! Full-duplex pipes, where a single endpoint can be both read from and written to.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    os_pipes::{sys, PipeImplBase},
+    AsPipeFd, PipeError, PipeFd,
+};
+
+use super::unidirectional::{PipeFdType, PipeFdTypeEnum};
+
+/// Creates a pair of connected, full-duplex endpoints. Unlike [`unidirectional::pipe`](super::unidirectional::pipe),
+/// each endpoint here can be both read from and written to, over a single handle. This enables
+/// request/response RPC between two long-lived processes over one inheritable handle pair,
+/// instead of spawning a fresh child and tearing it down for every call.
+///
+/// Uses `socketpair(2)` on unix and a duplex named pipe on Windows.
+///
+/// Each returned endpoint round-trips through the same `PipeFd` (de)serialization machinery a
+/// unidirectional pipe end already uses, so one can be handed to a spawned child (e.g. JSON-
+/// encoded into its argv) and reconstructed there exactly like `unidirectional::pipe`'s halves
+/// are today - without the caller juggling two separate `pipe()` calls and four descriptors to
+/// get the same request/response shape.
+pub fn duplex() -> Result<(PipeFd<PipeDuplex>, PipeFd<PipeDuplex>), PipeError> {
+    sys::PipeImpl::create_duplex_pair()
+}
+
+pub(crate) struct RawDuplexStream<T: AsPipeFd<PipeDuplex>>(pub(crate) T);
+
+impl<T: AsPipeFd<PipeDuplex>> std::io::Read for RawDuplexStream<T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(sys::PipeImpl::read_duplex(&self.0, buf)?)
+    }
+}
+impl<T: AsPipeFd<PipeDuplex>> std::io::Write for RawDuplexStream<T> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(sys::PipeImpl::write_duplex(&self.0, buf)?)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The capability marker of one endpoint of a [`duplex`] pipe.
+///
+/// Unlike [`PipeRead`](super::unidirectional::PipeRead)/[`PipeWrite`](super::unidirectional::PipeWrite),
+/// both endpoints of a duplex pipe share this same type, since the two are interchangeable:
+/// either one can be read from or written to. This also means a `PipeFd<PipeDuplex>` can never be
+/// (de)serialized as a `PipeFd<PipeRead>` or `PipeFd<PipeWrite>`, or vice versa — the same
+/// anti-transmutation guard that protects unidirectional pipes applies here too, since the
+/// `PipeFd` serialization format always round-trips the `PipeFdType::NAME` alongside the fd.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct PipeDuplex(std::marker::PhantomData<()>);
+
+impl PipeFdType for PipeDuplex {
+    const NAME: char = 'd';
+    const TYPE: PipeFdTypeEnum = PipeFdTypeEnum::Duplex;
+    type Other = PipeDuplex;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{duplex::PipeDuplex, unidirectional::PipeRead, FromRawPipeFd, PipeFd};
+
+    #[test]
+    fn assert_duplex_cant_be_transmuted_to_unidirectional() {
+        let duplex: PipeFd<PipeDuplex> = unsafe { PipeFd::from_raw_pipe_fd(42) };
+
+        let serialized = serde_json::to_string(&duplex).unwrap();
+        println!("{}", serialized);
+        // deserialize the endpoint as a unidirectional read end
+        let deserialized = serde_json::from_str::<PipeFd<PipeRead>>(&serialized);
+
+        assert!(deserialized.is_err());
+        println!("This is expected: {:?}", deserialized.unwrap_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! Fd hygiene for spawning a plugin child process - making sure only the fds nushell actually
+//! means to hand over survive into the child, not whatever else happens to be open in this
+//! process at the time.
+
+#[cfg(unix)]
+use std::os::fd::RawFd;
+
+/// Installed as a [`pre_exec`](std::os::unix::process::CommandExt::pre_exec) hook on the
+/// [`std::process::Command`] used to spawn a plugin. Every fd created through `nu_pipes` is
+/// already `O_CLOEXEC` by default (see [`crate::os_pipes::PipeImplBase::create_pipe`]) except the
+/// one end a given call actually hands over, so in the common case there's nothing to clean up
+/// here - this exists as a backstop against whatever else might be open in the process (a file, a
+/// socket, a pipe created outside this crate) and not yet marked close-on-exec, which would
+/// otherwise leak into the child and keep a handed-over pipe from ever reaching EOF there - the
+/// same hang `pipe_in_another_thread_cancelled` exercises, but against a process boundary instead
+/// of a thread one.
+///
+/// `keep` lists the fds this particular spawn intentionally inherits (e.g. the write end of a
+/// `CallInput::Pipe`, or the data pipe from `create_command`); everything else still open above
+/// stderr gets `FD_CLOEXEC` set on it instead of being inherited.
+///
+/// # Safety
+///
+/// Must only run between `fork` and `exec`, i.e. only from inside a `pre_exec` closure - the
+/// child is single-threaded and not yet sharing anything with the rest of the (possibly
+/// multi-threaded) parent at that point, which is what makes reading `/proc/self/fd` here safe
+/// despite not being async-signal-safe in general.
+#[cfg(unix)]
+pub unsafe fn close_other_fds_on_exec(keep: &[RawFd]) -> std::io::Result<()> {
+    let Ok(dir) = std::fs::read_dir("/proc/self/fd") else {
+        // Not every unix has /proc (e.g. macOS, the BSDs) - the per-pipe `O_CLOEXEC` applied at
+        // creation time is the only protection available there.
+        return Ok(());
+    };
+
+    for entry in dir.flatten() {
+        let Ok(fd) = entry.file_name().to_string_lossy().parse::<RawFd>() else {
+            continue;
+        };
+
+        if fd > 2 && !keep.contains(&fd) {
+            libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+    }
+
+    Ok(())
+}
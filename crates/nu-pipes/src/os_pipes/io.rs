@@ -23,6 +23,7 @@ use std::io::{BufReader, BufWriter, Write};
 use nu_protocol::ShellError;
 
 use crate::{
+    duplex::{PipeDuplex, RawDuplexStream},
     errors::PipeError,
     unidirectional::{PipeRead, PipeWrite, RawPipeReader, RawPipeWriter},
     PipeFd, PIPE_BUFFER_CAPACITY,
@@ -170,6 +171,30 @@ impl OwningPipeReader {
     pub fn into_inner(self) -> PipeFd<PipeRead> {
         self.reader.into_inner().0
     }
+
+    /// Consumes the `OwningPipeReader` and converts it into a [`std::process::Stdio`], so it can
+    /// be wired directly into a spawned external command's stdin instead of relaying bytes
+    /// through a thread.
+    ///
+    /// Any bytes already pulled into this reader's internal buffer but not yet consumed by a
+    /// caller are NOT carried over - only bytes still sitting in the underlying pipe make it to
+    /// the spawned process. This is fine for a reader that hasn't been read from yet (the common
+    /// case when wiring a fresh pipe into a child), but isn't a safe conversion for one that's
+    /// already been partially consumed.
+    pub fn into_stdio(self) -> std::process::Stdio {
+        self.into_inner().into()
+    }
+
+    /// Duplicates the underlying file descriptor, returning a new `OwningPipeReader` that reads
+    /// from the same pipe. See [`PipeFd::try_clone`].
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(OwningPipeReader)` wrapping the duplicated file descriptor.
+    /// - `Err(PipeError)` if duplicating the file descriptor fails.
+    pub fn try_clone(&self) -> Result<Self, PipeError> {
+        Ok(Self::new(self.fd().try_clone()?))
+    }
 }
 
 impl std::fmt::Debug for OwningPipeReader {
@@ -254,6 +279,26 @@ impl OwningPipeWriter {
             }
         }
     }
+
+    /// Duplicates the underlying file descriptor, returning a new `OwningPipeWriter` that writes
+    /// to the same pipe. See [`PipeFd::try_clone`].
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(OwningPipeWriter)` wrapping the duplicated file descriptor.
+    /// - `Err(PipeError)` if duplicating the file descriptor fails.
+    pub fn try_clone(&self) -> Result<Self, PipeError> {
+        Ok(Self::new(self.fd().try_clone()?))
+    }
+
+    /// Flushes any buffered bytes and converts the `OwningPipeWriter` into a
+    /// [`std::process::Stdio`], so it can be wired directly into a spawned external command's
+    /// stdout/stderr instead of relaying bytes through a thread.
+    pub fn into_stdio(
+        self,
+    ) -> Result<std::process::Stdio, CloseOwningError<OwningPipeWriter, std::io::Error>> {
+        self.into_inner().map(Into::into)
+    }
 }
 
 impl std::fmt::Debug for OwningPipeWriter {
@@ -273,6 +318,108 @@ impl std::io::Write for OwningPipeWriter {
     }
 }
 
+/// A structure representing one endpoint of a full-duplex pipe, borrowing the underlying fd.
+///
+/// Unlike [`PipeReader`]/[`PipeWriter`], this implements both [`std::io::Read`] and
+/// [`std::io::Write`], since either endpoint of a duplex pipe can do both.
+pub struct DuplexStream<'a> {
+    pub(crate) fd: &'a PipeFd<PipeDuplex>,
+    reader: BufReader<RawDuplexStream<&'a PipeFd<PipeDuplex>>>,
+    writer: BufWriter<RawDuplexStream<&'a PipeFd<PipeDuplex>>>,
+}
+
+impl<'a> DuplexStream<'a> {
+    /// Creates a new `DuplexStream` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `fd` - A reference to a `PipeFd<PipeDuplex>` object representing the pipe file descriptor.
+    pub fn new<'b: 'a>(fd: &'b PipeFd<PipeDuplex>) -> Self {
+        Self {
+            fd,
+            reader: BufReader::with_capacity(PIPE_BUFFER_CAPACITY, RawDuplexStream(fd)),
+            writer: BufWriter::with_capacity(PIPE_BUFFER_CAPACITY, RawDuplexStream(fd)),
+        }
+    }
+}
+
+impl std::io::Read for DuplexStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+impl std::io::Write for DuplexStream<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// An owning counterpart to [`DuplexStream`]. See [`OwningPipeReader`]/[`OwningPipeWriter`] for
+/// the equivalent owning wrappers around a unidirectional pipe.
+///
+/// Reads go through the endpoint's fd and writes through a duplicate of it (via
+/// [`PipeFd::try_clone`]), since a single fd can't be moved into both a [`BufReader`] and a
+/// [`BufWriter`] at once; both refer to the same underlying duplex channel.
+pub struct OwningDuplexStream {
+    reader: BufReader<RawDuplexStream<PipeFd<PipeDuplex>>>,
+    writer: BufWriter<RawDuplexStream<PipeFd<PipeDuplex>>>,
+}
+
+impl OwningDuplexStream {
+    /// Creates a new `OwningDuplexStream` with the given pipe file descriptor.
+    pub fn new(fd: PipeFd<PipeDuplex>) -> Result<Self, PipeError> {
+        let write_fd = fd.try_clone()?;
+
+        Ok(Self {
+            reader: BufReader::with_capacity(PIPE_BUFFER_CAPACITY, RawDuplexStream(fd)),
+            writer: BufWriter::with_capacity(PIPE_BUFFER_CAPACITY, RawDuplexStream(write_fd)),
+        })
+    }
+
+    /// Closes the `OwningDuplexStream` and releases both underlying file descriptors.
+    pub fn close(mut self) -> Result<(), CloseOwningError<OwningDuplexStream, PipeError>> {
+        if let Err(e) = self.flush() {
+            return Err(CloseOwningError::new(
+                PipeError::os_error(e.to_string()),
+                self,
+            ));
+        }
+
+        let read_fd = unsafe { std::ptr::read(&self.reader.get_ref().0) };
+        let write_fd = unsafe { std::ptr::read(&self.writer.get_ref().0) };
+
+        let read_result = read_fd.close();
+        let write_result = write_fd.close();
+
+        match (read_result, write_result) {
+            (Ok(_), Ok(_)) => Ok(()),
+            (Err(e), _) | (_, Err(e)) => {
+                let (err, _) = e.into_parts();
+                Err(CloseOwningError::new(err, self))
+            }
+        }
+    }
+}
+
+impl std::io::Read for OwningDuplexStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+impl std::io::Write for OwningDuplexStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
 /// A wrapper type for an error that occurred when closing the owning pipe reader or writer.
 ///
 /// This type is used to associate an error with the underlying resource that failed to close.
@@ -371,7 +518,63 @@ impl Iterator for PipeIterator<'_> {
 
         match reader.read(&mut buf) {
             Ok(0) => None,
-            Ok(_) => Some(Ok(buf)),
+            Ok(n) => {
+                buf.truncate(n);
+                Some(Ok(buf))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// An iterator over a pipe carrying a length-prefixed frame per message (the same framing
+/// [`crate::channel`] uses: a little-endian `u32` byte count followed by that many raw bytes),
+/// rather than [`PipeIterator`]'s arbitrary OS-read-sized fragments. Each item is one complete
+/// message - iteration only advances once a whole frame has been buffered, even if it took
+/// several reads to arrive.
+///
+/// Ends (`None`) when EOF lands exactly on a frame boundary, i.e. the pipe closed cleanly between
+/// messages. A pipe that closes mid-frame - after the length prefix or part of the payload, but
+/// before the rest - yields a final `Some(Err(_))` instead, since that means a message never
+/// finished writing.
+pub struct FramedPipeIterator<'a> {
+    reader: MaybeOwnedMut<'a>,
+}
+
+impl Iterator for FramedPipeIterator<'_> {
+    type Item = Result<Vec<u8>, ShellError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let reader: &mut dyn std::io::Read = match &mut self.reader {
+            MaybeOwnedMut::Owned(reader) => reader,
+            MaybeOwnedMut::Borrowed(reader) => reader,
+        };
+
+        let mut len_buf = [0u8; 4];
+        let mut filled = 0;
+        while filled < len_buf.len() {
+            match reader.read(&mut len_buf[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => {
+                    return Some(Err(ShellError::IOError {
+                        msg: "pipe closed in the middle of a frame's length prefix".into(),
+                    }))
+                }
+                Ok(n) => filled += n,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+        let len = u32::from_le_bytes(len_buf) as usize;
+
+        let mut payload = vec![0u8; len];
+        match reader.read_exact(&mut payload) {
+            Ok(()) => Some(Ok(payload)),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                Some(Err(ShellError::IOError {
+                    msg: "pipe closed in the middle of a frame's payload".into(),
+                }))
+            }
             Err(e) => Some(Err(e.into())),
         }
     }
@@ -404,6 +607,14 @@ impl<'a> PipeReader<'a> {
             reader: MaybeOwnedMut::Borrowed(self),
         }
     }
+
+    /// Like [`PipeReader::stream`], but yields one complete length-prefixed frame per iteration
+    /// instead of arbitrary OS-read-sized fragments. See [`FramedPipeIterator`].
+    pub fn stream_framed(&'a mut self) -> FramedPipeIterator {
+        FramedPipeIterator {
+            reader: MaybeOwnedMut::Borrowed(self),
+        }
+    }
 }
 
 impl OwningPipeReader {
@@ -432,4 +643,12 @@ impl OwningPipeReader {
             reader: MaybeOwnedMut::Owned(self),
         }
     }
+
+    /// Like [`OwningPipeReader::stream`], but yields one complete length-prefixed frame per
+    /// iteration instead of arbitrary OS-read-sized fragments. See [`FramedPipeIterator`].
+    pub fn stream_framed(&mut self) -> FramedPipeIterator {
+        FramedPipeIterator {
+            reader: MaybeOwnedMut::Owned(self),
+        }
+    }
 }
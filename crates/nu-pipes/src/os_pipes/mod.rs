@@ -1,5 +1,5 @@
 #[cfg(unix)]
-use std::os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, RawFd};
 use std::{marker::PhantomData, ops::Deref};
 
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,7 @@ use crate::{
 };
 
 use self::{
+    duplex::PipeDuplex,
     io::{CloseOwningError, OwningPipeReader, OwningPipeWriter, PipeWriter},
     sys::NativeFd,
     unidirectional::{PipeFdType, PipeFdTypeEnum, PipeRead, PipeWrite},
@@ -17,7 +18,101 @@ use self::{
 /// The inner type of a pipe file descriptor, i32 on Unix and HANDLE on Windows.
 pub type RawPipeFd = i32;
 
+pub use cancel::CancelToken;
+
+/// Blocks until `fd` has data to read (or has hit EOF/broken pipe, which also reads as "ready"
+/// since a read won't block either), or `timeout` elapses, without performing the read itself -
+/// the readiness half of the evented-pipe pattern, so a caller can poll several pipes from one
+/// thread instead of dedicating a thread to each blocking read.
+///
+/// On unix this `poll`s `fd` for `POLLIN`, and works regardless of whether `fd` was created with
+/// [`CreatePipeOptions::nonblocking_read`]. On Windows, anonymous pipes don't support the
+/// named-pipe-only `PIPE_NOWAIT` mode, so there's no event to wait on directly; this instead
+/// polls `PeekNamedPipe` in a short sleep loop, which works on an ordinary synchronous handle.
+pub fn wait_readable(
+    fd: impl AsPipeFd<PipeRead>,
+    timeout: Option<std::time::Duration>,
+) -> PipeResult<bool> {
+    sys::wait_readable(fd, timeout)
+}
+
+/// Which readiness state(s) a [`PollFd`] is interested in, and (after [`poll`] returns) which
+/// one(s) were actually observed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Interest {
+    /// Interested in / observed as readable (including EOF/broken-pipe, which reads as ready
+    /// since a subsequent `read` won't block either).
+    pub readable: bool,
+    /// Interested in / observed as writable.
+    pub writable: bool,
+}
+
+impl Interest {
+    /// Interested in readability only.
+    pub fn readable() -> Self {
+        Self {
+            readable: true,
+            writable: false,
+        }
+    }
+
+    /// Interested in writability only.
+    pub fn writable() -> Self {
+        Self {
+            readable: false,
+            writable: true,
+        }
+    }
+}
+
+/// One pipe end registered with [`poll`]: the fd itself, the [`Interest`] the caller wants to
+/// know about, and (after `poll` returns) the `Interest` that was actually observed.
+pub struct PollFd<'a, T: PipeFdType> {
+    fd: &'a dyn AsPipeFd<T>,
+    interest: Interest,
+    revents: Interest,
+}
+
+impl<'a, T: PipeFdType> PollFd<'a, T> {
+    /// Registers `fd` with `poll`, interested in `interest`.
+    pub fn new(fd: &'a impl AsPipeFd<T>, interest: Interest) -> Self {
+        Self {
+            fd,
+            interest,
+            revents: Interest::default(),
+        }
+    }
+
+    /// The readiness `poll` last observed for this fd. Empty (both fields `false`) until `poll`
+    /// has actually been called with this `PollFd`.
+    pub fn revents(&self) -> Interest {
+        self.revents
+    }
+}
+
+/// Polls every fd in `fds` for the readiness each one registered interest in, in a single
+/// syscall rather than one blocking operation per fd, and returns how many fds came back ready.
+/// Blocks until at least one fd is ready or `timeout` elapses (`None` blocks indefinitely).
+///
+/// Meant for multiplexing several pipes from one thread - e.g. a child's stdout and stderr, or
+/// several plugin channels - instead of dedicating a blocking-read thread to each. On unix this
+/// is backed by `libc::poll`. On Windows, anonymous pipes have no direct writability-readiness
+/// primitive, so a `writable` interest there is reported ready unconditionally (matching the
+/// synchronous, always-eventually-blocking `write` these handles already give you); `readable`
+/// interest polls `PeekNamedPipe` the same way [`wait_readable`] does.
+pub fn poll<T: PipeFdType>(
+    fds: &mut [PollFd<'_, T>],
+    timeout: Option<std::time::Duration>,
+) -> PipeResult<usize> {
+    sys::poll(fds, timeout)
+}
+
+pub mod cancel;
+pub mod duplex;
+pub mod hygiene;
 pub mod io;
+pub mod named;
+pub mod ring;
 pub mod unidirectional;
 
 #[cfg_attr(windows, path = "windows.rs")]
@@ -27,20 +122,221 @@ mod sys;
 /// The capacity of pipe buffers.
 pub const PIPE_BUFFER_CAPACITY: usize = 1024 * 8;
 
+/// Which end(s) of a newly created pipe a spawned child process is allowed to inherit.
+///
+/// [`PipeImplBase::create_pipe`] creates both ends close-on-exec by default, so a descriptor
+/// isn't silently leaked into every `exec`'d child; passing `CreatePipeOptions` to
+/// [`PipeImplBase::create_pipe_with`] opts specific ends back in for the one child that's
+/// actually meant to inherit them.
+///
+/// The close-on-exec default is set atomically at creation, not patched on after the fact, so it
+/// can't race a concurrent `fork`/spawn on another thread: unix uses `pipe2(O_CLOEXEC)` (falling
+/// back to `pipe()` + `fcntl(FD_CLOEXEC)` on the platforms - macOS/iOS among them - where `pipe2`
+/// isn't available), and Windows creates both handles with `bInheritHandle: FALSE`. A caller that
+/// wants a specific end to survive `exec` either opts in here or calls [`PipeFd::into_inheritable`]
+/// on an already-created end to get an inheritable duplicate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CreatePipeOptions {
+    pub(crate) inheritable_read: bool,
+    pub(crate) inheritable_write: bool,
+    pub(crate) nonblocking_read: bool,
+    pub(crate) nonblocking_write: bool,
+}
+
+impl CreatePipeOptions {
+    /// Neither end is inheritable - equivalent to [`PipeImplBase::create_pipe`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes the read end inheritable by a spawned child.
+    pub fn inheritable_read(mut self) -> Self {
+        self.inheritable_read = true;
+        self
+    }
+
+    /// Makes the write end inheritable by a spawned child.
+    pub fn inheritable_write(mut self) -> Self {
+        self.inheritable_write = true;
+        self
+    }
+
+    /// Sets `O_NONBLOCK` on the read end at creation, so a [`PipeImplBase::read`] on it returns
+    /// [`PipeError`] with [`crate::errors::OSErrorKind::WouldBlock`] instead of blocking when
+    /// there's nothing to read yet. Meant for callers driving several pipes from one thread via
+    /// [`wait_readable`] rather than dedicating a thread per pipe.
+    pub fn nonblocking_read(mut self) -> Self {
+        self.nonblocking_read = true;
+        self
+    }
+
+    /// Sets `O_NONBLOCK` on the write end at creation; see [`Self::nonblocking_read`].
+    pub fn nonblocking_write(mut self) -> Self {
+        self.nonblocking_write = true;
+        self
+    }
+}
+
 pub(crate) trait PipeImplBase {
     fn create_pipe() -> Result<OsPipe, PipeError>;
 
+    /// Like [`PipeImplBase::create_pipe`], but `options` selects which end(s) a spawned child is
+    /// allowed to inherit; the other end stays private to this process. On Windows both ends are
+    /// created non-inheritable and `HANDLE_FLAG_INHERIT` is set via `SetHandleInformation` on the
+    /// requested end(s);
+    /// on unix both ends are created `O_CLOEXEC` via `pipe2` (falling back to `pipe()` +
+    /// `fcntl` where `pipe2` isn't available) and `FD_CLOEXEC` is cleared on the requested
+    /// end(s).
+    fn create_pipe_with(options: CreatePipeOptions) -> Result<OsPipe, PipeError>;
+
     fn read(fd: impl AsPipeFd<PipeRead>, buf: &mut [u8]) -> PipeResult<usize>;
 
+    /// Like [`PipeImplBase::read`], but gives up and returns [`PipeError::timed_out`] if no data
+    /// (or EOF) arrives within `timeout`, instead of blocking indefinitely. On Windows this polls
+    /// the read with an overlapped `ReadFile` + `WaitForSingleObject`, calling `CancelIoEx` on
+    /// timeout; on unix it `poll`s the fd for readability before issuing the `read`.
+    ///
+    /// The Windows path only actually bounds the wait on a handle that was opened with
+    /// `FILE_FLAG_OVERLAPPED` - on a handle opened synchronously (which is what every constructor
+    /// in this module currently produces), the OS ignores the overlapped structure and this
+    /// blocks the same as [`PipeImplBase::read`]. Opting a given pipe's creation into overlapped
+    /// mode so this actually bounds the wait is left to a future change.
+    fn read_timeout(
+        fd: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        timeout: std::time::Duration,
+    ) -> PipeResult<usize>;
+
+    /// Like [`PipeImplBase::read`], but also watches `cancel`'s internal control pipe/event
+    /// alongside `fd`, returning a [`PipeError`] with [`crate::errors::OSErrorKind::Cancelled`]
+    /// (mapped to `std::io::ErrorKind::Interrupted`) the moment
+    /// [`CancelToken::cancel`] is called, instead of blocking for data or EOF on `fd`. On unix
+    /// this `poll`s both fds; on Windows this waits on the overlapped read's event together with
+    /// the token's event via `WaitForMultipleObjects`, subject to the same
+    /// synchronous-handle caveat as [`PipeImplBase::read_timeout`].
+    fn read_cancellable(
+        fd: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        cancel: &CancelToken,
+    ) -> PipeResult<usize>;
+
+    /// Combines [`PipeImplBase::read_cancellable`] and [`PipeImplBase::read_timeout`]: gives up
+    /// with [`PipeError::timed_out`] if neither data nor a cancellation arrives within `timeout`.
+    fn read_cancellable_timeout(
+        fd: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        cancel: &CancelToken,
+        timeout: std::time::Duration,
+    ) -> PipeResult<usize>;
+
     fn write(fd: impl AsPipeFd<PipeWrite>, buf: &[u8]) -> PipeResult<usize>;
 
+    /// Scatter-reads into `bufs` in one syscall where the platform supports it (`readv` on unix),
+    /// rather than requiring the caller to first gather everything into one contiguous buffer.
+    /// The default implementation just forwards to [`PipeImplBase::read`] with the first
+    /// non-empty buffer, which is correct but gives up the syscall-count savings.
+    fn read_vectored(
+        fd: impl AsPipeFd<PipeRead>,
+        bufs: &mut [std::io::IoSliceMut<'_>],
+    ) -> PipeResult<usize> {
+        let buf = bufs
+            .iter_mut()
+            .find(|b| !b.is_empty())
+            .map_or(&mut [][..], |b| &mut **b);
+        Self::read(fd, buf)
+    }
+
+    /// Gather-writes `bufs` in one syscall where the platform supports it (`writev` on unix),
+    /// rather than requiring the caller to first copy everything into one contiguous buffer. The
+    /// default implementation just forwards to [`PipeImplBase::write`] with the first non-empty
+    /// buffer, which is correct but gives up the syscall-count savings.
+    fn write_vectored(
+        fd: impl AsPipeFd<PipeWrite>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> PipeResult<usize> {
+        let buf = bufs
+            .iter()
+            .find(|b| !b.is_empty())
+            .map_or(&[][..], |b| &**b);
+        Self::write(fd, buf)
+    }
+
+    /// Reads from one endpoint of a full-duplex pipe. See [`PipeImplBase::read`].
+    fn read_duplex(fd: impl AsPipeFd<PipeDuplex>, buf: &mut [u8]) -> PipeResult<usize>;
+
+    /// Writes to one endpoint of a full-duplex pipe. See [`PipeImplBase::write`].
+    fn write_duplex(fd: impl AsPipeFd<PipeDuplex>, buf: &[u8]) -> PipeResult<usize>;
+
     fn close_pipe<T: PipeFdType>(fd: impl AsPipeFd<T>) -> PipeResult<()>;
 
     fn dup<T: PipeFdType>(fd: impl AsPipeFd<T>) -> PipeResult<PipeFd<T>>;
 
+    /// Like [`PipeImplBase::dup`], but the duplicate keeps `FD_CLOEXEC`/doesn't get
+    /// `HANDLE_FLAG_INHERIT` - the duplicate won't survive a spawned child's `exec` unless it's
+    /// explicitly passed down, same as the original. Backs [`PipeFd::try_clone`]; [`dup`] stays
+    /// the primitive behind [`PipeFd::into_inheritable`], which wants the opposite.
+    ///
+    /// [`dup`]: PipeImplBase::dup
+    fn dup_cloexec<T: PipeFdType>(fd: impl AsPipeFd<T>) -> PipeResult<PipeFd<T>>;
+
+    /// Toggles `fd` in or out of non-blocking mode after creation - unlike
+    /// [`CreatePipeOptions::nonblocking_read`]/[`CreatePipeOptions::nonblocking_write`], which
+    /// only apply at creation time, this lets a caller flip a pipe it already has its hands on
+    /// (e.g. one just received over a [`crate::channel`]). On unix this toggles `O_NONBLOCK` via
+    /// `fcntl`. Anonymous pipes on Windows have no non-blocking read/write mode to toggle (see
+    /// the caveat on [`PipeImplBase::create_pipe_with`]), so this is a no-op there; use
+    /// [`crate::wait_readable`]/[`poll`] to avoid blocking instead.
+    fn set_nonblocking<T: PipeFdType>(fd: impl AsPipeFd<T>, nonblocking: bool) -> PipeResult<()>;
+
+    /// Creates a pair of connected, full-duplex endpoints. Each endpoint can be both read from
+    /// and written to, unlike the two ends of a [`unidirectional::pipe`]. Uses `socketpair(2)`
+    /// on unix and a duplex named pipe on Windows.
+    fn create_duplex_pair() -> Result<(PipeFd<PipeDuplex>, PipeFd<PipeDuplex>), PipeError>;
+
+    /// Creates an addressable, full-duplex endpoint named `name` and blocks until a peer
+    /// connects to it via [`PipeImplBase::connect_named_pipe`]. Unlike
+    /// [`PipeImplBase::create_duplex_pair`], the two endpoints don't need to be created by the
+    /// same process or inherited across a `fork`/`exec` - this is for a process that wants to be
+    /// found by name instead, e.g. a plugin or daemon started independently of its caller. Backed
+    /// by a named pipe (`CreateNamedPipeW`) on Windows and a Unix domain socket on unix.
+    fn create_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError>;
+
+    /// Connects to a named pipe previously created with [`PipeImplBase::create_named_pipe`].
+    fn connect_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError>;
+
+    /// Creates an addressable, unidirectional read endpoint named `name` and blocks until a peer
+    /// opens the other end for writing via [`PipeImplBase::connect_named_pipe_writer`]. Unlike
+    /// [`PipeImplBase::create_named_pipe`], the two ends aren't interchangeable - this one can
+    /// only be read from - but the rendezvous is the same: the peer doesn't need to be spawned as
+    /// a child or inherit a handle, it only needs to know `name`. Backed by a `mkfifo(3)` FIFO
+    /// opened `O_RDONLY` on unix, and a `CreateNamedPipeW` pipe with `PIPE_ACCESS_INBOUND` on
+    /// Windows.
+    fn create_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError>;
+
+    /// Connects to the write end of a named pipe previously created with
+    /// [`PipeImplBase::create_named_pipe_reader`].
+    fn connect_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError>;
+
+    /// Like [`PipeImplBase::create_named_pipe_reader`], but this side takes the write end,
+    /// blocking until a peer connects for reading via [`PipeImplBase::connect_named_pipe_reader`].
+    fn create_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError>;
+
+    /// Connects to the read end of a named pipe previously created with
+    /// [`PipeImplBase::create_named_pipe_writer`].
+    fn connect_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError>;
+
     const INVALID_FD_VALUE: NativeFd;
 }
 
+/// The pair of ends produced by [`PipeImplBase::create_pipe`]/[`PipeImplBase::create_pipe_with`].
+///
+/// Each end is a distinct, independently-owned, independently-serializable [`PipeFd`] - a
+/// `PipeFd<PipeRead>` only implements [`std::io::Read`]-adjacent operations and a
+/// `PipeFd<PipeWrite>` only write ones, so there's no struct field holding both handles together
+/// that a serializer could skip or a caller could forget to hand to the other process. [`pipe`]
+/// destructures this into the `(PipeFd<PipeRead>, PipeFd<PipeWrite>)` tuple callers actually use.
+///
+/// [`pipe`]: crate::unidirectional::pipe
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub(crate) struct OsPipe {
     read_fd: PipeFd<PipeRead>,
@@ -70,6 +366,17 @@ impl PipeFd<PipeRead> {
     pub fn stdin() -> PipeFd<PipeRead> {
         unsafe { PipeFd::from_raw_pipe_fd(0) }
     }
+
+    /// Reads from the pipe, giving up with [`PipeError::timed_out`] if no data (or EOF) arrives
+    /// within `timeout` rather than blocking indefinitely. See [`PipeImplBase::read_timeout`] for
+    /// the platform-specific caveats.
+    pub fn read_timeout(
+        &self,
+        buf: &mut [u8],
+        timeout: std::time::Duration,
+    ) -> Result<usize, PipeError> {
+        sys::PipeImpl::read_timeout(self, buf, timeout)
+    }
 }
 
 impl PipeFd<PipeWrite> {
@@ -89,10 +396,24 @@ impl PipeFd<PipeWrite> {
     }
 }
 
+impl PipeFd<PipeDuplex> {
+    /// Creates a new `OwningDuplexStream` from the given pipe file descriptor.
+    pub fn into_duplex_stream(self) -> Result<io::OwningDuplexStream, PipeError> {
+        io::OwningDuplexStream::new(self)
+    }
+}
+
 impl<T: PipeFdType> PipeFd<T> {
-    /// Duplicates the current pipe file descriptor.
+    /// Duplicates the current pipe file descriptor, preserving close-on-exec so the duplicate
+    /// doesn't leak into a child process unless it's explicitly made inheritable afterwards.
     pub fn try_clone(&self) -> Result<PipeFd<T>, PipeError> {
-        sys::PipeImpl::dup(self)
+        sys::PipeImpl::dup_cloexec(self)
+    }
+
+    /// Toggles this pipe end in or out of non-blocking mode. See
+    /// [`PipeImplBase::set_nonblocking`] for the platform-specific caveats.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), PipeError> {
+        sys::PipeImpl::set_nonblocking(self, nonblocking)
     }
 }
 
@@ -105,6 +426,7 @@ impl<T: PipeFdType> std::fmt::Debug for PipeFd<T> {
         match T::TYPE {
             PipeFdTypeEnum::Read => write!(f, "PipeFd::Read({})", fd),
             PipeFdTypeEnum::Write => write!(f, "PipeFd::Write({})", fd),
+            PipeFdTypeEnum::Duplex => write!(f, "PipeFd::Duplex({})", fd),
             PipeFdTypeEnum::Unknown => write!(f, "PipeFd::Unknown({})", fd),
         }
     }
@@ -238,6 +560,12 @@ impl AsPipeFd<PipeWrite> for PipeWriter<'_> {
         self.fd
     }
 }
+impl AsPipeFd<PipeDuplex> for io::DuplexStream<'_> {
+    #[inline]
+    fn as_pipe_fd(&self) -> &PipeFd<PipeDuplex> {
+        self.fd
+    }
+}
 impl AsPipeFd<PipeRead> for OsPipe {
     #[inline]
     fn as_pipe_fd(&self) -> &PipeFd<PipeRead> {
@@ -258,6 +586,19 @@ impl<T: PipeFdType, F: AsPipeFd<T>> AsPipeFd<T> for &F {
     }
 }
 
+/// Converts a pipe end into a [`std::process::Stdio`], transferring ownership of the underlying
+/// descriptor so it can be wired straight into a spawned [`std::process::Command`]'s stdin,
+/// stdout or stderr instead of shuttling bytes through a relay thread.
+///
+/// Windows already has its own `From<PipeFd<T>> for Stdio` next to its other raw-handle
+/// conversions in `sys`; this covers the unix side so both platforms get the conversion.
+#[cfg(unix)]
+impl<T: PipeFdType> From<PipeFd<T>> for std::process::Stdio {
+    fn from(fd: PipeFd<T>) -> Self {
+        unsafe { std::process::Stdio::from_raw_fd(fd.as_raw_fd()) }
+    }
+}
+
 impl<T: PipeFdType> std::fmt::Display for PipeFd<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let fd = unsafe { self.as_raw_pipe_fd() };
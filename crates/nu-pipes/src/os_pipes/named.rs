@@ -0,0 +1,76 @@
+//! Named, addressable pipe endpoints, for connecting two processes that don't share an
+//! inherited handle - e.g. a plugin or daemon started independently of whoever wants to talk to
+//! it, rather than spawned as its child.
+use crate::{
+    duplex::PipeDuplex,
+    io::OwningDuplexStream,
+    os_pipes::{sys, PipeImplBase},
+    unidirectional::{PipeRead, PipeWrite},
+    PipeError, PipeFd,
+};
+
+/// Creates a named pipe server at `name` and blocks until a peer connects to it via
+/// [`connect_named_pipe`], returning a full-duplex endpoint to talk to it over.
+///
+/// `name` is a pipe name on Windows (exposed as `\\.\pipe\<name>`) and a filesystem path on
+/// unix (a Unix domain socket is bound there); a stale file left over at that path from a
+/// previous, uncleanly-terminated run is removed before binding.
+pub fn create_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError> {
+    sys::PipeImpl::create_named_pipe(name)
+}
+
+/// Connects to a named pipe server previously created with [`create_named_pipe`], returning a
+/// full-duplex endpoint to talk to it over. See [`create_named_pipe`] for what `name` means.
+pub fn connect_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError> {
+    sys::PipeImpl::connect_named_pipe(name)
+}
+
+/// Creates a named, unidirectional read endpoint at `name` and blocks until a peer opens the
+/// same name for writing via [`connect_named_pipe_writer`] - for a caller that wants
+/// [`pipe`](super::unidirectional::pipe)'s one-way byte stream, but whose peer can't inherit a
+/// file descriptor (e.g. a plugin spawned under a sandbox or a wrapper shell that scrubs
+/// inherited fds), and so has to be told a path to open instead.
+///
+/// `name` is a pipe name on Windows (exposed as `\\.\pipe\<name>`) and a filesystem path on unix
+/// (a FIFO is created there via `mkfifo(2)`); a stale FIFO left over at that path from a
+/// previous, uncleanly-terminated run is reused rather than treated as an error.
+pub fn create_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError> {
+    sys::PipeImpl::create_named_pipe_reader(name)
+}
+
+/// Connects to the write end of a named pipe previously created with
+/// [`create_named_pipe_reader`]. See that function for what `name` means.
+pub fn connect_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError> {
+    sys::PipeImpl::connect_named_pipe_writer(name)
+}
+
+/// Like [`create_named_pipe_reader`], but this side takes the write end, blocking until a peer
+/// connects for reading via [`connect_named_pipe_reader`].
+pub fn create_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError> {
+    sys::PipeImpl::create_named_pipe_writer(name)
+}
+
+/// Connects to the read end of a named pipe previously created with
+/// [`create_named_pipe_writer`]. See that function for what `name` means.
+pub fn connect_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError> {
+    sys::PipeImpl::connect_named_pipe_reader(name)
+}
+
+/// A `bind`/`connect`-shaped entry point over [`create_named_pipe`]/[`connect_named_pipe`], for
+/// callers used to a listener/client API rather than naming both sides of the handshake
+/// explicitly. Both sides get an [`OwningDuplexStream`] - already `Read` + `Write` - so code
+/// written against a plain pipe doesn't need to change to talk to one of these instead.
+pub struct NamedPipe;
+
+impl NamedPipe {
+    /// Binds a named pipe server at `name` and blocks until a client [`NamedPipe::connect`]s.
+    /// See [`create_named_pipe`] for what `name` means.
+    pub fn bind(name: &str) -> Result<OwningDuplexStream, PipeError> {
+        create_named_pipe(name)?.into_duplex_stream()
+    }
+
+    /// Connects to a named pipe server previously bound with [`NamedPipe::bind`].
+    pub fn connect(name: &str) -> Result<OwningDuplexStream, PipeError> {
+        connect_named_pipe(name)?.into_duplex_stream()
+    }
+}
@@ -0,0 +1,296 @@
+//! A buffered pipe reader backed by a "magic"/virtual ring buffer, so a read that logically wraps
+//! around the end of the buffer still hands out one contiguous slice instead of forcing a
+//! copy/compaction the way [`std::io::BufReader`] does.
+//!
+//! The trick (the same one the `vmap` crate uses): allocate a buffer whose size is a multiple of
+//! the page size, back it with an anonymous file (`memfd_create` on Linux), and map that file
+//! twice into adjacent virtual address ranges so the second mapping mirrors the first byte for
+//! byte. Reads and writes into the physical region never need special-casing for the wrap -
+//! indexing `cap` bytes past the start of the "real" copy just lands on the mirror, which the
+//! kernel keeps byte-identical to the original automatically.
+//!
+//! This only pays off on platforms where the double-map trick is available; elsewhere (and if the
+//! mapping fails for any reason, e.g. no `memfd_create`) [`RingPipeReader`] transparently falls
+//! back to a plain buffer, refilled from its start once drained, so callers never have to care which strategy backed a given
+//! instance.
+
+use std::io::{BufRead, Read};
+
+use crate::{
+    errors::PipeError,
+    io::CloseOwningError,
+    unidirectional::{PipeRead, RawPipeReader},
+    PipeFd, PIPE_BUFFER_CAPACITY,
+};
+
+/// A double-mapped ring buffer: `cap` bytes of real storage, mapped twice back to back so any
+/// `cap`-byte window starting anywhere in `[0, cap)` is contiguous in the process's address space.
+#[cfg(target_os = "linux")]
+struct MirroredBuffer {
+    ptr: *mut u8,
+    cap: usize,
+}
+
+#[cfg(target_os = "linux")]
+impl MirroredBuffer {
+    /// Allocates a mirrored buffer of at least `min_cap` bytes, rounded up to a whole number of
+    /// pages. Returns `None` if the double-map trick isn't available (e.g. `memfd_create` or the
+    /// fixed second mapping fails) so the caller can fall back to a plain buffer instead.
+    fn new(min_cap: usize) -> Option<Self> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let cap = min_cap.div_ceil(page_size) * page_size;
+
+        unsafe {
+            let name = b"nu-pipes-ring\0";
+            let memfd = libc::memfd_create(name.as_ptr() as *const libc::c_char, 0);
+            if memfd < 0 {
+                return None;
+            }
+
+            let result = Self::map_mirrored(memfd, cap);
+            libc::close(memfd);
+            result
+        }
+    }
+
+    /// Reserves `2 * cap` bytes of address space, then maps `memfd` (already sized to `cap`
+    /// bytes via `ftruncate`) into both halves, so the second half mirrors the first.
+    unsafe fn map_mirrored(memfd: libc::c_int, cap: usize) -> Option<Self> {
+        if libc::ftruncate(memfd, cap as libc::off_t) != 0 {
+            return None;
+        }
+
+        let reservation = libc::mmap(
+            std::ptr::null_mut(),
+            cap * 2,
+            libc::PROT_NONE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        if reservation == libc::MAP_FAILED {
+            return None;
+        }
+
+        let first = libc::mmap(
+            reservation,
+            cap,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_FIXED,
+            memfd,
+            0,
+        );
+        if first == libc::MAP_FAILED {
+            libc::munmap(reservation, cap * 2);
+            return None;
+        }
+
+        let second = libc::mmap(
+            (reservation as usize + cap) as *mut libc::c_void,
+            cap,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_FIXED,
+            memfd,
+            0,
+        );
+        if second == libc::MAP_FAILED {
+            libc::munmap(reservation, cap * 2);
+            return None;
+        }
+
+        Some(Self {
+            ptr: reservation as *mut u8,
+            cap,
+        })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.cap * 2) }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.cap * 2) }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for MirroredBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut libc::c_void, self.cap * 2);
+        }
+    }
+}
+
+// SAFETY: the mapping is only ever accessed through `&self`/`&mut self` on `MirroredBuffer`,
+// which already enforces Rust's aliasing rules on the pointer.
+#[cfg(target_os = "linux")]
+unsafe impl Send for MirroredBuffer {}
+
+enum Storage {
+    /// Backed by a double-mapped ring buffer; `head`/`tail` are unwrapped byte counters, at most
+    /// `cap` apart; both are reduced by `cap` on the next fill once `head` has reached `cap`.
+    #[cfg(target_os = "linux")]
+    Mirrored {
+        buf: MirroredBuffer,
+        head: usize,
+        tail: usize,
+    },
+    /// A plain buffer that is refilled from its start once fully consumed, instead of wrapping -
+    /// used whenever the mirrored mapping isn't available.
+    Linear {
+        buf: Box<[u8]>,
+        pos: usize,
+        filled: usize,
+    },
+}
+
+/// A buffered [`Read`]/[`BufRead`] reader over a [`PipeFd<PipeRead>`], backed by a mirrored ring
+/// buffer where the platform supports it (see the module docs), and a plain compacting buffer
+/// otherwise. Implements `Read`/`BufRead` the same way whichever backing this ended up using, so
+/// code generic over those traits can take it in place of [`crate::io::OwningPipeReader`]; the
+/// `stream`/`stream_framed` iterators of `OwningPipeReader` are not provided here.
+pub struct RingPipeReader {
+    fd: PipeFd<PipeRead>,
+    storage: Storage,
+}
+
+impl RingPipeReader {
+    /// Creates a new `RingPipeReader` reading from `fd`, with a ring buffer sized to at least
+    /// [`PIPE_BUFFER_CAPACITY`] bytes (rounded up to a whole number of pages on platforms that
+    /// support the mirrored mapping).
+    pub fn new(fd: PipeFd<PipeRead>) -> Self {
+        Self::with_capacity(fd, PIPE_BUFFER_CAPACITY)
+    }
+
+    /// Like [`RingPipeReader::new`], but with an explicit minimum buffer capacity.
+    pub fn with_capacity(fd: PipeFd<PipeRead>, capacity: usize) -> Self {
+        #[cfg(target_os = "linux")]
+        let storage = match MirroredBuffer::new(capacity) {
+            Some(buf) => Storage::Mirrored {
+                buf,
+                head: 0,
+                tail: 0,
+            },
+            None => Storage::Linear {
+                buf: vec![0u8; capacity].into_boxed_slice(),
+                pos: 0,
+                filled: 0,
+            },
+        };
+
+        #[cfg(not(target_os = "linux"))]
+        let storage = Storage::Linear {
+            buf: vec![0u8; capacity].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+        };
+
+        Self { fd, storage }
+    }
+
+    /// Returns a reference to the underlying pipe file descriptor.
+    pub fn fd(&self) -> &PipeFd<PipeRead> {
+        &self.fd
+    }
+
+    /// Closes the `RingPipeReader` and releases the underlying file descriptor.
+    pub fn close(self) -> Result<(), CloseOwningError<RingPipeReader, PipeError>> {
+        let RingPipeReader { fd, storage } = self;
+
+        match fd.close() {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let (err, fd) = e.into_parts();
+                Err(CloseOwningError::new(err, RingPipeReader { fd, storage }))
+            }
+        }
+    }
+
+    /// Consumes the `RingPipeReader` and returns the underlying pipe file descriptor.
+    pub fn into_inner(self) -> PipeFd<PipeRead> {
+        self.fd
+    }
+
+    fn fill_more(&mut self) -> std::io::Result<()> {
+        let mut reader = RawPipeReader(&self.fd);
+
+        match &mut self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Mirrored { buf, head, tail } => {
+                let cap = buf.cap;
+                let avail = *tail - *head;
+                let space = cap - avail;
+                if space == 0 {
+                    return Ok(());
+                }
+
+                let tail_phys = *tail % cap;
+                let n = reader.read(&mut buf.as_mut_slice()[tail_phys..tail_phys + space])?;
+                *tail += n;
+
+                // Keep the counters small instead of letting them grow for the life of the
+                // reader; the physical offset (`% cap`) is unaffected by subtracting `cap` from
+                // both ends evenly.
+                if *head >= cap {
+                    *head -= cap;
+                    *tail -= cap;
+                }
+            }
+            Storage::Linear { buf, pos, filled } => {
+                if *pos == *filled {
+                    *pos = 0;
+                    *filled = 0;
+                }
+                if *filled == buf.len() {
+                    return Ok(());
+                }
+
+                let n = reader.read(&mut buf[*filled..])?;
+                *filled += n;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Read for RingPipeReader {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(out.len());
+        out[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for RingPipeReader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        let is_empty = match &self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Mirrored { head, tail, .. } => head == tail,
+            Storage::Linear { pos, filled, .. } => pos == filled,
+        };
+        if is_empty {
+            self.fill_more()?;
+        }
+
+        Ok(match &self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Mirrored { buf, head, tail } => {
+                let head_phys = *head % buf.cap;
+                &buf.as_slice()[head_phys..head_phys + (*tail - *head)]
+            }
+            Storage::Linear { buf, pos, filled } => &buf[*pos..*filled],
+        })
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match &mut self.storage {
+            #[cfg(target_os = "linux")]
+            Storage::Mirrored { head, .. } => *head += amt,
+            Storage::Linear { pos, .. } => *pos += amt,
+        }
+    }
+}
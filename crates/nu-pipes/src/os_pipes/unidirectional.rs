@@ -2,7 +2,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    os_pipes::{sys, PipeImplBase},
+    os_pipes::{sys, CreatePipeOptions, PipeImplBase},
     AsPipeFd, PipeError, PipeFd,
 };
 
@@ -14,6 +14,17 @@ pub fn pipe() -> Result<(PipeFd<PipeRead>, PipeFd<PipeWrite>), PipeError> {
     Ok((pipe.read_fd, pipe.write_fd))
 }
 
+/// Like [`pipe`], but `options` selects which end(s) a spawned child process is allowed to
+/// inherit - by default (and with [`pipe`]) neither end is inheritable, so a descriptor is never
+/// leaked into every `exec`'d child by accident.
+pub fn pipe_with(
+    options: CreatePipeOptions,
+) -> Result<(PipeFd<PipeRead>, PipeFd<PipeWrite>), PipeError> {
+    let pipe = sys::PipeImpl::create_pipe_with(options)?;
+
+    Ok((pipe.read_fd, pipe.write_fd))
+}
+
 pub(crate) struct RawPipeReader<T: AsPipeFd<PipeRead>>(pub(crate) T);
 pub(crate) struct RawPipeWriter<T: AsPipeFd<PipeWrite>>(pub(crate) T);
 
@@ -21,12 +32,28 @@ impl<T: AsPipeFd<PipeRead>> std::io::Read for RawPipeReader<T> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         Ok(sys::PipeImpl::read(&self.0, buf)?)
     }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        Ok(sys::PipeImpl::read_vectored(&self.0, bufs)?)
+    }
+
+    fn is_read_vectored(&self) -> bool {
+        true
+    }
 }
 impl<T: AsPipeFd<PipeWrite>> std::io::Write for RawPipeWriter<T> {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         Ok(sys::PipeImpl::write(&self.0, buf)?)
     }
 
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        Ok(sys::PipeImpl::write_vectored(&self.0, bufs)?)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
     fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
@@ -57,6 +84,8 @@ pub enum PipeFdTypeEnum {
     Read,
     /// Write end of the pipe.
     Write,
+    /// One endpoint of a full-duplex pipe, which can be both read from and written to.
+    Duplex,
     /// Unknown type of pipe.
     Unknown,
 }
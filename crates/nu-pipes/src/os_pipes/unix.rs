@@ -1,11 +1,19 @@
+use std::os::unix::{
+    io::IntoRawFd,
+    net::{UnixListener, UnixStream},
+};
+use std::time::Duration;
+
 use crate::{
+    cancel::CancelToken,
+    duplex::PipeDuplex,
     errors::PipeResult,
     libc_call, trace_pipe,
     unidirectional::{PipeFdType, PipeRead, PipeWrite},
     AsNativeFd, AsPipeFd, PipeFd,
 };
 
-use super::{IntoPipeFd, OsPipe, PipeError, PipeImplBase};
+use super::{CreatePipeOptions, IntoPipeFd, OsPipe, PipeError, PipeImplBase};
 
 pub type OSError = std::io::Error;
 pub type NativeFd = libc::c_int;
@@ -14,6 +22,12 @@ pub(crate) struct PipeImpl {}
 
 impl PipeImplBase for PipeImpl {
     fn create_pipe() -> Result<OsPipe, PipeError> {
+        Self::create_pipe_with(CreatePipeOptions::new())
+    }
+
+    fn create_pipe_with(options: CreatePipeOptions) -> Result<OsPipe, PipeError> {
+        ignore_sigpipe();
+
         let mut fds = [0i32; 2];
         cfg_if::cfg_if! {
             if #[cfg(any(
@@ -34,6 +48,22 @@ impl PipeImplBase for PipeImpl {
             }
         }
 
+        // Both ends were just created close-on-exec; clear FD_CLOEXEC on whichever end(s)
+        // `options` asks to hand down to a spawned child.
+        if options.inheritable_read {
+            libc_call!(libc::fcntl(fds[0], libc::F_SETFD, 0))?;
+        }
+        if options.inheritable_write {
+            libc_call!(libc::fcntl(fds[1], libc::F_SETFD, 0))?;
+        }
+
+        if options.nonblocking_read {
+            set_nonblocking(fds[0])?;
+        }
+        if options.nonblocking_write {
+            set_nonblocking(fds[1])?;
+        }
+
         Ok(OsPipe {
             read_fd: unsafe { fds[0].into_pipe_fd() },
             write_fd: unsafe { fds[1].into_pipe_fd() },
@@ -53,16 +83,140 @@ impl PipeImplBase for PipeImpl {
             fd.as_pipe_fd().native_fd(),
             buf.as_mut_ptr() as *mut _,
             buf.len(),
-        ))?;
+        ))
+        .map_err(translate_would_block)?;
 
         trace_pipe!("read {} bytes", bytes_read);
 
         Ok(bytes_read as usize)
     }
 
+    fn read_vectored(
+        fd: impl AsPipeFd<PipeRead>,
+        bufs: &mut [std::io::IoSliceMut<'_>],
+    ) -> PipeResult<usize> {
+        trace_pipe!("readv {} buffers from {:?}", bufs.len(), fd.as_pipe_fd());
+
+        let iov: Vec<libc::iovec> = bufs
+            .iter_mut()
+            .map(|buf| libc::iovec {
+                iov_base: buf.as_mut_ptr() as *mut _,
+                iov_len: buf.len(),
+            })
+            .collect();
+
+        let bytes_read = libc_call!(libc::readv(
+            fd.as_pipe_fd().native_fd(),
+            iov.as_ptr(),
+            iov.len() as libc::c_int,
+        ))
+        .map_err(translate_would_block)?;
+
+        trace_pipe!("readv read {} bytes", bytes_read);
+
+        Ok(bytes_read as usize)
+    }
+
+    fn read_timeout(
+        fd: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> PipeResult<usize> {
+        trace_pipe!("polling {:?} for up to {:?}", fd.as_pipe_fd(), timeout);
+
+        let mut pollfd = libc::pollfd {
+            fd: unsafe { fd.as_pipe_fd().native_fd() },
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout_ms = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+
+        let ready = libc_call!(libc::poll(&mut pollfd, 1, timeout_ms))?;
+
+        if ready == 0 {
+            trace_pipe!("timed out waiting for data on {:?}", fd.as_pipe_fd());
+            return Err(PipeError::timed_out());
+        }
+
+        Self::read(fd, buf)
+    }
+
+    fn read_cancellable(
+        fd: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        cancel: &CancelToken,
+    ) -> PipeResult<usize> {
+        poll_cancellable(fd, buf, cancel, None)
+    }
+
+    fn read_cancellable_timeout(
+        fd: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        cancel: &CancelToken,
+        timeout: Duration,
+    ) -> PipeResult<usize> {
+        poll_cancellable(fd, buf, cancel, Some(timeout))
+    }
+
     fn write(fd: impl AsPipeFd<PipeWrite>, buf: &[u8]) -> PipeResult<usize> {
         trace_pipe!("writing {:?} bytes to {:?}", buf.len(), fd.as_pipe_fd());
 
+        let written = libc_call!(libc::write(
+            fd.as_pipe_fd().native_fd(),
+            buf.as_ptr() as *const _,
+            buf.len(),
+        ))
+        .map_err(translate_would_block)
+        .map_err(translate_broken_pipe)?;
+
+        trace_pipe!("wrote {} bytes", written);
+
+        Ok(written as usize)
+    }
+
+    fn write_vectored(
+        fd: impl AsPipeFd<PipeWrite>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> PipeResult<usize> {
+        trace_pipe!("writev {} buffers to {:?}", bufs.len(), fd.as_pipe_fd());
+
+        let iov: Vec<libc::iovec> = bufs
+            .iter()
+            .map(|buf| libc::iovec {
+                iov_base: buf.as_ptr() as *mut _,
+                iov_len: buf.len(),
+            })
+            .collect();
+
+        let written = libc_call!(libc::writev(
+            fd.as_pipe_fd().native_fd(),
+            iov.as_ptr(),
+            iov.len() as libc::c_int,
+        ))
+        .map_err(translate_would_block)
+        .map_err(translate_broken_pipe)?;
+
+        trace_pipe!("writev wrote {} bytes", written);
+
+        Ok(written as usize)
+    }
+
+    fn read_duplex(fd: impl AsPipeFd<PipeDuplex>, buf: &mut [u8]) -> PipeResult<usize> {
+        trace_pipe!("reading {:?} bytes from {:?}", buf.len(), fd.as_pipe_fd());
+        let bytes_read = libc_call!(libc::read(
+            fd.as_pipe_fd().native_fd(),
+            buf.as_mut_ptr() as *mut _,
+            buf.len(),
+        ))?;
+
+        trace_pipe!("read {} bytes", bytes_read);
+
+        Ok(bytes_read as usize)
+    }
+
+    fn write_duplex(fd: impl AsPipeFd<PipeDuplex>, buf: &[u8]) -> PipeResult<usize> {
+        trace_pipe!("writing {:?} bytes to {:?}", buf.len(), fd.as_pipe_fd());
+
         let written = libc_call!(libc::write(
             fd.as_pipe_fd().native_fd(),
             buf.as_ptr() as *const _,
@@ -82,9 +236,305 @@ impl PipeImplBase for PipeImpl {
         Ok(dup_fd)
     }
 
+    fn set_nonblocking<T: PipeFdType>(
+        fd: impl AsPipeFd<T>,
+        nonblocking: bool,
+    ) -> Result<(), PipeError> {
+        let native_fd = unsafe { fd.as_pipe_fd().native_fd() };
+        let flags = libc_call!(libc::fcntl(native_fd, libc::F_GETFL))?;
+        let flags = if nonblocking {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+        libc_call!(libc::fcntl(native_fd, libc::F_SETFL, flags))?;
+        Ok(())
+    }
+
+    fn dup_cloexec<T: PipeFdType>(fd: impl AsPipeFd<T>) -> Result<PipeFd<T>, PipeError> {
+        let duped = libc_call!(libc::fcntl(
+            fd.as_pipe_fd().native_fd(),
+            libc::F_DUPFD_CLOEXEC,
+            0
+        ))?;
+
+        let dup_fd = unsafe { PipeFd::from_raw_fd(duped) };
+        trace_pipe!("duplicated {:?} to {:?} (cloexec)", fd.as_pipe_fd(), dup_fd);
+        Ok(dup_fd)
+    }
+
+    fn create_duplex_pair() -> Result<(PipeFd<PipeDuplex>, PipeFd<PipeDuplex>), PipeError> {
+        let mut fds = [0i32; 2];
+        libc_call!(libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM,
+            0,
+            fds.as_mut_ptr(),
+        ))?;
+        libc_call!(libc::fcntl(fds[0], libc::F_SETFD, libc::FD_CLOEXEC))?;
+        libc_call!(libc::fcntl(fds[1], libc::F_SETFD, libc::FD_CLOEXEC))?;
+
+        trace_pipe!("created duplex pair ({}, {})", fds[0], fds[1]);
+
+        Ok(unsafe { (fds[0].into_pipe_fd(), fds[1].into_pipe_fd()) })
+    }
+
+    fn create_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError> {
+        trace_pipe!("Creating named pipe server at {}", name);
+
+        // A previous, uncleanly-terminated run may have left the socket file behind, in which
+        // case `bind` would fail with `EADDRINUSE` even though nothing is listening anymore.
+        let _ = std::fs::remove_file(name);
+
+        let listener = UnixListener::bind(name)?;
+        trace_pipe!("Waiting for a client to connect to {}", name);
+        let (stream, _) = listener.accept()?;
+
+        let fd = stream.into_raw_fd();
+        libc_call!(libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+        trace_pipe!("Client connected to {}", name);
+
+        Ok(unsafe { fd.into_pipe_fd() })
+    }
+
+    fn connect_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError> {
+        trace_pipe!("Connecting to named pipe {}", name);
+
+        let stream = UnixStream::connect(name)?;
+        let fd = stream.into_raw_fd();
+        libc_call!(libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+
+        Ok(unsafe { fd.into_pipe_fd() })
+    }
+
+    fn create_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError> {
+        trace_pipe!("Creating named pipe (FIFO) at {}", name);
+        make_fifo(name)?;
+
+        trace_pipe!("Waiting for a writer to open {}", name);
+        let fd = open_fifo(name, libc::O_RDONLY)?;
+        trace_pipe!("Writer connected to {}", name);
+
+        Ok(unsafe { fd.into_pipe_fd() })
+    }
+
+    fn connect_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError> {
+        trace_pipe!("Connecting to named pipe (FIFO) {} for writing", name);
+        let fd = open_fifo(name, libc::O_WRONLY)?;
+        trace_pipe!("Connected to {}", name);
+
+        Ok(unsafe { fd.into_pipe_fd() })
+    }
+
+    fn create_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError> {
+        trace_pipe!("Creating named pipe (FIFO) at {}", name);
+        make_fifo(name)?;
+
+        trace_pipe!("Waiting for a reader to open {}", name);
+        let fd = open_fifo(name, libc::O_WRONLY)?;
+        trace_pipe!("Reader connected to {}", name);
+
+        Ok(unsafe { fd.into_pipe_fd() })
+    }
+
+    fn connect_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError> {
+        trace_pipe!("Connecting to named pipe (FIFO) {} for reading", name);
+        let fd = open_fifo(name, libc::O_RDONLY)?;
+        trace_pipe!("Connected to {}", name);
+
+        Ok(unsafe { fd.into_pipe_fd() })
+    }
+
     const INVALID_FD_VALUE: NativeFd = -1;
 }
 
+/// Creates the FIFO at `name` if it doesn't already exist. A previous, uncleanly-terminated run
+/// may have left it behind, which is fine - `mkfifo` failing with `EEXIST` just means there's
+/// already one there to open.
+fn make_fifo(name: &str) -> Result<(), PipeError> {
+    let c_name = std::ffi::CString::new(name)
+        .map_err(|_| PipeError::os_error("named pipe path contains a nul byte"))?;
+
+    if unsafe { libc::mkfifo(c_name.as_ptr(), 0o600) } < 0 {
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::EEXIST) {
+            return Err(err.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens the FIFO at `name` with the given `libc::O_RDONLY`/`libc::O_WRONLY` mode. Blocks, as
+/// `open(2)` does for a FIFO, until a peer has opened the other end.
+fn open_fifo(name: &str, mode: libc::c_int) -> Result<NativeFd, PipeError> {
+    let c_name = std::ffi::CString::new(name)
+        .map_err(|_| PipeError::os_error("named pipe path contains a nul byte"))?;
+
+    let fd = libc_call!(libc::open(c_name.as_ptr(), mode | libc::O_CLOEXEC))?;
+
+    Ok(fd)
+}
+
+/// Sets `O_NONBLOCK` on `fd`, so a subsequent `read`/`write` returns `EAGAIN` instead of blocking
+/// when the pipe isn't ready, and [`wait_readable`] becomes the way to find out when it is.
+fn set_nonblocking(fd: NativeFd) -> Result<(), PipeError> {
+    let flags = libc_call!(libc::fcntl(fd, libc::F_GETFL))?;
+    libc_call!(libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK))?;
+    Ok(())
+}
+
+/// Maps the `EAGAIN`/`EWOULDBLOCK` a non-blocking `read`/`write` raises when the pipe isn't ready
+/// onto [`PipeError::would_block`], so callers can match on that instead of an opaque OS error.
+fn translate_would_block(err: PipeError) -> PipeError {
+    if err.kind == crate::errors::OSErrorKind::WouldBlock {
+        PipeError::would_block()
+    } else {
+        err
+    }
+}
+
+/// Maps the `EPIPE` a `write`/`writev` raises when the reading end has already been closed onto
+/// [`PipeError::broken_pipe`], so callers can match on [`PipeError::is_broken_pipe`] instead of
+/// an opaque OS error.
+fn translate_broken_pipe(err: PipeError) -> PipeError {
+    if err.kind == crate::errors::OSErrorKind::BrokenPipe {
+        PipeError::broken_pipe()
+    } else {
+        err
+    }
+}
+
+/// Ignores `SIGPIPE` the first time a pipe is created, so a `write`/`writev` into a pipe whose
+/// reader is gone returns `EPIPE` (translated to [`PipeError::broken_pipe`] by the call sites
+/// above) instead of killing the process with the default `SIGPIPE` disposition - matching how
+/// every other pipe operation in this crate surfaces a closed-reader as a normal error rather
+/// than terminating the writer outright.
+fn ignore_sigpipe() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_IGN);
+    });
+}
+
+/// Blocks until `fd` is ready to be read from (or `timeout` elapses), without performing the
+/// read itself. Meant for a reactor driving several non-blocking pipes from one thread: poll
+/// every registered fd for readiness, then only issue a `read` on the ones that are ready.
+pub(crate) fn wait_readable(
+    fd: impl AsPipeFd<PipeRead>,
+    timeout: Option<Duration>,
+) -> PipeResult<bool> {
+    let timeout_ms = match timeout {
+        Some(d) => i32::try_from(d.as_millis()).unwrap_or(i32::MAX),
+        None => -1,
+    };
+
+    let mut pollfd = libc::pollfd {
+        fd: unsafe { fd.as_pipe_fd().native_fd() },
+        events: libc::POLLIN,
+        revents: 0,
+    };
+
+    let ready = libc_call!(libc::poll(&mut pollfd, 1, timeout_ms))?;
+    Ok(ready > 0)
+}
+
+/// Backs [`super::poll`]: polls every registered [`super::PollFd`] in one `libc::poll` call and
+/// writes the observed readiness back into each one's `revents`.
+pub(crate) fn poll<T: PipeFdType>(
+    fds: &mut [super::PollFd<'_, T>],
+    timeout: Option<Duration>,
+) -> PipeResult<usize> {
+    let timeout_ms = match timeout {
+        Some(d) => i32::try_from(d.as_millis()).unwrap_or(i32::MAX),
+        None => -1,
+    };
+
+    let mut pollfds: Vec<libc::pollfd> = fds
+        .iter()
+        .map(|pf| {
+            let mut events = 0;
+            if pf.interest.readable {
+                events |= libc::POLLIN;
+            }
+            if pf.interest.writable {
+                events |= libc::POLLOUT;
+            }
+            libc::pollfd {
+                fd: unsafe { pf.fd.as_pipe_fd().native_fd() },
+                events,
+                revents: 0,
+            }
+        })
+        .collect();
+
+    let ready = libc_call!(libc::poll(
+        pollfds.as_mut_ptr(),
+        pollfds.len() as libc::nfds_t,
+        timeout_ms
+    ))?;
+
+    for (pf, raw) in fds.iter_mut().zip(pollfds.iter()) {
+        // `POLLHUP`/`POLLERR` (the peer closed/broke the connection) also counts as "readable":
+        // a subsequent `read` observes EOF immediately rather than blocking, matching
+        // `wait_readable`'s treatment of a broken pipe as ready.
+        pf.revents = super::Interest {
+            readable: raw.revents & (libc::POLLIN | libc::POLLHUP | libc::POLLERR) != 0,
+            writable: raw.revents & libc::POLLOUT != 0,
+        };
+    }
+
+    Ok(ready as usize)
+}
+
+/// Backs [`PipeImplBase::read_cancellable`]/[`PipeImplBase::read_cancellable_timeout`]: `poll`s
+/// `fd` and `cancel`'s control fd together, returning [`PipeError::cancelled`] if the control fd
+/// becomes readable first, or [`PipeError::timed_out`] if `timeout` elapses before either does.
+fn poll_cancellable(
+    fd: impl AsPipeFd<PipeRead>,
+    buf: &mut [u8],
+    cancel: &CancelToken,
+    timeout: Option<Duration>,
+) -> PipeResult<usize> {
+    trace_pipe!(
+        "polling {:?} (cancellable, timeout {:?}) for data",
+        fd.as_pipe_fd(),
+        timeout
+    );
+
+    let timeout_ms = match timeout {
+        Some(d) => i32::try_from(d.as_millis()).unwrap_or(i32::MAX),
+        None => -1,
+    };
+
+    let mut pollfds = [
+        libc::pollfd {
+            fd: unsafe { fd.as_pipe_fd().native_fd() },
+            events: libc::POLLIN,
+            revents: 0,
+        },
+        libc::pollfd {
+            fd: unsafe { cancel.control_fd().native_fd() },
+            events: libc::POLLIN,
+            revents: 0,
+        },
+    ];
+
+    let ready = libc_call!(libc::poll(pollfds.as_mut_ptr(), 2, timeout_ms))?;
+
+    if ready == 0 {
+        trace_pipe!("timed out waiting for data on {:?}", fd.as_pipe_fd());
+        return Err(PipeError::timed_out());
+    }
+
+    if pollfds[1].revents & libc::POLLIN != 0 {
+        trace_pipe!("cancelled while waiting for data on {:?}", fd.as_pipe_fd());
+        return Err(PipeError::cancelled());
+    }
+
+    PipeImpl::read(fd, buf)
+}
+
 impl<T: PipeFdType> IntoPipeFd<T> for NativeFd {
     unsafe fn into_pipe_fd(self) -> PipeFd<T> {
         PipeFd::from_raw_fd(self)
@@ -120,7 +570,7 @@ mod test {
     }
 
     #[test]
-    fn duplicating_pipe_fd_doesnt_preserve_cloexec() {
+    fn cloning_pipe_fd_preserves_cloexec() {
         let (read, write) = pipe().unwrap();
 
         let dup_read = read.try_clone().unwrap();
@@ -131,8 +581,8 @@ mod test {
         let dup_write_flags =
             unsafe { libc::fcntl(dup_write.as_pipe_fd().native_fd(), libc::F_GETFD) };
 
-        assert!(dup_read_flags.isnt(libc::FD_CLOEXEC));
-        assert!(dup_write_flags.isnt(libc::FD_CLOEXEC));
+        assert!(dup_read_flags.is(libc::FD_CLOEXEC));
+        assert!(dup_write_flags.is(libc::FD_CLOEXEC));
     }
     #[test]
     fn duplicating_pipe_fd_creates_new_fd() {
@@ -144,4 +594,18 @@ mod test {
         assert_ne!(read, dup_read);
         assert_ne!(write, dup_write);
     }
+
+    #[test]
+    fn into_inheritable_clears_cloexec() {
+        let (read, write) = pipe().unwrap();
+
+        let read = read.into_inheritable().unwrap();
+        let write = write.into_inheritable().unwrap();
+
+        let read_flags = unsafe { libc::fcntl(read.as_pipe_fd().native_fd(), libc::F_GETFD) };
+        let write_flags = unsafe { libc::fcntl(write.as_pipe_fd().native_fd(), libc::F_GETFD) };
+
+        assert!(read_flags.isnt(libc::FD_CLOEXEC));
+        assert!(write_flags.isnt(libc::FD_CLOEXEC));
+    }
 }
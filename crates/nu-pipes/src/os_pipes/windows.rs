@@ -1,27 +1,47 @@
 use std::{
     os::windows::io::{AsRawHandle, FromRawHandle, RawHandle},
     process::Stdio,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
 };
 
 use serde::{Deserialize, Serialize};
+use windows::core::PCWSTR;
 use windows::Win32::{
     Foundation::{
-        CloseHandle, DuplicateHandle, BOOL, DUPLICATE_SAME_ACCESS, ERROR_BROKEN_PIPE, HANDLE,
-        INVALID_HANDLE_VALUE,
+        CloseHandle, DuplicateHandle, BOOL, DUPLICATE_SAME_ACCESS, ERROR_BROKEN_PIPE,
+        ERROR_IO_PENDING, ERROR_PIPE_CONNECTED, HANDLE, HANDLE_FLAG_INHERIT, INVALID_HANDLE_VALUE,
+        WAIT_OBJECT_0, WAIT_TIMEOUT,
     },
     Security::SECURITY_ATTRIBUTES,
-    Storage::FileSystem::{ReadFile, WriteFile},
-    System::{Pipes::CreatePipe, Threading::GetCurrentProcess},
+    Storage::FileSystem::{
+        CreateFileW, ReadFile, WriteFile, FILE_FLAGS_AND_ATTRIBUTES, FILE_GENERIC_READ,
+        FILE_GENERIC_WRITE, FILE_SHARE_MODE, OPEN_EXISTING,
+    },
+    System::{
+        Pipes::{
+            ConnectNamedPipe, CreateNamedPipeW, CreatePipe, PeekNamedPipe, WaitNamedPipeW,
+            NMPWAIT_USE_DEFAULT_WAIT, PIPE_ACCESS_DUPLEX, PIPE_ACCESS_INBOUND,
+            PIPE_ACCESS_OUTBOUND, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE, PIPE_WAIT,
+        },
+        Threading::{
+            CreateEventW, GetCurrentProcess, SetHandleInformation, WaitForMultipleObjects,
+            WaitForSingleObject,
+        },
+        IO::{CancelIoEx, GetOverlappedResult, OVERLAPPED},
+    },
 };
 
 use crate::{
+    cancel::CancelToken,
+    duplex::PipeDuplex,
     trace_pipe,
     unidirectional::{PipeFdType, PipeRead, PipeWrite},
     AsNativeFd, AsPipeFd, AsRawPipeFd, FromNativeFd, FromRawPipeFd, OsPipe, PipeFd, PipeResult,
-    RawPipeFd,
+    RawPipeFd, PIPE_BUFFER_CAPACITY,
 };
 
-use super::{PipeError, PipeImplBase};
+use super::{CreatePipeOptions, PipeError, PipeImplBase};
 
 pub type NativeFd = windows::Win32::Foundation::HANDLE;
 
@@ -37,6 +57,10 @@ const DEFAULT_SECURITY_ATTRIBUTES: SECURITY_ATTRIBUTES = SECURITY_ATTRIBUTES {
 
 impl PipeImplBase for Win32PipeImpl {
     fn create_pipe() -> Result<OsPipe, PipeError> {
+        Self::create_pipe_with(CreatePipeOptions::new())
+    }
+
+    fn create_pipe_with(options: CreatePipeOptions) -> Result<OsPipe, PipeError> {
         trace_pipe!("Creating pipe");
 
         let mut read_fd = INVALID_HANDLE_VALUE;
@@ -51,6 +75,20 @@ impl PipeImplBase for Win32PipeImpl {
             )
         }?;
 
+        // Both ends are created non-inheritable via `DEFAULT_SECURITY_ATTRIBUTES`; opt whichever
+        // end(s) `options` asks for back in for a spawned child, leaving the other private.
+        if options.inheritable_read {
+            unsafe { SetHandleInformation(read_fd, HANDLE_FLAG_INHERIT, HANDLE_FLAG_INHERIT) }?;
+        }
+        if options.inheritable_write {
+            unsafe { SetHandleInformation(write_fd, HANDLE_FLAG_INHERIT, HANDLE_FLAG_INHERIT) }?;
+        }
+
+        // `options.nonblocking_{read,write}` has no effect here: anonymous pipes from
+        // `CreatePipe` don't support `PIPE_NOWAIT` (that's a named-pipe-only mode set via
+        // `SetNamedPipeHandleState`), so `read`/`write` on the handles this returns still block.
+        // `wait_readable` (below) still works without it by polling `PeekNamedPipe` instead.
+
         Ok(OsPipe {
             read_fd: unsafe { PipeFd::from_native_fd(read_fd) },
             write_fd: unsafe { PipeFd::from_native_fd(write_fd) },
@@ -93,6 +131,115 @@ impl PipeImplBase for Win32PipeImpl {
         }
     }
 
+    fn read_vectored(
+        handle: impl AsPipeFd<PipeRead>,
+        bufs: &mut [std::io::IoSliceMut<'_>],
+    ) -> PipeResult<usize> {
+        // Win32 anonymous/named pipes have no scatter-read equivalent that's usable on a
+        // synchronous handle (`ReadFileScatter` requires an overlapped, page-aligned handle), so
+        // this just loops `ReadFile` per buffer, stopping as soon as one comes back short (EOF or
+        // a short read on a message-mode pipe) rather than assuming more data is available.
+        let mut total = 0;
+        for buf in bufs.iter_mut() {
+            if buf.is_empty() {
+                continue;
+            }
+            let n = Self::read(&handle, buf)?;
+            total += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    fn read_timeout(
+        handle: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> PipeResult<usize> {
+        trace_pipe!(
+            "Reading {} from {:?} with a {:?} timeout",
+            buf.len(),
+            handle.as_pipe_fd(),
+            timeout
+        );
+
+        let fd = unsafe { handle.as_pipe_fd().native_fd() };
+
+        let event =
+            unsafe { CreateEventW(None, BOOL::from(true), BOOL::from(false), PCWSTR::null()) }?;
+        let mut overlapped: OVERLAPPED = unsafe { std::mem::zeroed() };
+        overlapped.hEvent = event;
+
+        let mut bytes_read = 0u32;
+        let immediate =
+            unsafe { ReadFile(fd, Some(buf), Some(&mut bytes_read), Some(&mut overlapped)) };
+
+        let result = match immediate {
+            Ok(_) => {
+                trace_pipe!("Read {} bytes immediately", bytes_read);
+                Ok(bytes_read as usize)
+            }
+            Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => {
+                trace_pipe!("Broken pipe, meaning EOF");
+                Ok(0)
+            }
+            Err(e) if e.code() == ERROR_IO_PENDING.to_hresult() => {
+                let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+
+                match unsafe { WaitForSingleObject(event, millis) } {
+                    WAIT_OBJECT_0 => {
+                        match unsafe {
+                            GetOverlappedResult(fd, &overlapped, &mut bytes_read, BOOL::from(false))
+                        } {
+                            Ok(_) => {
+                                trace_pipe!("Read {} bytes after waiting", bytes_read);
+                                Ok(bytes_read as usize)
+                            }
+                            Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => {
+                                trace_pipe!("Broken pipe, meaning EOF");
+                                Ok(0)
+                            }
+                            Err(e) => Err(e.into()),
+                        }
+                    }
+                    WAIT_TIMEOUT => {
+                        trace_pipe!("read timed out after {:?}, cancelling", timeout);
+                        unsafe { _ = CancelIoEx(fd, Some(&overlapped)) };
+                        Err(PipeError::timed_out())
+                    }
+                    _ => Err(windows::core::Error::from_win32().into()),
+                }
+            }
+            Err(e) => {
+                trace_pipe!("Read error: {:?}", e);
+                Err(e.into())
+            }
+        };
+
+        unsafe { _ = CloseHandle(event) };
+
+        result
+    }
+
+    fn read_cancellable(
+        handle: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        cancel: &CancelToken,
+    ) -> PipeResult<usize> {
+        wait_cancellable(handle, buf, cancel, None)
+    }
+
+    fn read_cancellable_timeout(
+        handle: impl AsPipeFd<PipeRead>,
+        buf: &mut [u8],
+        cancel: &CancelToken,
+        timeout: Duration,
+    ) -> PipeResult<usize> {
+        wait_cancellable(handle, buf, cancel, Some(timeout))
+    }
+
     fn write(handle: impl AsPipeFd<PipeWrite>, buf: &[u8]) -> PipeResult<usize> {
         trace_pipe!("Writing {} bytes to {:?}", buf.len(), handle.as_pipe_fd());
 
@@ -111,6 +258,319 @@ impl PipeImplBase for Win32PipeImpl {
         Ok(bytes_written as usize)
     }
 
+    fn write_vectored(
+        handle: impl AsPipeFd<PipeWrite>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> PipeResult<usize> {
+        // See the comment on `read_vectored` - `WriteFileGather` has the same overlapped,
+        // page-aligned handle requirement, so this loops `WriteFile` per buffer instead.
+        let mut total = 0;
+        for buf in bufs.iter() {
+            if buf.is_empty() {
+                continue;
+            }
+            let n = Self::write(&handle, buf)?;
+            total += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    fn read_duplex(handle: impl AsPipeFd<PipeDuplex>, buf: &mut [u8]) -> PipeResult<usize> {
+        trace_pipe!("Reading {} from {:?}", buf.len(), handle.as_pipe_fd());
+
+        let mut bytes_read = 0;
+        let res = unsafe {
+            ReadFile(
+                handle.as_pipe_fd().native_fd(),
+                Some(buf),
+                Some(&mut bytes_read),
+                None,
+            )
+        };
+
+        match res {
+            Ok(_) => {
+                trace_pipe!("Read {} bytes", bytes_read);
+                Ok(bytes_read as usize)
+            }
+            Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => {
+                trace_pipe!("Broken pipe, meaning EOF");
+                Ok(0)
+            }
+            Err(e) => {
+                trace_pipe!("Read error: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    fn write_duplex(handle: impl AsPipeFd<PipeDuplex>, buf: &[u8]) -> PipeResult<usize> {
+        trace_pipe!("Writing {} bytes to {:?}", buf.len(), handle.as_pipe_fd());
+
+        let mut bytes_written = 0;
+        unsafe {
+            WriteFile(
+                handle.as_pipe_fd().native_fd(),
+                Some(buf),
+                Some(&mut bytes_written),
+                None,
+            )
+        }?;
+
+        trace_pipe!("Wrote {} bytes", bytes_written);
+
+        Ok(bytes_written as usize)
+    }
+
+    fn create_duplex_pair() -> Result<(PipeFd<PipeDuplex>, PipeFd<PipeDuplex>), PipeError> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let name = format!(r"\\.\pipe\nu-duplex-{}-{}", std::process::id(), id);
+        let wide_name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        trace_pipe!("Creating duplex pipe pair {}", name);
+
+        let server = unsafe {
+            CreateNamedPipeW(
+                PCWSTR(wide_name.as_ptr()),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                1,
+                PIPE_BUFFER_CAPACITY as u32,
+                PIPE_BUFFER_CAPACITY as u32,
+                0,
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+            )
+        };
+
+        if server == INVALID_HANDLE_VALUE {
+            return Err(windows::core::Error::from_win32().into());
+        }
+
+        let client = unsafe {
+            CreateFileW(
+                PCWSTR(wide_name.as_ptr()),
+                (FILE_GENERIC_READ | FILE_GENERIC_WRITE).0,
+                FILE_SHARE_MODE(0),
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+                OPEN_EXISTING,
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                None,
+            )
+        }?;
+
+        // The client is already connected at this point, so `ConnectNamedPipe` reports
+        // `ERROR_PIPE_CONNECTED`, which means success here.
+        if let Err(e) = unsafe { ConnectNamedPipe(server, None) } {
+            if e.code() != ERROR_PIPE_CONNECTED.to_hresult() {
+                return Err(e.into());
+            }
+        }
+
+        trace_pipe!(
+            "duplex pair connected: server={:?}, client={:?}",
+            server,
+            client
+        );
+
+        Ok(unsafe {
+            (
+                PipeFd::from_native_fd(server),
+                PipeFd::from_native_fd(client),
+            )
+        })
+    }
+
+    fn create_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError> {
+        let full_name = format!(r"\\.\pipe\{name}");
+        let wide_name: Vec<u16> = full_name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        trace_pipe!("Creating named pipe server {}", full_name);
+
+        let server = unsafe {
+            CreateNamedPipeW(
+                PCWSTR(wide_name.as_ptr()),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                1,
+                PIPE_BUFFER_CAPACITY as u32,
+                PIPE_BUFFER_CAPACITY as u32,
+                0,
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+            )
+        };
+
+        if server == INVALID_HANDLE_VALUE {
+            return Err(windows::core::Error::from_win32().into());
+        }
+
+        trace_pipe!("Waiting for a client to connect to {}", full_name);
+
+        // A client that connected in the gap between `CreateNamedPipeW` and this call makes
+        // `ConnectNamedPipe` fail with `ERROR_PIPE_CONNECTED` - that's success too, not an error.
+        if let Err(e) = unsafe { ConnectNamedPipe(server, None) } {
+            if e.code() != ERROR_PIPE_CONNECTED.to_hresult() {
+                return Err(e.into());
+            }
+        }
+
+        trace_pipe!("Client connected to {}", full_name);
+
+        Ok(unsafe { PipeFd::from_native_fd(server) })
+    }
+
+    fn connect_named_pipe(name: &str) -> Result<PipeFd<PipeDuplex>, PipeError> {
+        let full_name = format!(r"\\.\pipe\{name}");
+        let wide_name: Vec<u16> = full_name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        trace_pipe!("Connecting to named pipe {}", full_name);
+
+        unsafe { WaitNamedPipeW(PCWSTR(wide_name.as_ptr()), NMPWAIT_USE_DEFAULT_WAIT) }?;
+
+        let client = unsafe {
+            CreateFileW(
+                PCWSTR(wide_name.as_ptr()),
+                (FILE_GENERIC_READ | FILE_GENERIC_WRITE).0,
+                FILE_SHARE_MODE(0),
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+                OPEN_EXISTING,
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                None,
+            )
+        }?;
+
+        trace_pipe!("Connected to {}", full_name);
+
+        Ok(unsafe { PipeFd::from_native_fd(client) })
+    }
+
+    fn create_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError> {
+        let full_name = format!(r"\\.\pipe\{name}");
+        let wide_name: Vec<u16> = full_name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        trace_pipe!("Creating named pipe reader {}", full_name);
+
+        let server = unsafe {
+            CreateNamedPipeW(
+                PCWSTR(wide_name.as_ptr()),
+                PIPE_ACCESS_INBOUND,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                1,
+                PIPE_BUFFER_CAPACITY as u32,
+                PIPE_BUFFER_CAPACITY as u32,
+                0,
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+            )
+        };
+
+        if server == INVALID_HANDLE_VALUE {
+            return Err(windows::core::Error::from_win32().into());
+        }
+
+        trace_pipe!("Waiting for a writer to connect to {}", full_name);
+
+        // A peer that connected in the gap between `CreateNamedPipeW` and this call makes
+        // `ConnectNamedPipe` fail with `ERROR_PIPE_CONNECTED` - that's success too, not an error.
+        if let Err(e) = unsafe { ConnectNamedPipe(server, None) } {
+            if e.code() != ERROR_PIPE_CONNECTED.to_hresult() {
+                return Err(e.into());
+            }
+        }
+
+        trace_pipe!("Writer connected to {}", full_name);
+
+        Ok(unsafe { PipeFd::from_native_fd(server) })
+    }
+
+    fn connect_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError> {
+        let full_name = format!(r"\\.\pipe\{name}");
+        let wide_name: Vec<u16> = full_name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        trace_pipe!("Connecting to named pipe {} for writing", full_name);
+
+        unsafe { WaitNamedPipeW(PCWSTR(wide_name.as_ptr()), NMPWAIT_USE_DEFAULT_WAIT) }?;
+
+        let client = unsafe {
+            CreateFileW(
+                PCWSTR(wide_name.as_ptr()),
+                FILE_GENERIC_WRITE.0,
+                FILE_SHARE_MODE(0),
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+                OPEN_EXISTING,
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                None,
+            )
+        }?;
+
+        trace_pipe!("Connected to {}", full_name);
+
+        Ok(unsafe { PipeFd::from_native_fd(client) })
+    }
+
+    fn create_named_pipe_writer(name: &str) -> Result<PipeFd<PipeWrite>, PipeError> {
+        let full_name = format!(r"\\.\pipe\{name}");
+        let wide_name: Vec<u16> = full_name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        trace_pipe!("Creating named pipe writer {}", full_name);
+
+        let server = unsafe {
+            CreateNamedPipeW(
+                PCWSTR(wide_name.as_ptr()),
+                PIPE_ACCESS_OUTBOUND,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                1,
+                PIPE_BUFFER_CAPACITY as u32,
+                PIPE_BUFFER_CAPACITY as u32,
+                0,
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+            )
+        };
+
+        if server == INVALID_HANDLE_VALUE {
+            return Err(windows::core::Error::from_win32().into());
+        }
+
+        trace_pipe!("Waiting for a reader to connect to {}", full_name);
+
+        if let Err(e) = unsafe { ConnectNamedPipe(server, None) } {
+            if e.code() != ERROR_PIPE_CONNECTED.to_hresult() {
+                return Err(e.into());
+            }
+        }
+
+        trace_pipe!("Reader connected to {}", full_name);
+
+        Ok(unsafe { PipeFd::from_native_fd(server) })
+    }
+
+    fn connect_named_pipe_reader(name: &str) -> Result<PipeFd<PipeRead>, PipeError> {
+        let full_name = format!(r"\\.\pipe\{name}");
+        let wide_name: Vec<u16> = full_name.encode_utf16().chain(std::iter::once(0)).collect();
+
+        trace_pipe!("Connecting to named pipe {} for reading", full_name);
+
+        unsafe { WaitNamedPipeW(PCWSTR(wide_name.as_ptr()), NMPWAIT_USE_DEFAULT_WAIT) }?;
+
+        let client = unsafe {
+            CreateFileW(
+                PCWSTR(wide_name.as_ptr()),
+                FILE_GENERIC_READ.0,
+                FILE_SHARE_MODE(0),
+                Some(&DEFAULT_SECURITY_ATTRIBUTES),
+                OPEN_EXISTING,
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                None,
+            )
+        }?;
+
+        trace_pipe!("Connected to {}", full_name);
+
+        Ok(unsafe { PipeFd::from_native_fd(client) })
+    }
+
     fn dup<T: PipeFdType>(fd: impl AsPipeFd<T>) -> PipeResult<crate::PipeFd<T>> {
         let mut new_fd = INVALID_HANDLE_VALUE;
         unsafe {
@@ -131,9 +591,223 @@ impl PipeImplBase for Win32PipeImpl {
         Ok(dup_fd)
     }
 
+    fn dup_cloexec<T: PipeFdType>(fd: impl AsPipeFd<T>) -> PipeResult<crate::PipeFd<T>> {
+        let mut new_fd = INVALID_HANDLE_VALUE;
+        unsafe {
+            let current_process = GetCurrentProcess();
+            DuplicateHandle(
+                current_process,
+                fd.as_pipe_fd().native_fd(),
+                current_process,
+                &mut new_fd,
+                0,
+                BOOL::from(false),
+                DUPLICATE_SAME_ACCESS,
+            )
+        }?;
+        let dup_fd = unsafe { PipeFd::from_native_fd(new_fd) };
+        trace_pipe!(
+            "Duplicated {:?} to {:?} (non-inheritable)",
+            fd.as_pipe_fd(),
+            dup_fd
+        );
+
+        Ok(dup_fd)
+    }
+
+    fn set_nonblocking<T: PipeFdType>(_fd: impl AsPipeFd<T>, _nonblocking: bool) -> PipeResult<()> {
+        // Anonymous pipes from `CreatePipe` have no non-blocking mode to toggle (`PIPE_NOWAIT`
+        // is named-pipe-only, see the caveat on `create_pipe_with`), so there's nothing to do -
+        // `wait_readable`/`poll` are how a caller avoids blocking on one of these instead.
+        Ok(())
+    }
+
     const INVALID_FD_VALUE: NativeFd = INVALID_HANDLE_VALUE;
 }
 
+/// Backs [`PipeImplBase::read_cancellable`]/[`PipeImplBase::read_cancellable_timeout`]: mirrors
+/// [`Win32PipeImpl::read_timeout`]'s overlapped `ReadFile`, but waits on `cancel`'s event alongside
+/// the overlapped read's own event via `WaitForMultipleObjects`, returning [`PipeError::cancelled`]
+/// if `cancel`'s event is what woke the wait instead of the read completing.
+///
+/// This is subject to the same caveat already documented on `read_timeout`: genuinely overlapped
+/// I/O requires the handle to have been opened with `FILE_FLAG_OVERLAPPED`, which nothing in this
+/// module currently does, so the wait below doesn't yet bound a read against a handle opened the
+/// ordinary (synchronous) way.
+fn wait_cancellable(
+    handle: impl AsPipeFd<PipeRead>,
+    buf: &mut [u8],
+    cancel: &CancelToken,
+    timeout: Option<Duration>,
+) -> PipeResult<usize> {
+    trace_pipe!(
+        "Reading {} from {:?} (cancellable, timeout {:?})",
+        buf.len(),
+        handle.as_pipe_fd(),
+        timeout
+    );
+
+    let fd = unsafe { handle.as_pipe_fd().native_fd() };
+
+    let event = unsafe { CreateEventW(None, BOOL::from(true), BOOL::from(false), PCWSTR::null()) }?;
+    let mut overlapped: OVERLAPPED = unsafe { std::mem::zeroed() };
+    overlapped.hEvent = event;
+
+    let mut bytes_read = 0u32;
+    let immediate =
+        unsafe { ReadFile(fd, Some(buf), Some(&mut bytes_read), Some(&mut overlapped)) };
+
+    let result = match immediate {
+        Ok(_) => {
+            trace_pipe!("Read {} bytes immediately", bytes_read);
+            Ok(bytes_read as usize)
+        }
+        Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => {
+            trace_pipe!("Broken pipe, meaning EOF");
+            Ok(0)
+        }
+        Err(e) if e.code() == ERROR_IO_PENDING.to_hresult() => {
+            let millis = timeout
+                .map(|d| u32::try_from(d.as_millis()).unwrap_or(u32::MAX))
+                .unwrap_or(u32::MAX);
+            let handles = [event, cancel.event_handle()];
+
+            match unsafe { WaitForMultipleObjects(&handles, BOOL::from(false), millis) } {
+                WAIT_OBJECT_0 => {
+                    match unsafe {
+                        GetOverlappedResult(fd, &overlapped, &mut bytes_read, BOOL::from(false))
+                    } {
+                        Ok(_) => {
+                            trace_pipe!("Read {} bytes after waiting", bytes_read);
+                            Ok(bytes_read as usize)
+                        }
+                        Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => {
+                            trace_pipe!("Broken pipe, meaning EOF");
+                            Ok(0)
+                        }
+                        Err(e) => Err(e.into()),
+                    }
+                }
+                wait_result if wait_result.0 == WAIT_OBJECT_0.0 + 1 => {
+                    trace_pipe!("read cancelled, cancelling underlying I/O");
+                    unsafe { _ = CancelIoEx(fd, Some(&overlapped)) };
+                    Err(PipeError::cancelled())
+                }
+                WAIT_TIMEOUT => {
+                    trace_pipe!("read timed out, cancelling");
+                    unsafe { _ = CancelIoEx(fd, Some(&overlapped)) };
+                    Err(PipeError::timed_out())
+                }
+                _ => Err(windows::core::Error::from_win32().into()),
+            }
+        }
+        Err(e) => {
+            trace_pipe!("Read error: {:?}", e);
+            Err(e.into())
+        }
+    };
+
+    unsafe { _ = CloseHandle(event) };
+
+    result
+}
+
+/// Backs [`super::wait_readable`]. Anonymous pipes don't support `PIPE_NOWAIT`/overlapped waits
+/// on a synchronous handle (see the caveat on [`Win32PipeImpl::create_pipe_with`]), so there's no
+/// event to block on directly; this instead polls `PeekNamedPipe` - which works on an ordinary
+/// handle - in a short sleep loop until data (or EOF) shows up or `timeout` elapses.
+pub(crate) fn wait_readable(
+    fd: impl AsPipeFd<PipeRead>,
+    timeout: Option<Duration>,
+) -> PipeResult<bool> {
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    let handle = unsafe { fd.as_pipe_fd().native_fd() };
+    let deadline = timeout.map(|d| std::time::Instant::now() + d);
+
+    loop {
+        let mut bytes_available: u32 = 0;
+        let peeked =
+            unsafe { PeekNamedPipe(handle, None, 0, None, Some(&mut bytes_available), None) };
+
+        match peeked {
+            Ok(()) if bytes_available > 0 => return Ok(true),
+            Ok(()) => {}
+            // The writer closed its end: a read will return immediately (with EOF), so this
+            // counts as "ready" too, matching `poll`'s behavior of firing `POLLIN` on EOF.
+            Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => return Ok(true),
+            Err(e) => return Err(e.into()),
+        }
+
+        if let Some(deadline) = deadline {
+            if std::time::Instant::now() >= deadline {
+                return Ok(false);
+            }
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Backs [`super::poll`]. Anonymous pipes have no event to wait on directly (see the caveat on
+/// [`Win32PipeImpl::create_pipe_with`]), so `readable` interest is polled via `PeekNamedPipe` in
+/// a short sleep loop, same as [`wait_readable`]. There's no equivalent readiness primitive for
+/// writability on an anonymous pipe, so `writable` interest is reported ready immediately - a
+/// `write` on one of these handles already just blocks synchronously rather than ever returning
+/// "not ready", so this doesn't change that behavior, it just means `poll` can't shorten the wait
+/// for a full pipe the way it does on unix.
+pub(crate) fn poll<T: PipeFdType>(
+    fds: &mut [super::PollFd<'_, T>],
+    timeout: Option<Duration>,
+) -> PipeResult<usize> {
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    let deadline = timeout.map(|d| std::time::Instant::now() + d);
+
+    loop {
+        let mut ready_count = 0;
+        for pf in fds.iter_mut() {
+            let mut revents = super::Interest::default();
+
+            if pf.interest.writable {
+                revents.writable = true;
+            }
+
+            if pf.interest.readable {
+                let handle = unsafe { pf.fd.as_pipe_fd().native_fd() };
+                let mut bytes_available: u32 = 0;
+                let peeked = unsafe {
+                    PeekNamedPipe(handle, None, 0, None, Some(&mut bytes_available), None)
+                };
+
+                match peeked {
+                    Ok(()) if bytes_available > 0 => revents.readable = true,
+                    Ok(()) => {}
+                    Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => revents.readable = true,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            if revents.readable || revents.writable {
+                ready_count += 1;
+            }
+            pf.revents = revents;
+        }
+
+        if ready_count > 0 {
+            return Ok(ready_count);
+        }
+
+        if let Some(deadline) = deadline {
+            if std::time::Instant::now() >= deadline {
+                return Ok(0);
+            }
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[repr(transparent)]
 #[serde(remote = "windows::Win32::Foundation::HANDLE")]
@@ -1,11 +1,8 @@
-use nu_protocol::{CustomValue, ShellError, Span, Spanned, StreamDataType, Value};
+use nu_protocol::{CustomValue, RawStream, ShellError, Span, Spanned, StreamDataType, Value};
 use serde::{Deserialize, Serialize};
 use std::{io::Read, sync::OnceLock};
 
-use crate::{
-    unidirectional::{Pipe, PipeRead, UnOpenedPipe},
-    PipeReader,
-};
+use crate::{unidirectional::PipeRead, PipeFd, PIPE_BUFFER_CAPACITY};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct StreamCustomValue {
@@ -13,16 +10,16 @@ pub struct StreamCustomValue {
     #[serde(skip, default)]
     pub data: OnceLock<Vec<u8>>,
     #[serde(skip, default)]
-    pipe: OnceLock<UnOpenedPipe<PipeRead>>,
+    pipe: OnceLock<PipeFd<PipeRead>>,
     datatype: StreamDataType,
 }
 
 impl StreamCustomValue {
-    pub fn new(os_pipe: UnOpenedPipe<PipeRead>, span: Span) -> Self {
+    pub fn new(pipe: PipeFd<PipeRead>, datatype: StreamDataType, span: Span) -> Self {
         Self {
             span,
-            datatype: os_pipe.datatype,
-            pipe: OnceLock::from(os_pipe),
+            datatype,
+            pipe: OnceLock::from(pipe),
             data: OnceLock::new(),
         }
     }
@@ -33,8 +30,37 @@ impl StreamCustomValue {
         } else if let Some(pipe) = self.pipe.get() {
             let vec = read_pipe(pipe)?;
 
-            return Ok(self.data.get_or_init(|| vec));
+            Ok(self.data.get_or_init(|| vec))
         } else {
+            Err(ShellError::GenericError(
+                "Failed to read binary data from pipe".to_string(),
+                " ".to_string(),
+                None,
+                None,
+                vec![],
+            ))
+        }
+    }
+
+    /// The streaming counterpart to [`Self::as_binary`]/[`CustomValue::clone_value`]: instead of
+    /// draining the whole pipe into `data` up front, returns a [`RawStream`] that reads
+    /// [`PIPE_BUFFER_CAPACITY`]-sized chunks on demand, so a caller that only needs to relay bytes
+    /// onward (e.g. `save`/`to text` on a multi-gigabyte external-command output) never has to
+    /// hold the full payload in memory at once.
+    ///
+    /// If [`Self::as_binary`] already buffered the pipe into `data`, this streams that buffer
+    /// back out instead of re-reading the (now-drained) pipe. [`CustomValue::clone_value`] puts
+    /// the bytes it reads into the clone it returns, not into this value's `data`.
+    pub fn into_raw_stream(&self) -> Result<RawStream, ShellError> {
+        if let Some(data) = self.data.get() {
+            let data = data.clone();
+            let mut stream =
+                RawStream::new(Box::new(std::iter::once(Ok(data))), None, self.span, None);
+            stream.datatype = self.datatype;
+            return Ok(stream);
+        }
+
+        let Some(pipe) = self.pipe.get() else {
             return Err(ShellError::GenericError(
                 "Failed to read binary data from pipe".to_string(),
                 " ".to_string(),
@@ -42,12 +68,32 @@ impl StreamCustomValue {
                 None,
                 vec![],
             ));
-        }
+        };
+
+        let mut reader = pipe.try_clone()?.into_reader();
+        let span = self.span;
+        let stream_iter = std::iter::from_fn(move || {
+            let mut buf = vec![0u8; PIPE_BUFFER_CAPACITY];
+            match reader.read(&mut buf) {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some(Ok(buf))
+                }
+                Err(err) => Some(Err(ShellError::IOError {
+                    msg: err.to_string(),
+                })),
+            }
+        });
+
+        let mut stream = RawStream::new(Box::new(stream_iter), None, span, None);
+        stream.datatype = self.datatype;
+        Ok(stream)
     }
 }
 
-fn read_pipe(pipe: &UnOpenedPipe<PipeRead>) -> Result<Vec<u8>, ShellError> {
-    let mut reader = pipe.open()?;
+fn read_pipe(pipe: &PipeFd<PipeRead>) -> Result<Vec<u8>, ShellError> {
+    let mut reader = pipe.try_clone()?.into_reader();
     let mut vec = Vec::new();
     _ = reader.read_to_end(&mut vec)?;
     Ok(vec)
@@ -0,0 +1,88 @@
+use std::io::Read;
+use std::sync::mpsc;
+use std::thread;
+
+use nu_protocol::{RawStream, ShellError, Span};
+
+use crate::unidirectional::PipeRead;
+use crate::utils::NamedScopedThreadSpawn;
+use crate::PIPE_BUFFER_CAPACITY;
+use crate::{trace_pipe, AsPipeFd, PipeFd};
+
+/// Trait for reconstructing a stream from a pipe. The mirror image of [`StreamWriter`](crate::StreamWriter).
+pub trait StreamReceiver<'a>: AsPipeFd<PipeRead> {
+    /// Spawns a named thread within `scope` that reads chunks off the pipe until it's closed or
+    /// broken, feeding them into the returned [`RawStream`].
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The thread scope.
+    /// * `span` - The span to attribute the reconstructed stream's values to.
+    ///
+    /// # Returns
+    ///
+    /// The reconstructed `RawStream`, plus a handle to the background thread feeding it; joining
+    /// the handle confirms the thread has stopped reading (at EOF, on a read error, or once the
+    /// stream was dropped) and has closed its end of the pipe.
+    fn recv_stream_scoped<'scope, 'env: 'scope>(
+        self,
+        scope: &'scope thread::Scope<'scope, 'env>,
+        span: Span,
+    ) -> Result<(RawStream, thread::ScopedJoinHandle<'scope, ()>), ShellError>
+    where
+        'a: 'env;
+}
+
+impl<'a> StreamReceiver<'a> for PipeFd<PipeRead> {
+    /// Starts a new thread that reads chunks from the os pipe and forwards them to a
+    /// `RawStream`, decoupling the pipe's read pace from how fast the stream is consumed.
+    fn recv_stream_scoped<'scope, 'env: 'scope>(
+        self,
+        scope: &'scope thread::Scope<'scope, 'env>,
+        span: Span,
+    ) -> Result<(RawStream, thread::ScopedJoinHandle<'scope, ()>), ShellError>
+    where
+        'a: 'env,
+    {
+        let (tx, rx) = mpsc::channel::<Result<Vec<u8>, ShellError>>();
+
+        let handle = scope
+            .spawn_named("recv_stream", move || {
+                trace_pipe!("starting to read");
+
+                let mut reader = self.into_reader();
+                let mut buf = vec![0u8; PIPE_BUFFER_CAPACITY];
+
+                loop {
+                    match reader.read(&mut buf) {
+                        Ok(0) => {
+                            trace_pipe!("no more data to read");
+                            break;
+                        }
+                        Ok(n) => {
+                            trace_pipe!("read {} bytes", n);
+                            if tx.send(Ok(buf[..n].to_vec())).is_err() {
+                                trace_pipe!("receiving end dropped, stopping");
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            trace_pipe!("error: failed to read chunk: {:?}", e);
+                            let _ = tx.send(Err(ShellError::IOError { msg: e.to_string() }));
+                            break;
+                        }
+                    }
+                }
+
+                match reader.close() {
+                    Ok(_) => trace_pipe!("closed pipe"),
+                    Err(e) => trace_pipe!("error: failed to close pipe: {:?}", e),
+                }
+            })
+            .expect("failed to spawn thread");
+
+        let stream = RawStream::new(Box::new(rx.into_iter()), None, span, None);
+
+        Ok((stream, handle))
+    }
+}
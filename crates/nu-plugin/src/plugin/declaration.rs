@@ -1,19 +1,23 @@
 use crate::EvaluatedCall;
 
-use super::{call_plugin, create_command, get_plugin_encoding};
+use super::{
+    call_plugin, call_plugin_persistent, create_command, get_plugin_encoding, CodecRegistry,
+    PluginCallOutcome,
+};
 use crate::protocol::{
-    CallInfo, CallInput, PluginCall, PluginCustomValue, PluginData, PluginResponse,
+    CallInfo, CallInput, PluginCall, PluginCustomValue, PluginData, PluginKind, PluginResponse,
 };
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use std::thread;
 
 use log::trace;
-use nu_pipes::unidirectional::{pipe, PipeWrite};
-use nu_pipes::{trace_pipe, PipeFd, PipeReader, StreamSender};
+use nu_pipes::unidirectional::{pipe, PipeRead, PipeWrite};
+use nu_pipes::{trace_pipe, PipeFd, PipeReader, PipeWriter, StreamReceiver, StreamSender};
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::{ast::Call, PluginSignature, Signature};
-use nu_protocol::{Example, PipelineData, RawStream, ShellError, Value};
+use nu_protocol::{Example, ListStream, PipelineData, RawStream, ShellError, Value};
 
 #[doc(hidden)] // Note: not for plugin authors / only used in nu-parser
 #[derive(Clone)]
@@ -46,11 +50,25 @@ impl PluginDeclaration {
             } = input
             {
                 let stream = stdout.take().unwrap();
+
+                // A plugin that can't rely on fd inheritance (sandboxed, or spawned behind a
+                // wrapper shell that scrubs every inherited fd but 0/1/2) is told a path to
+                // connect to instead. The path is created, and actually connected to, from the
+                // scoped thread in `run` once the plugin has been spawned - doing it here would
+                // block forever, since nothing has opened the other end yet.
+                if self.signature.requires_named_pipe_rendezvous {
+                    let name = named_pipe_path(&self.name);
+                    return Ok(CallInputWithOptPipe(
+                        CallInput::NamedPipe(name.clone(), stream.datatype),
+                        Some((PendingOutputSink::Named(name), stream)),
+                    ));
+                }
+
                 match pipe() {
                     Ok((pr, pw)) => {
                         return Ok(CallInputWithOptPipe(
                             CallInput::Pipe(pr.into_inheritable()?, stream.datatype),
-                            Some((pw, stream)),
+                            Some((PendingOutputSink::Fd(pw), stream)),
                         ));
                     }
                     Err(e) => {
@@ -97,6 +115,91 @@ impl PluginDeclaration {
         };
         Ok(CallInputWithOptPipe(input, None))
     }
+
+    /// Creates the dedicated pipe a plugin streams its output over when `supports_pipelined_output`
+    /// is set, returning the write end to hand to the plugin via `CallInfo` and the read end to
+    /// keep in nushell. Must be called before the plugin process is spawned, so the write end's
+    /// inheritable fd is already open in the child by the time it execs.
+    fn make_call_output(&self) -> Option<(PipeFd<PipeWrite>, PipeFd<PipeRead>)> {
+        if !self.signature.supports_pipelined_output {
+            return None;
+        }
+        match pipe() {
+            Ok((pr, pw)) => match pw.into_inheritable() {
+                Ok(pw) => Some((pw, pr)),
+                Err(e) => {
+                    trace!(
+                        "Unable to make output pipe inheritable for plugin {}: {}, falling back to regular output",
+                        self.name,
+                        e
+                    );
+                    None
+                }
+            },
+            Err(e) => {
+                trace!(
+                    "Unable to create output pipe for plugin {}: {}, falling back to regular output",
+                    self.name,
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Runs this call against the pooled, already-running process for
+    /// [`PluginKind::LongLived`] plugins instead of spawning a fresh one. Doesn't support
+    /// [`PluginPipelineData::OutputStream`]/the legacy streamed-response protocol, since those
+    /// both depend on a call having exclusive use of the plugin's pipes for their duration, which
+    /// doesn't hold once a long-lived process is shared between concurrent calls.
+    fn run_long_lived(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        call_input: CallInput,
+    ) -> Result<PipelineData, ShellError> {
+        let plugin_call = PluginCall::CallInfo(CallInfo {
+            name: self.name.clone(),
+            call: EvaluatedCall::try_from_call(call, engine_state, stack)?,
+            input: call_input,
+            output_pipe: None,
+        });
+
+        let response = call_plugin_persistent(&self.filename, self.shell.as_deref(), plugin_call)
+            .map_err(|err| ShellError::GenericError {
+            error: format!("Unable to call long-lived plugin {}", self.name),
+            msg: err.to_string(),
+            span: Some(call.head),
+            help: None,
+            inner: Vec::new(),
+        })?;
+
+        match response {
+            PluginResponse::Value(value) => Ok(PipelineData::Value(value.as_ref().clone(), None)),
+            PluginResponse::PluginData(name, plugin_data) => Ok(PipelineData::Value(
+                Value::custom_value(
+                    Box::new(PluginCustomValue {
+                        name,
+                        data: plugin_data.data,
+                        filename: self.filename.clone(),
+                        shell: self.shell.clone(),
+                        source: engine_state.get_decl(call.decl_id).name().to_owned(),
+                    }),
+                    plugin_data.span,
+                ),
+                None,
+            )),
+            PluginResponse::Error(err) => Err(err.into()),
+            _ => Err(ShellError::GenericError {
+                error: "Plugin missing value".into(),
+                msg: "received an unexpected response from a long-lived plugin".into(),
+                span: Some(call.head),
+                help: None,
+                inner: Vec::new(),
+            }),
+        }
+    }
 }
 
 impl Command for PluginDeclaration {
@@ -148,6 +251,17 @@ impl Command for PluginDeclaration {
         call: &Call,
         input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
+        let (call_input, pipe, stream) = self.make_call_input(input, call)?.spread_pipe();
+
+        // A long-lived plugin's process is already running, so there's no command to build or
+        // spawn - unless this particular call needs a per-call streaming pipe, which only a fresh
+        // process can be handed (the fd has to be set up for inheritance before `spawn`, and a
+        // long-lived process has already spawned). That one case falls through to the regular
+        // spawn-per-call path below instead.
+        if self.signature.plugin_kind == PluginKind::LongLived && pipe.is_none() {
+            return self.run_long_lived(engine_state, stack, call, call_input);
+        }
+
         // Call the command with self path
         // Decode information from plugin
         // Create PipelineData
@@ -167,7 +281,40 @@ impl Command for PluginDeclaration {
         let current_envs = nu_engine::env::env_to_strings(engine_state, stack).unwrap_or_default();
         plugin_cmd.command.envs(current_envs);
 
-        let (call_input, pipe, stream) = self.make_call_input(input, call)?.spread_pipe();
+        let (output_write, output_read) = self.make_call_output().unzip();
+
+        // Every fd `nu_pipes` hands out is already `O_CLOEXEC` except the one end a given call
+        // actually means to inherit (see `PipeImplBase::create_pipe`), but that's only a
+        // guarantee about fds this crate created - something else open in the process (another
+        // pipe that slipped through, a log file, a socket) could still leak across `exec` and
+        // keep whichever pipe we did hand over from ever reaching EOF in the child, hanging the
+        // reader the same way `pipe_in_another_thread_cancelled` hangs a writer. Harden the spawn
+        // against that by marking everything but the fds we actually mean to hand over
+        // close-on-exec right before `exec`.
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+            use std::os::unix::process::CommandExt;
+
+            let mut keep_fds: Vec<std::os::fd::RawFd> = Vec::new();
+            if let Some(fd) = plugin_cmd.inherited_data_fd {
+                keep_fds.push(fd);
+            }
+            if let CallInput::Pipe(ref pr, _) = call_input {
+                keep_fds.push(pr.as_raw_fd());
+            }
+            if let Some(ref pw) = output_write {
+                keep_fds.push(pw.as_raw_fd());
+            }
+
+            // Safety: `close_other_fds_on_exec` only touches this process's fd table between
+            // `fork` and `exec`, which is exactly the window `pre_exec` runs in.
+            unsafe {
+                plugin_cmd
+                    .command
+                    .pre_exec(move || nu_pipes::hygiene::close_other_fds_on_exec(&keep_fds));
+            }
+        }
 
         let mut child = plugin_cmd.command.spawn().map_err(|err| {
             let decl = engine_state.get_decl(call.decl_id);
@@ -183,64 +330,146 @@ impl Command for PluginDeclaration {
         trace_pipe!("Spawned plugin, getting encoding");
 
         let encoding = {
+            let mut stdin_writer = PipeWriter::new(&plugin_cmd.stdin);
             let mut stdout_reader = PipeReader::new(&plugin_cmd.stdout);
-            get_plugin_encoding(&mut stdout_reader)?
+            get_plugin_encoding(&mut stdin_writer, &mut stdout_reader, &CodecRegistry::new())?
         };
 
-        trace_pipe!("Got encoding ({:?}), calling plugin", encoding);
+        trace_pipe!("Got encoding, calling plugin");
 
         thread::scope(|s| {
-            let join_handle = if let (Some(pipe), Some(stream)) = (pipe, stream) {
-                pipe.send_stream_scoped(s, stream)?
-            } else {
-                None
+            let join_handle = match (pipe, stream) {
+                (Some(PendingOutputSink::Fd(pipe)), Some(stream)) => {
+                    pipe.send_stream_scoped(s, stream)?
+                }
+                (Some(PendingOutputSink::Named(name)), Some(stream)) => {
+                    Some(send_named_pipe_stream_scoped(s, name, stream))
+                }
+                _ => None,
+            };
+
+            let mut output_stream = match output_read {
+                Some(output_read) => Some(output_read.recv_stream_scoped(s, call.head)?),
+                None => None,
             };
 
             let plugin_call = PluginCall::CallInfo(CallInfo {
                 name: self.name.clone(),
                 call: EvaluatedCall::try_from_call(call, engine_state, stack)?,
                 input: call_input,
+                output_pipe: output_write,
             });
 
-            let response =
-                call_plugin(&plugin_cmd, plugin_call, &encoding, call.head).map_err(|err| {
-                    let decl = engine_state.get_decl(call.decl_id);
-                    ShellError::GenericError {
-                        error: format!("Unable to decode call for {}", decl.name()),
-                        msg: err.to_string(),
-                        span: Some(call.head),
-                        help: None,
-                        inner: Vec::new(),
-                    }
-                });
+            let response = call_plugin(
+                &plugin_cmd,
+                plugin_call,
+                &encoding,
+                engine_state,
+                stack,
+                call.head,
+            )
+            .map_err(|err| {
+                let decl = engine_state.get_decl(call.decl_id);
+                ShellError::GenericError {
+                    error: format!("Unable to decode call for {}", decl.name()),
+                    msg: err.to_string(),
+                    span: Some(call.head),
+                    help: None,
+                    inner: Vec::new(),
+                }
+            });
 
             trace_pipe!("Got response from plugin");
 
             let pipeline_data = match response {
-                Ok(PluginResponse::Value(value)) => {
+                Ok(PluginCallOutcome::Response(PluginResponse::Value(value))) => {
                     Ok(PipelineData::Value(value.as_ref().clone(), None))
                 }
-                Ok(PluginResponse::PluginData(name, plugin_data)) => Ok(PipelineData::Value(
-                    Value::custom_value(
-                        Box::new(PluginCustomValue {
-                            name,
-                            data: plugin_data.data,
-                            filename: self.filename.clone(),
-                            shell: self.shell.clone(),
-                            source: engine_state.get_decl(call.decl_id).name().to_owned(),
+                Ok(PluginCallOutcome::Response(PluginResponse::PluginData(name, plugin_data))) => {
+                    Ok(PipelineData::Value(
+                        Value::custom_value(
+                            Box::new(PluginCustomValue {
+                                name,
+                                data: plugin_data.data,
+                                filename: self.filename.clone(),
+                                shell: self.shell.clone(),
+                                source: engine_state.get_decl(call.decl_id).name().to_owned(),
+                            }),
+                            plugin_data.span,
+                        ),
+                        None,
+                    ))
+                }
+                Ok(PluginCallOutcome::Response(PluginResponse::Error(err))) => Err(err.into()),
+                // The plugin wrote its output straight to the pipe we handed it via
+                // `CallInfo::output_pipe`, instead of returning a value or streaming it over the
+                // regular response channel - wrap the background reader's `RawStream` up the same
+                // way external command stdout is wrapped.
+                Ok(PluginCallOutcome::Response(PluginResponse::StreamPiped(datatype))) => {
+                    match output_stream.take() {
+                        Some((mut stdout, _)) => {
+                            stdout.datatype = datatype;
+                            Ok(PipelineData::ExternalStream {
+                                stdout: Some(stdout),
+                                stderr: None,
+                                exit_code: None,
+                                span: call.head,
+                                metadata: None,
+                                trim_end_newline: false,
+                            })
+                        }
+                        None => Err(ShellError::GenericError {
+                            error: "Plugin missing output pipe".into(),
+                            msg: "received a piped stream response but no output pipe was given \
+                                for this call"
+                                .into(),
+                            span: Some(call.head),
+                            help: None,
+                            inner: Vec::new(),
                         }),
-                        plugin_data.span,
-                    ),
-                    None,
-                )),
-                Ok(PluginResponse::Error(err)) => Err(err.into()),
-                Ok(PluginResponse::Signature(..)) => Err(ShellError::GenericError {
+                    }
+                }
+                Ok(PluginCallOutcome::Response(
+                    PluginResponse::Signature(..)
+                    | PluginResponse::EngineCall(..)
+                    | PluginResponse::Stream(..)
+                    | PluginResponse::StreamValue(..)
+                    | PluginResponse::StreamBytes(..)
+                    | PluginResponse::StreamEnd,
+                )) => Err(ShellError::GenericError {
                     error: "Plugin missing value".into(),
-                    msg: "Received a signature from plugin instead of value".into(),
+                    msg: "Received an unexpected response from plugin instead of a value".into(),
                     span: Some(call.head),
                     help: None,
                     inner: Vec::new(),
                 }),
+                // A stream's values are pulled lazily as the returned `PipelineData` is consumed;
+                // dropping it early (e.g. `first 3` stopping the rest of the pipeline) closes the
+                // plugin's pipes via `PluginOutputStream`'s `Drop` impl instead of waiting for the
+                // stream to run to `StreamEnd`.
+                Ok(PluginCallOutcome::Stream {
+                    datatype: None,
+                    stream,
+                }) => Ok(PipelineData::ListStream(
+                    ListStream::from_stream(stream.into_list_iter(), None),
+                    None,
+                )),
+                Ok(PluginCallOutcome::Stream {
+                    datatype: Some(datatype),
+                    stream,
+                }) => {
+                    let mut stdout =
+                        RawStream::new(Box::new(stream.into_byte_iter()), None, call.head, None);
+                    stdout.datatype = datatype;
+                    Ok(PipelineData::ExternalStream {
+                        stdout: Some(stdout),
+                        stderr: None,
+                        exit_code: None,
+                        span: call.head,
+                        metadata: None,
+                        trim_end_newline: false,
+                    })
+                }
                 Err(err) => Err(err),
             };
 
@@ -254,6 +483,20 @@ impl Command for PluginDeclaration {
                 })?;
             }
 
+            // Only still `Some` here if the plugin didn't end up using the output pipe it was
+            // given (e.g. it returned a regular value instead) - the `RawStream` case above
+            // already took its handle. Joining here waits for the reader thread to finish
+            // (reading until EOF), as `thread::scope` would before returning anyway.
+            if let Some((_, handle)) = output_stream {
+                handle.join().map_err(|_| ShellError::GenericError {
+                    error: format!("Unable to join output thread for {}", &self.name),
+                    msg: "Unable to join thread".into(),
+                    span: Some(call.head),
+                    help: None,
+                    inner: Vec::new(),
+                })?;
+            }
+
             // We need to call .wait() on the child, or we'll risk summoning the zombie horde
             let _ = child.wait();
 
@@ -266,9 +509,16 @@ impl Command for PluginDeclaration {
     }
 }
 
-struct CallInputWithOptPipe(CallInput, Option<(PipeFd<PipeWrite>, RawStream)>);
+/// Where the engine should end up writing a piped-input stream's bytes, once the plugin has been
+/// spawned: either an fd it already inherited, or a named pipe it was only handed the path to.
+enum PendingOutputSink {
+    Fd(PipeFd<PipeWrite>),
+    Named(String),
+}
+
+struct CallInputWithOptPipe(CallInput, Option<(PendingOutputSink, RawStream)>);
 impl CallInputWithOptPipe {
-    fn spread_pipe(self) -> (CallInput, Option<PipeFd<PipeWrite>>, Option<RawStream>) {
+    fn spread_pipe(self) -> (CallInput, Option<PendingOutputSink>, Option<RawStream>) {
         if let Some((pipe, stdout)) = self.1 {
             (self.0, Some(pipe), Some(stdout))
         } else {
@@ -276,3 +526,56 @@ impl CallInputWithOptPipe {
         }
     }
 }
+
+/// A filesystem path (on Windows, a pipe name) unique enough that two calls in flight at once
+/// never collide, for [`PluginSignature::requires_named_pipe_rendezvous`] plugins that have to be
+/// handed a path instead of an inherited fd.
+fn named_pipe_path(plugin_name: &str) -> String {
+    static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+    std::env::temp_dir()
+        .join(format!(
+            "nu-plugin-{plugin_name}-{}-{id}.pipe",
+            std::process::id()
+        ))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Like [`StreamWriter::send_stream_scoped`](nu_pipes::StreamWriter::send_stream_scoped), but for
+/// a [`PendingOutputSink::Named`] endpoint: connects to the named pipe (blocking until the
+/// plugin opens its end, which it does once it receives the [`CallInput::NamedPipe`] naming it)
+/// before writing the stream the same way.
+fn send_named_pipe_stream_scoped<'scope, 'env>(
+    scope: &'scope thread::Scope<'scope, 'env>,
+    name: String,
+    stdout: RawStream,
+) -> thread::ScopedJoinHandle<'scope, ()> {
+    scope.spawn(move || {
+        let pw = match nu_pipes::named::create_named_pipe_writer(&name) {
+            Ok(pw) => pw,
+            Err(e) => {
+                trace_pipe!("error: failed to connect to named pipe {}: {:?}", name, e);
+                return;
+            }
+        };
+
+        let mut writer = pw.into_writer();
+        let mut stdout = stdout;
+
+        while let Some(item) = stdout.stream.next() {
+            match item {
+                Ok(item) => {
+                    if let Err(e) = writer.write_all(&item) {
+                        trace_pipe!("error: failed to write item: {:?}", e);
+                    }
+                }
+                Err(e) => trace_pipe!("error: failed to get item: {:?}", e),
+            }
+        }
+
+        let _ = writer.flush();
+        let _ = writer.close();
+    })
+}
@@ -1,4 +1,5 @@
 mod declaration;
+use base64::{engine::general_purpose, Engine};
 pub use declaration::PluginDeclaration;
 use nu_engine::documentation::get_flags_section;
 use nu_pipes::unidirectional::{PipeRead, PipeWrite};
@@ -9,21 +10,99 @@ use std::collections::HashMap;
 use std::ffi::OsStr;
 
 use crate::protocol::{
-    CallInput, LabeledError, PluginCall, PluginData, PluginPipelineData, PluginResponse,
+    CallInput, EngineCall, EngineCallResponse, LabeledError, OutputStream, PluginCall, PluginData,
+    PluginPipelineData, PluginResponse,
 };
 use crate::EncodingType;
 use std::env;
 use std::fmt::Write;
-use std::io::{Error, ErrorKind, Write as WriteTrait};
-use std::path::Path;
+use std::io::{Error, ErrorKind, Read, Write as WriteTrait};
+use std::path::{Path, PathBuf};
 use std::process::{exit, Command as CommandSys, Stdio};
+use std::sync::mpsc;
+use std::sync::Arc;
 
-use nu_protocol::{CustomValue, PluginSignature, ShellError, Span, Value};
+use nu_protocol::engine::{Closure, EngineState, Stack};
+use nu_protocol::{CustomValue, PluginSignature, ShellError, Span, Spanned, StreamDataType, Value};
 
 use super::EvaluatedCall;
 
+/// A plugin's means of asking Nushell to do something mid-call that it can't do on its own:
+/// evaluate a closure the caller passed in, or read config/environment state that only Nushell's
+/// `EngineState`/`Stack` know about. Handed to [`Plugin::run`] alongside the call and input, for
+/// plugins whose protocol version advertises
+/// [`plugin_protocol::Capability::EngineCalls`](nu_protocol::plugin_protocol::Capability::EngineCalls).
+///
+/// Each method sends a [`PluginResponse::EngineCall`] over `stdout` in place of a final response
+/// and then blocks on `stdin` for the matching [`PluginCall::EngineCallResponse`], so Nushell
+/// sees the request as a continuation of the same call rather than a new one.
+pub struct EngineInterface<'p, 'r> {
+    stdin_reader: &'r mut PipeReader<'p>,
+    stdout_writer: &'r mut PipeWriter<'p>,
+    codec: &'r dyn PluginCodec,
+}
+
+impl<'p, 'r> EngineInterface<'p, 'r> {
+    pub(crate) fn new(
+        stdin_reader: &'r mut PipeReader<'p>,
+        stdout_writer: &'r mut PipeWriter<'p>,
+        codec: &'r dyn PluginCodec,
+    ) -> Self {
+        Self {
+            stdin_reader,
+            stdout_writer,
+            codec,
+        }
+    }
+
+    fn call(&mut self, engine_call: EngineCall) -> Result<Value, ShellError> {
+        self.codec
+            .encode_response(&PluginResponse::EngineCall(engine_call), self.stdout_writer)?;
+        self.stdout_writer
+            .flush()
+            .map_err(|err| ShellError::IOError(err.to_string()))?;
+
+        match self.codec.decode_call(self.stdin_reader)? {
+            PluginCall::EngineCallResponse(EngineCallResponse::Value(value)) => Ok(value),
+            PluginCall::EngineCallResponse(EngineCallResponse::Error(err)) => Err(err.into()),
+            _ => Err(ShellError::PluginFailedToDecode {
+                msg: "expected an EngineCallResponse from nushell".into(),
+            }),
+        }
+    }
+
+    /// Evaluates `closure` with `args` bound to its parameters, in Nushell's engine.
+    pub fn eval_closure(
+        &mut self,
+        closure: Spanned<Closure>,
+        args: Vec<Value>,
+    ) -> Result<Value, ShellError> {
+        self.call(EngineCall::EvalClosure { closure, args })
+    }
+
+    /// Fetches Nushell's current configuration, as a value.
+    pub fn get_config(&mut self) -> Result<Value, ShellError> {
+        self.call(EngineCall::GetConfig)
+    }
+
+    /// Looks up an environment variable by name, returning `None` if it isn't set.
+    pub fn get_env_var(&mut self, name: impl Into<String>) -> Result<Option<Value>, ShellError> {
+        match self.call(EngineCall::GetEnvVar(name.into()))? {
+            Value::Nothing { .. } => Ok(None),
+            value => Ok(Some(value)),
+        }
+    }
+}
+
 /// Encoding scheme that defines a plugin's communication protocol with Nu
-pub trait PluginCodec: Clone {
+///
+/// Methods take `&mut dyn Write`/`&mut dyn BufRead` rather than generic `impl Write`/`impl
+/// BufRead` parameters, and the trait carries no `Clone` supertrait, so that `dyn PluginCodec`
+/// is a valid trait object - this is what lets [`CodecRegistry`] hold a heterogeneous set of
+/// codecs and [`serve_plugin`] pick one of them at runtime after the handshake negotiates a
+/// name, instead of every codec having to be known at compile time as a single generic
+/// parameter.
+pub trait PluginCodec {
     /// The name of the encoder (e.g., `json`)
     fn name(&self) -> &str;
 
@@ -31,53 +110,199 @@ pub trait PluginCodec: Clone {
     fn encode_call(
         &self,
         plugin_call: &PluginCall,
-        writer: &mut impl std::io::Write,
+        writer: &mut dyn std::io::Write,
     ) -> Result<(), ShellError>;
 
     /// Deserialize a `PluginCall` from the `PluginEncoder`s format
-    fn decode_call(&self, reader: &mut impl std::io::BufRead) -> Result<PluginCall, ShellError>;
+    fn decode_call(&self, reader: &mut dyn std::io::BufRead) -> Result<PluginCall, ShellError>;
 
     /// Serialize a `PluginResponse` from the plugin in this `PluginEncoder`'s preferred
     /// format
     fn encode_response(
         &self,
         plugin_response: &PluginResponse,
-        writer: &mut impl std::io::Write,
+        writer: &mut dyn std::io::Write,
     ) -> Result<(), ShellError>;
 
     /// Deserialize a `PluginResponse` from the plugin from this `PluginEncoder`'s
     /// preferred format
     fn decode_response(
         &self,
-        reader: &mut impl std::io::BufRead,
+        reader: &mut dyn std::io::BufRead,
     ) -> Result<PluginResponse, ShellError>;
 }
 
+/// A named set of available [`PluginCodec`]s, used on both sides of the codec negotiation
+/// handshake: [`serve_plugin`] advertises every name in its registry and honors whichever one
+/// comes back, while [`get_plugin_encoding`] picks the first of the plugin's advertised names
+/// that's also in its own registry and tells the plugin which one it picked. Because a third
+/// party's codec (e.g. CBOR, a compressed MessagePack variant) just needs to be registered
+/// wherever it's wanted, adding one doesn't require patching this crate.
+#[derive(Default)]
+pub struct CodecRegistry {
+    codecs: HashMap<String, Arc<dyn PluginCodec>>,
+}
+
+impl CodecRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `codec` under `name`, so it can be offered and recognized during the
+    /// negotiation handshake. Overwrites any codec already registered under the same name.
+    pub fn register_codec(&mut self, name: impl Into<String>, codec: Box<dyn PluginCodec>) {
+        let name = name.into();
+        let codec: Arc<dyn PluginCodec> = Arc::from(codec);
+        match self.codecs.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(entry) => entry.1 = codec,
+            None => self.codecs.push((name, codec)),
+        }
+    }
+
+    /// Builder-style [`CodecRegistry::register_codec`], for assembling a registry in one
+    /// expression, e.g. `CodecRegistry::new().with_codec("msgpack", MsgPackSerializer {})`.
+    pub fn with_codec(
+        mut self,
+        name: impl Into<String>,
+        codec: impl PluginCodec + 'static,
+    ) -> Self {
+        self.register_codec(name, Box::new(codec));
+        self
+    }
+
+    /// The names of every codec in this registry. Order isn't meaningful beyond being whatever
+    /// a plugin lists its codecs in, which [`get_plugin_encoding`] treats as its preference order.
+    fn names(&self) -> impl Iterator<Item = &str> {
+        self.codecs.keys().map(String::as_str)
+    }
+
+    /// Looks up a codec by name, falling back to the protocol's built-in [`EncodingType`] codecs
+    /// (e.g. `"json"`, `"msgpack"`) so those stay available without every registry needing to
+    /// register them by hand.
+    fn get(&self, name: &str) -> Option<Arc<dyn PluginCodec>> {
+        if let Some(codec) = self.codecs.get(name) {
+            return Some(Arc::clone(codec));
+        }
+        EncodingType::try_from_bytes(name.as_bytes())
+            .map(|encoding| Arc::new(encoding) as Arc<dyn PluginCodec>)
+    }
+}
+
 pub(crate) struct PluginCommand {
     pub(crate) command: CommandSys,
     pub(crate) stdin: PipeFd<PipeWrite>,
     pub(crate) stdout: PipeFd<PipeRead>,
+    /// The write end of an extra pipe handed to the plugin alongside stdin/stdout, present only
+    /// when `create_command` was asked for one. Used to stream a large `PluginData` payload into
+    /// the plugin instead of inlining it in a `PluginCall` message.
+    pub(crate) data: Option<PipeFd<PipeWrite>>,
+    /// The raw fd of `data`'s counterpart - the read end the child inherits - kept so
+    /// `PluginDeclaration::run` can tell it apart from whatever else happens to be open in this
+    /// process when it hardens the spawn against leaking stray fds into the plugin. `None` if no
+    /// data pipe was requested. Unix only: on Windows a handle only crosses `CreateProcess` if
+    /// it was explicitly flagged inheritable via `SetHandleInformation`, which `into_inheritable`
+    /// already does for exactly the one end meant to cross, so there's nothing else to guard
+    /// against there.
+    #[cfg(unix)]
+    pub(crate) inherited_data_fd: Option<std::os::fd::RawFd>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct PluginPipes {
     pub(crate) stdin: PipeFd<PipeRead>,
     pub(crate) stdout: PipeFd<PipeWrite>,
+    /// The read end of the extra data pipe, present only when the caller asked `create_command`
+    /// for one. A plugin call that streams its payload (e.g. `PluginCall::CollapseCustomValuePiped`)
+    /// reads it from here instead of from an inline `Vec<u8>`.
+    pub(crate) data: Option<PipeFd<PipeRead>>,
+    /// The protocol version Nushell negotiated for this invocation, passed down so `serve_plugin`
+    /// knows whether it's allowed to loop over more than one call
+    /// ([`plugin_protocol::Capability::Persistent`]) or must fall back to the original
+    /// single-call-then-exit behavior for compatibility.
+    pub(crate) protocol_version: plugin_protocol::Version,
+}
+
+/// Identifies the wire format of a `PluginPipes` handoff, so a plugin built against an older
+/// layout fails loudly on a magic/version mismatch instead of misinterpreting a raw handle value
+/// as something else.
+const PLUGIN_PIPES_MAGIC: [u8; 4] = *b"NPPw";
+const PLUGIN_PIPES_VERSION: u8 = 1;
+
+impl PluginPipes {
+    /// Encodes this handoff as `MAGIC || VERSION || msgpack(self)`, base64'd so it can ride along
+    /// as a single argv entry. Replaces the old `serde_json::to_string` call, which put raw
+    /// fd/HANDLE integers straight into a JSON string on the command line - fragile to quote
+    /// across `cmd`/`sh`/`python` shells and bulkier than it needs to be.
+    pub(crate) fn encode_for_child(&self) -> Result<String, Error> {
+        let mut buf = Vec::from(PLUGIN_PIPES_MAGIC);
+        buf.push(PLUGIN_PIPES_VERSION);
+        rmp_serde::encode::write(&mut buf, self)
+            .map_err(|err| Error::new(ErrorKind::Other, err))?;
+
+        Ok(general_purpose::STANDARD.encode(buf))
+    }
+
+    /// The inverse of [`Self::encode_for_child`]; called by the plugin side on whatever it finds
+    /// in its first positional argument.
+    pub(crate) fn decode_from_child(arg: &str) -> Result<Self, Error> {
+        let bytes = general_purpose::STANDARD
+            .decode(arg)
+            .map_err(|err| Error::new(ErrorKind::Other, err))?;
+
+        let prefix_len = PLUGIN_PIPES_MAGIC.len() + 1;
+        if bytes.len() < prefix_len {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "pipe descriptor is shorter than its magic/version prefix",
+            ));
+        }
+        let (prefix, payload) = bytes.split_at(prefix_len);
+        if prefix[..PLUGIN_PIPES_MAGIC.len()] != PLUGIN_PIPES_MAGIC {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "pipe descriptor has the wrong magic bytes",
+            ));
+        }
+        let version = prefix[PLUGIN_PIPES_MAGIC.len()];
+        if version != PLUGIN_PIPES_VERSION {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "pipe descriptor wire version {version} isn't supported by this plugin (expected {PLUGIN_PIPES_VERSION})"
+                ),
+            ));
+        }
+
+        rmp_serde::decode::from_slice(payload).map_err(|err| Error::new(ErrorKind::Other, err))
+    }
 }
 
 pub(crate) fn create_command(
     path: &Path,
     shell: Option<&Path>,
     protocol_version: plugin_protocol::Version,
+    with_data_pipe: bool,
 ) -> PluginCommand {
     let (stdin_pipe_read, stdin_pipe_write) = nu_pipes::unidirectional::pipe().unwrap();
     let (stdout_pipe_read, stdout_pipe_write) = nu_pipes::unidirectional::pipe().unwrap();
+    let data_pipe = with_data_pipe.then(|| nu_pipes::unidirectional::pipe().unwrap());
+
     let plugin_pipes = PluginPipes {
         stdin: stdin_pipe_read.into_inheritable().unwrap(),
         stdout: stdout_pipe_write.into_inheritable().unwrap(),
+        data: data_pipe
+            .as_ref()
+            .map(|(read, _)| read.try_clone().unwrap().into_inheritable().unwrap()),
+        protocol_version,
     };
 
-    let pipes_ser = serde_json::to_string(&plugin_pipes).unwrap();
+    #[cfg(unix)]
+    let inherited_data_fd = plugin_pipes
+        .data
+        .as_ref()
+        .map(std::os::fd::AsRawFd::as_raw_fd);
+
+    let pipes_ser = plugin_pipes.encode_for_child().unwrap();
 
     let mut process = match (path.extension(), shell) {
         (_, Some(shell)) => {
@@ -119,55 +344,469 @@ pub(crate) fn create_command(
             .stdout(plugin_pipes.stdout);
     }
 
+    // The data pipe's read end was already marked inheritable above and its fd is handed to the
+    // plugin via `pipes_ser`, so it doesn't need to be wired through `Command::stdin`/`stdout`
+    // like the other two pipes - on Unix an inheritable fd survives `exec` regardless. `data_pipe`
+    // itself is dropped here, closing our copy of the read end; the plugin keeps the one it
+    // inherited.
     PluginCommand {
         command: process,
         stdin: stdin_pipe_write,
         stdout: stdout_pipe_read,
+        data: data_pipe.map(|(read, write)| {
+            let _ = read.close();
+            write
+        }),
+        #[cfg(unix)]
+        inherited_data_fd,
+    }
+}
+
+/// What a call to [`call_plugin`] resolved to: either a final, already-decoded response, or a
+/// streamed response whose items are still being pulled lazily off the plugin's still-open pipes.
+pub(crate) enum PluginCallOutcome {
+    Response(PluginResponse),
+    Stream {
+        datatype: Option<StreamDataType>,
+        stream: PluginOutputStream,
+    },
+}
+
+/// The still-open pipe pair behind a [`PluginResponse::Stream`], kept alive past [`call_plugin`]'s
+/// return so its caller can pull further [`PluginResponse::StreamValue`]/
+/// [`PluginResponse::StreamBytes`] messages lazily instead of buffering the whole stream up front.
+///
+/// Neither [`nu_pipes::io::OwningPipeReader`] nor [`nu_pipes::io::OwningPipeWriter`] close
+/// themselves on drop, so this wrapper's `Drop` impl is what makes abandoning the stream early -
+/// e.g. `plugin generate | first 3` stopping before [`PluginResponse::StreamEnd`] - actually close
+/// the pipes and signal the plugin to stop, rather than just leaking the file descriptors until
+/// the plugin process exits on its own.
+pub(crate) struct PluginOutputStream {
+    stdin_writer: Option<nu_pipes::io::OwningPipeWriter>,
+    stdout_reader: Option<nu_pipes::io::OwningPipeReader>,
+    encoding: Arc<dyn PluginCodec>,
+}
+
+impl Drop for PluginOutputStream {
+    fn drop(&mut self) {
+        if let Some(stdin_writer) = self.stdin_writer.take() {
+            let _ = stdin_writer.close();
+        }
+        if let Some(stdout_reader) = self.stdout_reader.take() {
+            let _ = stdout_reader.close();
+        }
+    }
+}
+
+impl PluginOutputStream {
+    /// Reads the next length-prefixed stream chunk, returning `None` once the plugin sends
+    /// [`PluginResponse::StreamEnd`] or its pipe is otherwise done.
+    fn next_response(&mut self) -> Option<PluginResponse> {
+        let stdout_reader = self.stdout_reader.as_mut()?;
+        let framed = read_framed(stdout_reader).ok().flatten()?;
+        match self
+            .encoding
+            .decode_response(&mut std::io::Cursor::new(framed))
+        {
+            Ok(PluginResponse::StreamEnd) | Err(_) => None,
+            Ok(response) => Some(response),
+        }
+    }
+
+    /// Turns this into a lazy iterator of values, pulling one [`PluginResponse::StreamValue`] off
+    /// the pipe per item as Nushell consumes it.
+    pub(crate) fn into_list_iter(mut self) -> impl Iterator<Item = Value> + Send + 'static {
+        std::iter::from_fn(move || match self.next_response() {
+            Some(PluginResponse::StreamValue(value)) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Turns this into a lazy iterator of byte chunks, pulling one [`PluginResponse::StreamBytes`]
+    /// off the pipe per chunk as Nushell consumes it.
+    pub(crate) fn into_byte_iter(
+        mut self,
+    ) -> impl Iterator<Item = Result<Vec<u8>, ShellError>> + Send + 'static {
+        std::iter::from_fn(move || match self.next_response() {
+            Some(PluginResponse::StreamBytes(bytes)) => Some(Ok(bytes)),
+            Some(PluginResponse::Error(err)) => Some(Err(err.into())),
+            _ => None,
+        })
     }
 }
 
 pub(crate) fn call_plugin(
-    plugin_cmd: PluginCommand,
+    plugin_cmd: &PluginCommand,
     plugin_call: PluginCall,
-    encoding: &EncodingType,
+    encoding: &Arc<dyn PluginCodec>,
+    engine_state: &EngineState,
+    stack: &mut Stack,
     _span: Span,
-) -> Result<PluginResponse, ShellError> {
+) -> Result<PluginCallOutcome, ShellError> {
+    let mut stdin_writer = plugin_cmd
+        .stdin
+        .try_clone()
+        .map_err(|err| ShellError::PluginFailedToLoad {
+            msg: format!("Failed to clone plugin stdin: {err}"),
+        })?
+        .into_writer();
+    let mut stdout_reader = plugin_cmd
+        .stdout
+        .try_clone()
+        .map_err(|err| ShellError::PluginFailedToLoad {
+            msg: format!("Failed to clone plugin stdout: {err}"),
+        })?
+        .into_reader();
+
     // If the child process fills its stdout buffer, it may end up waiting until the parent
     // reads the stdout, and not be able to read stdin in the meantime, causing a deadlock.
-    // Writing from another thread ensures that stdout is being read at the same time, avoiding the problem.
-    std::thread::scope(|s| {
-        let encoding_clone = encoding.clone();
-        let handle = s.spawn(move || {
-            let mut stdin_writer = plugin_cmd.stdin.into_writer();
-            encoding_clone
-                .encode_call(&plugin_call, &mut stdin_writer)
-                .and_then(|_| {
-                    stdin_writer
-                        .close()
-                        .map_err(|err| ShellError::PluginFailedToLoad {
-                            msg: format!("Failed to close stdin: {}", err.error()),
-                        })
-                })
+    // Writing the (potentially large) initial call from another thread ensures stdout is being
+    // read at the same time, avoiding the problem.
+    let mut response = std::thread::scope(|s| -> Result<PluginResponse, ShellError> {
+        let encoding_clone = Arc::clone(encoding);
+        let stdin_writer_ref = &mut stdin_writer;
+        let handle = s.spawn(move || encoding_clone.encode_call(&plugin_call, stdin_writer_ref));
+
+        let response = encoding.decode_response(&mut stdout_reader)?;
+        handle.join().unwrap()?;
+        Ok(response)
+    })?;
+
+    // A plugin that needs something only Nushell can provide - evaluating a closure, reading
+    // config/environment - answers with a `PluginResponse::EngineCall` instead of its real
+    // response, as many times as it needs to, before finally sending one. Each of those is small
+    // compared to the initial call, so servicing them directly here rather than routing back
+    // through a writer thread is fine.
+    while let PluginResponse::EngineCall(engine_call) = response {
+        let engine_response = service_engine_call(engine_state, stack, engine_call);
+        encoding.encode_call(
+            &PluginCall::EngineCallResponse(engine_response),
+            &mut stdin_writer,
+        )?;
+        response = encoding.decode_response(&mut stdout_reader)?;
+    }
+
+    // `PluginResponse::Stream` means the plugin isn't done sending yet, so the pipes have to stay
+    // open past this function's return - `PluginOutputStream` picks up from here and is
+    // responsible for eventually closing them, whether the stream runs to `StreamEnd` or is
+    // abandoned early.
+    if let PluginResponse::Stream(datatype) = response {
+        return Ok(PluginCallOutcome::Stream {
+            datatype,
+            stream: PluginOutputStream {
+                stdin_writer: Some(stdin_writer),
+                stdout_reader: Some(stdout_reader),
+                encoding: Arc::clone(encoding),
+            },
         });
+    }
 
-        // Deserialize response from plugin to extract the resulting value
+    stdin_writer
+        .close()
+        .map_err(|err| ShellError::PluginFailedToLoad {
+            msg: format!("Failed to close stdin: {}", err.error()),
+        })?;
+    stdout_reader
+        .close()
+        .map_err(|err| ShellError::PluginFailedToLoad {
+            msg: format!("Failed to close stdout: {}", err.error()),
+        })?;
 
-        let mut stdout_reader = plugin_cmd.stdout.into_reader();
+    Ok(PluginCallOutcome::Response(response))
+}
 
-        let res = encoding.decode_response(&mut stdout_reader)?;
+/// Services an [`EngineCall`] a plugin sent mid-call, against the caller's live `EngineState`/
+/// `Stack`, and packages the result as an [`EngineCallResponse`] to send back.
+fn service_engine_call(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    engine_call: EngineCall,
+) -> EngineCallResponse {
+    let result = match engine_call {
+        EngineCall::EvalClosure { closure, args } => {
+            eval_closure_for_plugin(engine_state, stack, closure, args)
+        }
+        EngineCall::GetConfig => Ok(engine_state
+            .get_config()
+            .clone()
+            .into_value(Span::unknown())),
+        EngineCall::GetEnvVar(name) => Ok(stack
+            .get_env_var(engine_state, &name)
+            .unwrap_or_else(|| Value::nothing(Span::unknown()))),
+    };
 
-        handle.join().unwrap()?;
+    match result {
+        Ok(value) => EngineCallResponse::Value(value),
+        Err(err) => EngineCallResponse::Error(err.into()),
+    }
+}
 
-        stdout_reader
-            .close()
-            .map_err(|err| ShellError::PluginFailedToLoad {
-                msg: format!("Failed to close stdout: {}", err.error()),
-            })?;
+/// Evaluates `closure` with `args` bound to its block's required positional parameters.
+fn eval_closure_for_plugin(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    closure: Spanned<Closure>,
+    args: Vec<Value>,
+) -> Result<Value, ShellError> {
+    let block = engine_state.get_block(closure.item.block_id);
+    let mut callee_stack = stack.captures_to_stack(closure.item.captures.clone());
+
+    for (var, arg) in block.signature.required_positional.iter().zip(args) {
+        if let Some(var_id) = var.var_id {
+            callee_stack.add_var(var_id, arg);
+        }
+    }
+
+    nu_engine::eval_block_with_early_return(
+        engine_state,
+        &mut callee_stack,
+        block,
+        nu_protocol::PipelineData::empty(),
+        false,
+        false,
+    )
+    .map(|data| data.into_value(closure.span))
+}
+
+/// How long a [`LivePlugin`] may sit with no calls against it before [`reap_idle_plugins`] kills
+/// its process and drops it from [`PERSISTENT_PLUGINS`].
+const PERSISTENT_PLUGIN_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How often the reaper thread started by [`ensure_reaper_started`] wakes up to check every
+/// [`LivePlugin`] against [`PERSISTENT_PLUGIN_IDLE_TIMEOUT`].
+const PERSISTENT_PLUGIN_REAP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// A [`PluginKind::LongLived`] plugin's process, kept alive between calls, plus what's needed to
+/// let multiple concurrent [`call_plugin_persistent`] calls share its one pair of pipes safely:
+/// each call tags its request with a fresh id, and the single background reader thread
+/// ([`spawn_response_reader`]) demultiplexes responses back to the right caller by that same id,
+/// so one call's bytes can never end up decoded as another's response.
+///
+/// Held behind an `Arc` in [`PERSISTENT_PLUGINS`] so a call can clone its handle to this and drop
+/// the registry lock before blocking on the plugin's response, instead of holding the whole
+/// registry hostage for every other plugin while it waits.
+struct LivePlugin {
+    // `None` once `reap_idle_plugins` has taken and `wait()`-ed it.
+    child: std::sync::Mutex<Option<std::process::Child>>,
+    stdin: std::sync::Mutex<Option<nu_pipes::io::OwningPipeWriter>>,
+    encoding: Arc<dyn PluginCodec>,
+    next_id: std::sync::atomic::AtomicU64,
+    pending: Arc<std::sync::Mutex<HashMap<u64, mpsc::Sender<Vec<u8>>>>>,
+    #[allow(dead_code)] // never joined; the thread exits once the plugin closes its stdout
+    reader_handle: std::thread::JoinHandle<()>,
+    last_used: std::sync::Mutex<std::time::Instant>,
+}
+
+/// Persistent plugin processes, keyed by the `(filename, shell)` they were spawned from.
+/// Populated lazily the first time [`call_plugin_persistent`] sees a given key and reused on
+/// every later call against it, so a long-lived plugin's in-memory state survives across calls
+/// instead of being recreated per invocation the way [`call_plugin`] recreates it.
+static PERSISTENT_PLUGINS: std::sync::OnceLock<
+    std::sync::Mutex<HashMap<(PathBuf, Option<PathBuf>), Arc<LivePlugin>>>,
+> = std::sync::OnceLock::new();
+
+/// Makes sure [`reap_idle_plugins`] is running on a background thread, no matter how many times
+/// this is called - the first call starts it, every later one is a no-op.
+fn ensure_reaper_started() {
+    static REAPER_STARTED: std::sync::Once = std::sync::Once::new();
+    REAPER_STARTED.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(PERSISTENT_PLUGIN_REAP_INTERVAL);
+            reap_idle_plugins();
+        });
+    });
+}
+
+/// Evicts every [`LivePlugin`] that's been idle for longer than [`PERSISTENT_PLUGIN_IDLE_TIMEOUT`]:
+/// closes our end of its stdin (which makes its `serve_plugin` persistent loop see EOF and exit on
+/// its own) and waits for it to exit, so it doesn't accumulate as a zombie.
+fn reap_idle_plugins() {
+    let pool = PERSISTENT_PLUGINS.get_or_init(|| std::sync::Mutex::new(HashMap::new()));
+
+    let idle_keys: Vec<_> = {
+        let guard = pool
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        guard
+            .iter()
+            .filter(|(_, live)| {
+                live.last_used
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .elapsed()
+                    >= PERSISTENT_PLUGIN_IDLE_TIMEOUT
+            })
+            .map(|(key, _)| key.clone())
+            .collect()
+    };
 
-        Ok(res)
+    for key in idle_keys {
+        let evicted = pool
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&key);
+        if let Some(live) = evicted {
+            trace_pipe!("Reaping idle long-lived plugin at {:?}", key.0);
+            // Closing our end of stdin makes the plugin's `serve_plugin` persistent loop see EOF
+            // and exit on its own, so `wait()` below doesn't block forever.
+            if let Some(mut stdin) = live
+                .stdin
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .take()
+            {
+                let _ = stdin.close();
+            }
+            if let Some(mut child) = live
+                .child
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .take()
+            {
+                let _ = child.wait();
+            }
+        }
+    }
+}
+
+/// Spawns the background thread that reads every id-tagged response frame off `stdout` and routes
+/// it to whichever in-flight call registered that id in `pending`, via [`read_framed_with_id`].
+/// Exits (dropping every still-pending sender, which unblocks their callers with an error) once
+/// the plugin closes its end of the pipe or sends a malformed frame.
+fn spawn_response_reader(
+    mut stdout: nu_pipes::io::OwningPipeReader,
+    pending: Arc<std::sync::Mutex<HashMap<u64, mpsc::Sender<Vec<u8>>>>>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        match read_framed_with_id(&mut stdout) {
+            Ok(Some((id, payload))) => {
+                let sender = pending
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .remove(&id);
+                if let Some(sender) = sender {
+                    let _ = sender.send(payload);
+                }
+            }
+            Ok(None) => {
+                trace_pipe!("Long-lived plugin closed its stdout, stopping response reader");
+                break;
+            }
+            Err(err) => {
+                trace_pipe!("Error reading framed response from long-lived plugin: {err}");
+                break;
+            }
+        }
     })
 }
 
+/// Same as [`call_plugin`], but for [`PluginKind::LongLived`](crate::protocol::PluginKind)
+/// plugins: instead of spawning a fresh process and closing its stdin after one call, the process
+/// is looked up (or spawned once and cached) in [`PERSISTENT_PLUGINS`] keyed by `(path, shell)`,
+/// and the call is tagged with a fresh request id via [`write_framed_with_id`] so concurrent calls
+/// against the same process can share its pipes without their frames getting mixed up -
+/// [`spawn_response_reader`] demultiplexes responses back to the matching caller by that id.
+pub(crate) fn call_plugin_persistent(
+    path: &Path,
+    shell: Option<&Path>,
+    plugin_call: PluginCall,
+) -> Result<PluginResponse, ShellError> {
+    ensure_reaper_started();
+
+    let key = (path.to_path_buf(), shell.map(Path::to_path_buf));
+    let pool = PERSISTENT_PLUGINS.get_or_init(|| std::sync::Mutex::new(HashMap::new()));
+
+    // Look up (or spawn and insert) the live plugin, then clone its `Arc` and drop the registry
+    // lock immediately - everything past this point can block on the plugin's response, and
+    // holding the lock that long would stop every other plugin's calls from even starting.
+    let live = {
+        let mut registry = pool
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        if !registry.contains_key(&key) {
+            // Negotiate the newest protocol version we know about, since this is the one call
+            // site that actually wants the plugin to stick around - older versions fall back to
+            // the historical spawn-per-call behavior in `serve_plugin`.
+            let plugin_cmd = create_command(path, shell, plugin_protocol::Version::LATEST, false);
+            let mut command = plugin_cmd.command;
+            let child = command
+                .spawn()
+                .map_err(|err| ShellError::PluginFailedToLoad {
+                    msg: format!("Error spawning long-lived plugin process: {err}"),
+                })?;
+
+            let mut stdout_reader = plugin_cmd.stdout.into_reader();
+            let mut stdin_writer = plugin_cmd.stdin.into_writer();
+            let encoding =
+                get_plugin_encoding(&mut stdin_writer, &mut stdout_reader, &CodecRegistry::new())?;
+
+            let pending = Arc::new(std::sync::Mutex::new(HashMap::new()));
+            let reader_handle = spawn_response_reader(stdout_reader, Arc::clone(&pending));
+
+            registry.insert(
+                key.clone(),
+                Arc::new(LivePlugin {
+                    child: std::sync::Mutex::new(Some(child)),
+                    stdin: std::sync::Mutex::new(Some(stdin_writer)),
+                    encoding,
+                    next_id: std::sync::atomic::AtomicU64::new(0),
+                    pending,
+                    reader_handle,
+                    last_used: std::sync::Mutex::new(std::time::Instant::now()),
+                }),
+            );
+        }
+
+        Arc::clone(registry.get(&key).expect("just inserted above"))
+    };
+
+    *live
+        .last_used
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = std::time::Instant::now();
+
+    let id = live
+        .next_id
+        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let (tx, rx) = mpsc::channel();
+    live.pending
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .insert(id, tx);
+
+    let mut encoded_call = Vec::new();
+    live.encoding.encode_call(&plugin_call, &mut encoded_call)?;
+    {
+        let mut stdin = live
+            .stdin
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let stdin = stdin
+            .as_mut()
+            .ok_or_else(|| ShellError::PluginFailedToLoad {
+                msg: "long-lived plugin's stdin is already closed".into(),
+            })?;
+        write_framed_with_id(stdin, id, &encoded_call)
+            .map_err(|err| ShellError::IOError(err.to_string()))?;
+    }
+
+    let payload = rx.recv().map_err(|_| {
+        // The response reader thread dropped our sender without ever sending, meaning the plugin
+        // closed its pipe (or sent garbage) before answering this particular request - drop the
+        // dead entry so the next call respawns instead of hanging the same way.
+        pool.lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&key);
+        ShellError::PluginFailedToLoad {
+            msg: "long-lived plugin closed its pipe before responding".into(),
+        }
+    })?;
+
+    let mut response_reader = std::io::Cursor::new(payload);
+    live.encoding.decode_response(&mut response_reader)
+}
+
 #[doc(hidden)] // Note: not for plugin authors / only used in nu-parser
 /// In this function we assume the plugin is of version 1
 pub fn get_signature(
@@ -175,7 +814,7 @@ pub fn get_signature(
     shell: Option<&Path>,
     current_envs: &HashMap<String, String>,
 ) -> Result<Vec<PluginSignature>, ShellError> {
-    let mut plugin_cmd = create_command(path, shell, plugin_protocol::Version::V1);
+    let mut plugin_cmd = create_command(path, shell, plugin_protocol::Version::V1, false);
     let program_name = plugin_cmd
         .command
         .get_program()
@@ -214,8 +853,9 @@ pub fn get_signature(
     let mut stdout_reader = plugin_cmd.stdout.into_reader();
     let mut stdin_writer = plugin_cmd.stdin.into_writer();
     trace_pipe!("Getting encoding from plugin...");
-    let encoding = get_plugin_encoding(&mut stdout_reader)?;
-    trace_pipe!("Got encoding ({:?}), calling plugin", encoding);
+    let encoding =
+        get_plugin_encoding(&mut stdin_writer, &mut stdout_reader, &CodecRegistry::new())?;
+    trace_pipe!("Got encoding, calling plugin");
 
     // Create message to plugin to indicate that signature is required and
     // send call to plugin asking for signature
@@ -288,8 +928,9 @@ pub fn get_signature(
 ///         name: &str,
 ///         call: &EvaluatedCall,
 ///         input: PluginPipelineData,
-///     ) -> Result<Value, LabeledError> {
-///         Ok(Value::string("Hello, World!".to_owned(), call.head))
+///         engine: &mut EngineInterface<'_, '_>,
+///     ) -> Result<PluginPipelineData, LabeledError> {
+///         Ok(PluginPipelineData::Value(Value::string("Hello, World!".to_owned(), call.head)))
 ///     }
 /// }
 /// ```
@@ -311,13 +952,87 @@ pub trait Plugin {
     /// The `name` is only relevant for plugins that implement multiple commands as the
     /// invoked command will be passed in via this argument. The `call` contains
     /// metadata describing how the plugin was invoked and `input` contains the structured
-    /// data passed to the command implemented by this [Plugin].
+    /// data passed to the command implemented by this [Plugin]. `engine` lets the plugin ask
+    /// Nushell to do things mid-call that only it can do, like evaluating a closure the caller
+    /// passed in or reading config/environment state - see [`EngineInterface`].
+    ///
+    /// Returning [`PluginPipelineData::OutputStream`] (via
+    /// [`PluginPipelineData::output_stream`]/[`PluginPipelineData::output_byte_stream`]) instead
+    /// of [`PluginPipelineData::Value`] sends the output back to Nushell lazily, one item at a
+    /// time, instead of buffering it all into a single value first.
     fn run(
         &mut self,
         name: &str,
         call: &EvaluatedCall,
         input: PluginPipelineData,
-    ) -> Result<Value, LabeledError>;
+        engine: &mut EngineInterface<'_, '_>,
+    ) -> Result<PluginPipelineData, LabeledError>;
+
+    /// Translation tables for this plugin's command help, keyed by locale and then by
+    /// message id. `serve_plugin` consults this to localize the usage/extra usage strings in
+    /// [`Plugin::signature`]'s output and in `--help`, picking a locale from the `NU_LANG`/`LANG`
+    /// environment variable and falling back to the string [`Plugin::signature`] returned when no
+    /// translation is found for it. Plugins that only ship one language can leave this at its
+    /// default (empty catalog).
+    fn localizations(&self) -> Localizations {
+        Localizations::default()
+    }
+}
+
+/// A plugin-authored table of command help translations: locale -> message id -> translated
+/// string. A message id is simply the default-language string returned by [`Plugin::signature`]
+/// (e.g. a `usage` or `extra_usage` value) - there's no separate id namespace to keep in sync.
+#[derive(Debug, Default, Clone)]
+pub struct Localizations(HashMap<String, HashMap<String, String>>);
+
+impl Localizations {
+    /// Builds a catalog from `(locale, message_id, translation)` triples.
+    pub fn new(
+        entries: impl IntoIterator<Item = (&'static str, &'static str, &'static str)>,
+    ) -> Self {
+        let mut catalog: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for (locale, message_id, translation) in entries {
+            catalog
+                .entry(locale.to_string())
+                .or_default()
+                .insert(message_id.to_string(), translation.to_string());
+        }
+        Localizations(catalog)
+    }
+
+    /// Looks up `message_id` in `locale`'s table, falling back to `message_id` itself (the
+    /// default-language string) when no translation exists for it.
+    fn resolve<'a>(&'a self, locale: &str, message_id: &'a str) -> &'a str {
+        self.0
+            .get(locale)
+            .and_then(|table| table.get(message_id))
+            .map(String::as_str)
+            .unwrap_or(message_id)
+    }
+}
+
+/// Reads the locale to localize plugin help into from the environment, preferring `NU_LANG` over
+/// the more general `LANG` since it lets a user pick a different language for Nushell plugins
+/// specifically.
+fn active_locale() -> Option<String> {
+    env::var("NU_LANG").ok().or_else(|| env::var("LANG").ok())
+}
+
+/// Replaces `sig`'s usage/extra usage with their translations from `catalog` for `locale`, if
+/// any. A no-op when `locale` is `None` (no `NU_LANG`/`LANG` set) or when `catalog` has no
+/// translation for the relevant message id.
+fn localize_signature(
+    mut sig: PluginSignature,
+    catalog: &Localizations,
+    locale: Option<&str>,
+) -> PluginSignature {
+    if let Some(locale) = locale {
+        sig.sig.usage = catalog.resolve(locale, &sig.sig.usage).to_string();
+        if !sig.sig.extra_usage.is_empty() {
+            sig.sig.extra_usage = catalog.resolve(locale, &sig.sig.extra_usage).to_string();
+        }
+    }
+    sig
 }
 
 #[derive(Debug, Default)]
@@ -338,9 +1053,7 @@ impl PluginCli {
                 }
                 p if !p.starts_with('-') => {
                     if pos == 0 {
-                        let pipes = serde_json::from_str(p)
-                            .map_err(|err| Error::new(ErrorKind::Other, err))?;
-                        cli.pipes = Some(pipes);
+                        cli.pipes = Some(PluginPipes::decode_from_child(p)?);
                     }
                     pos += 1;
                 }
@@ -351,6 +1064,318 @@ impl PluginCli {
     }
 }
 
+/// Largest single frame [`read_framed`] will allocate a buffer for. Real `PluginCall`/
+/// `PluginResponse` payloads are nowhere near this size; the cap just bounds how much a buggy or
+/// compromised peer can force the host to allocate off an untrusted length prefix.
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Reads one length-prefixed message from `reader`: a 4-byte big-endian length followed by that
+/// many bytes. Returns `Ok(None)` on a clean EOF before any bytes of the length prefix are read,
+/// which [`serve_plugin`]'s persistent-mode loop treats as Nushell having closed the pipe.
+///
+/// Used to frame `PluginCall`/`PluginResponse` messages in persistent mode, where several of them
+/// share one long-lived stream and can't rely on the process exiting to mark where one ends.
+fn read_framed(reader: &mut impl std::io::Read) -> std::io::Result<Option<Vec<u8>>> {
+    let mut len_buf = [0u8; 4];
+    match reader.read_exact(&mut len_buf) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("frame length {len} exceeds the {MAX_FRAME_LEN}-byte maximum"),
+        ));
+    }
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf)?;
+    Ok(Some(buf))
+}
+
+/// Writes `payload` to `writer` as a length-prefixed message: a 4-byte big-endian length followed
+/// by `payload` itself. The counterpart to [`read_framed`].
+fn write_framed(writer: &mut impl std::io::Write, payload: &[u8]) -> std::io::Result<()> {
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Same as [`read_framed`], but for a message that's additionally tagged with an 8-byte
+/// big-endian request id ahead of the length prefix, as [`write_framed_with_id`] writes it.
+/// Used to demultiplex concurrent calls against a [`PluginKind::LongLived`](crate::protocol::PluginKind)
+/// plugin's shared pipes - see [`call_plugin_persistent`].
+fn read_framed_with_id(reader: &mut impl std::io::Read) -> std::io::Result<Option<(u64, Vec<u8>)>> {
+    let mut id_buf = [0u8; 8];
+    match reader.read_exact(&mut id_buf) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let id = u64::from_be_bytes(id_buf);
+    let payload = read_framed(reader)?.ok_or_else(|| {
+        Error::new(
+            ErrorKind::UnexpectedEof,
+            "connection closed between a frame's id and its length prefix",
+        )
+    })?;
+    Ok(Some((id, payload)))
+}
+
+/// Same as [`write_framed`], but prefixes `payload`'s frame with `id` as an 8-byte big-endian
+/// integer, so the reader on the other end can tell which in-flight request this message answers.
+/// The counterpart to [`read_framed_with_id`].
+fn write_framed_with_id(
+    writer: &mut impl std::io::Write,
+    id: u64,
+    payload: &[u8],
+) -> std::io::Result<()> {
+    writer.write_all(&id.to_be_bytes())?;
+    write_framed(writer, payload)
+}
+
+/// What dispatching a [`PluginCall`] resolved to, for [`serve_plugin`] to act on.
+enum DispatchOutcome {
+    /// `PluginCall::Goodbye`: nothing to reply with, and the caller should stop its call loop.
+    Goodbye,
+    /// A single response to encode and send back.
+    Response(PluginResponse),
+    /// The plugin returned [`PluginPipelineData::OutputStream`], and [`dispatch_call`] already
+    /// wrote it to `stdout_writer` as a stream header, one message per item, and a
+    /// [`PluginResponse::StreamEnd`] - there's nothing left for the caller to send.
+    Streamed,
+}
+
+/// Converts a [`Value`] a plugin returned into the [`PluginResponse`] to send back, collapsing
+/// custom values into [`PluginResponse::PluginData`] the same way a direct
+/// [`PluginPipelineData::Value`] result always has.
+fn value_to_response(value: Value) -> PluginResponse {
+    let span = value.span();
+    match value {
+        Value::CustomValue { val, .. } => match bincode::serialize(&val) {
+            Ok(data) => {
+                let name = val.value_string();
+                PluginResponse::PluginData(name, PluginData { data, span })
+            }
+            Err(err) => PluginResponse::Error(
+                ShellError::PluginFailedToEncode {
+                    msg: err.to_string(),
+                }
+                .into(),
+            ),
+        },
+        value => PluginResponse::Value(Box::new(value)),
+    }
+}
+
+/// Encodes `response` and writes it to `writer` as a length-prefixed chunk via [`write_framed`],
+/// so a reader pulling messages off the same stream as a [`PluginResponse::Stream`] header knows
+/// exactly where one ends and the next begins without relying on the codec's own format to be
+/// self-delimiting.
+fn write_framed_response(
+    writer: &mut impl std::io::Write,
+    codec: &dyn PluginCodec,
+    response: &PluginResponse,
+) -> Result<(), ShellError> {
+    let mut encoded = Vec::new();
+    codec.encode_response(response, &mut encoded)?;
+    write_framed(writer, &encoded).map_err(|err| ShellError::IOError(err.to_string()))
+}
+
+/// Writes `stream`'s header directly to `stdout_writer` the same way any other response is sent,
+/// followed by one length-prefixed chunk per item and a final [`PluginResponse::StreamEnd`] -
+/// both framed via [`write_framed`], since unlike the header there can be arbitrarily many of them
+/// sharing the pipe with nothing to mark where one ends and the next begins.
+///
+/// Stops early (without sending `StreamEnd`) if a write fails, which happens once Nushell closes
+/// its end of the pipe - e.g. because it stopped consuming early, as `first 3` would.
+fn write_output_stream(
+    stdout_writer: &mut PipeWriter<'_>,
+    codec: &dyn PluginCodec,
+    stream: OutputStream,
+) {
+    let header_written = codec
+        .encode_response(
+            &PluginResponse::Stream(stream.header_data_type()),
+            stdout_writer,
+        )
+        .and_then(|_| {
+            stdout_writer
+                .flush()
+                .map_err(|e| ShellError::IOError(e.to_string()))
+        });
+    if header_written.is_err() {
+        return;
+    }
+
+    let finished = match stream {
+        OutputStream::List(iter, _) => iter.try_for_each(|value| {
+            write_framed_response(stdout_writer, codec, &PluginResponse::StreamValue(value))
+        }),
+        OutputStream::Bytes(iter, _, _) => iter.try_for_each(|item| {
+            let response = match item {
+                Ok(bytes) => PluginResponse::StreamBytes(bytes),
+                Err(err) => PluginResponse::Error(err.into()),
+            };
+            write_framed_response(stdout_writer, codec, &response)
+        }),
+    };
+
+    if finished.is_ok() {
+        let _ = write_framed_response(stdout_writer, codec, &PluginResponse::StreamEnd);
+    }
+}
+
+/// Writes `iter`'s bytes directly to `output_pipe`, the dedicated pipe this call's `CallInfo`
+/// carried, instead of framing them as [`PluginResponse::StreamBytes`] messages over `stdout`.
+/// Stops early if a write fails, the same as [`write_output_stream`] does when Nushell closes its
+/// end early.
+fn write_output_pipe(
+    output_pipe: PipeFd<PipeWrite>,
+    iter: Box<dyn Iterator<Item = Result<Vec<u8>, ShellError>> + Send + 'static>,
+) {
+    let mut writer = output_pipe.into_writer();
+    for chunk in iter {
+        match chunk {
+            Ok(bytes) if writer.write_all(&bytes).is_ok() => {}
+            _ => break,
+        }
+    }
+    let _ = writer.close();
+}
+
+/// Runs `plugin_call` against `plugin` and returns what to do with the result. See
+/// [`DispatchOutcome`].
+fn dispatch_call(
+    plugin: &mut impl Plugin,
+    plugin_pipes: &PluginPipes,
+    plugin_call: PluginCall,
+    stdin_reader: &mut PipeReader<'_>,
+    stdout_writer: &mut PipeWriter<'_>,
+    codec: &dyn PluginCodec,
+) -> DispatchOutcome {
+    match plugin_call {
+        // Sending the signature back to nushell to create the declaration definition
+        PluginCall::Signature => {
+            let locale = active_locale();
+            let catalog = plugin.localizations();
+            let signatures = plugin
+                .signature()
+                .into_iter()
+                .map(|sig| localize_signature(sig, &catalog, locale.as_deref()))
+                .collect();
+            DispatchOutcome::Response(PluginResponse::Signature(signatures))
+        }
+        PluginCall::CallInfo(call_info) => {
+            let output_pipe = call_info.output_pipe;
+            let input = match call_info.input {
+                CallInput::Value(value) => Ok(PluginPipelineData::Value(value)),
+                CallInput::Data(plugin_data) => {
+                    bincode::deserialize::<Box<dyn CustomValue>>(&plugin_data.data)
+                        .map(|custom_value| Value::custom_value(custom_value, plugin_data.span))
+                        .map_err(|err| ShellError::PluginFailedToDecode {
+                            msg: err.to_string(),
+                        })
+                        .map(PluginPipelineData::Value)
+                }
+                CallInput::Pipe(pipe, dt) => Ok(PluginPipelineData::ExternalStream(
+                    pipe.into_reader(),
+                    dt,
+                    call_info.call.head.into(),
+                )),
+                // Unlike `CallInput::Pipe`, there's no inherited fd to read from - we were only
+                // handed a path, so connect to the named pipe the caller set up for us with
+                // `nu_pipes::named::create_named_pipe_writer` before we can read anything.
+                CallInput::NamedPipe(name, dt) => {
+                    match nu_pipes::named::connect_named_pipe_reader(&name) {
+                        Ok(pipe) => Ok(PluginPipelineData::ExternalStream(
+                            pipe.into_reader(),
+                            dt,
+                            call_info.call.head.into(),
+                        )),
+                        Err(err) => Err(ShellError::GenericError {
+                            error: "Unable to connect to named input pipe".into(),
+                            msg: err.to_string(),
+                            span: None,
+                            help: None,
+                            inner: Vec::new(),
+                        }),
+                    }
+                }
+            };
+
+            let mut engine = EngineInterface::new(stdin_reader, stdout_writer, codec);
+            let output = match input {
+                Ok(input) => plugin.run(&call_info.name, &call_info.call, input, &mut engine),
+                Err(err) => Err(err.into()),
+            };
+
+            match (output, output_pipe) {
+                // The call gave us a dedicated output pipe and the plugin produced a byte stream -
+                // write straight to the pipe instead of framing each chunk as a `StreamBytes`
+                // message over `stdout_writer`.
+                (
+                    Ok(PluginPipelineData::OutputStream(OutputStream::Bytes(iter, datatype, _))),
+                    Some(output_pipe),
+                ) => {
+                    write_output_pipe(output_pipe, iter);
+                    DispatchOutcome::Response(PluginResponse::StreamPiped(datatype))
+                }
+                (Ok(PluginPipelineData::OutputStream(stream)), _) => {
+                    write_output_stream(stdout_writer, codec, stream);
+                    DispatchOutcome::Streamed
+                }
+                (Ok(output), _) => {
+                    DispatchOutcome::Response(value_to_response(output.into_value()))
+                }
+                (Err(err), _) => DispatchOutcome::Response(PluginResponse::Error(err)),
+            }
+        }
+        PluginCall::CollapseCustomValue(plugin_data) => DispatchOutcome::Response(
+            bincode::deserialize::<Box<dyn CustomValue>>(&plugin_data.data)
+                .map_err(|err| ShellError::PluginFailedToDecode {
+                    msg: err.to_string(),
+                })
+                .and_then(|val| val.to_base_value(plugin_data.span))
+                .map(Box::new)
+                .map_err(LabeledError::from)
+                .map_or_else(PluginResponse::Error, PluginResponse::Value),
+        ),
+        PluginCall::CollapseCustomValuePiped(plugin_data_pipe) => {
+            DispatchOutcome::Response(match &plugin_pipes.data {
+                Some(data_pipe) => {
+                    let mut data = Vec::new();
+                    let mut data_reader = PipeReader::new(data_pipe);
+                    data_reader
+                        .read_to_end(&mut data)
+                        .map_err(|err| ShellError::PluginFailedToDecode {
+                            msg: format!("Failed to read custom value data pipe: {err}"),
+                        })
+                        .and_then(|_| {
+                            bincode::deserialize::<Box<dyn CustomValue>>(&data).map_err(|err| {
+                                ShellError::PluginFailedToDecode {
+                                    msg: err.to_string(),
+                                }
+                            })
+                        })
+                        .and_then(|val| val.to_base_value(plugin_data_pipe.span))
+                        .map(Box::new)
+                        .map_err(LabeledError::from)
+                        .map_or_else(PluginResponse::Error, PluginResponse::Value)
+                }
+                None => PluginResponse::Error(
+                    ShellError::PluginFailedToDecode {
+                        msg: "plugin was not given a data pipe to collapse over".into(),
+                    }
+                    .into(),
+                ),
+            })
+        }
+        PluginCall::Goodbye => DispatchOutcome::Goodbye,
+    }
+}
+
 /// Function used to implement the communication protocol between
 /// nushell and an external plugin.
 ///
@@ -364,18 +1389,18 @@ impl PluginCli {
 /// # impl MyPlugin { fn new() -> Self { Self }}
 /// # impl Plugin for MyPlugin {
 /// #     fn signature(&self) -> Vec<PluginSignature> {todo!();}
-/// #     fn run(&mut self, name: &str, call: &EvaluatedCall, input: PluginPipelineData)
-/// #         -> Result<Value, LabeledError> {todo!();}
+/// #     fn run(&mut self, name: &str, call: &EvaluatedCall, input: PluginPipelineData,
+/// #         engine: &mut EngineInterface<'_, '_>) -> Result<PluginPipelineData, LabeledError> {todo!();}
 /// # }
 /// fn main() {
-///    serve_plugin(&mut MyPlugin::new(), MsgPackSerializer)
+///    serve_plugin(&mut MyPlugin::new(), CodecRegistry::new().with_codec("msgpack", MsgPackSerializer))
 /// }
 /// ```
 ///
 /// The object that is expected to be received by nushell is the `PluginResponse` struct.
 /// The `serve_plugin` function should ensure that it is encoded correctly and sent
 /// to StdOut for nushell to decode and and present its result.
-pub fn serve_plugin(plugin: &mut impl Plugin, codec: impl PluginCodec) {
+pub fn serve_plugin(plugin: &mut impl Plugin, registry: CodecRegistry) {
     let cli = match PluginCli::parse_args() {
         Ok(cli) => cli,
         Err(err) => {
@@ -385,220 +1410,250 @@ pub fn serve_plugin(plugin: &mut impl Plugin, codec: impl PluginCodec) {
     };
 
     if cli.help {
-        print_help(plugin, codec);
+        print_help(plugin, &registry);
         exit(0);
     }
 
     let plugin_pipes = cli.pipes.unwrap_or(PluginPipes {
         stdin: PipeFd::stdin(),
         stdout: PipeFd::stdout(),
+        data: None,
+        // Running without pipe args means we weren't spawned by `create_command`, e.g. someone
+        // invoked the plugin binary directly for debugging - fall back to the original
+        // single-call behavior rather than assuming persistence was negotiated.
+        protocol_version: plugin_protocol::Version::V1,
     });
 
     let mut stdout_writer = PipeWriter::new(&plugin_pipes.stdout);
     let mut stdin_reader = PipeReader::new(&plugin_pipes.stdin);
 
-    trace_pipe!("Sending our encoding to nushell...");
+    trace_pipe!("Advertising our codecs to nushell...");
+
+    // Tell nushell every codec we can speak, framed the same way a stream chunk would be, then
+    // wait for it to tell us which one it picked - the counterpart to `get_plugin_encoding`.
+    let offered = registry.names().collect::<Vec<_>>().join(",");
+    write_framed(&mut stdout_writer, offered.as_bytes())
+        .expect("Failed to advertise our codecs to nushell");
+
+    trace_pipe!("Waiting for nushell to choose a codec...");
+    let codec = {
+        let chosen = read_framed(&mut stdin_reader)
+            .expect("Failed to read nushell's chosen codec")
+            .expect("nushell closed the pipe before choosing a codec");
+        let chosen = String::from_utf8_lossy(&chosen).into_owned();
+        registry
+            .get(&chosen)
+            .unwrap_or_else(|| panic!("nushell chose a codec we didn't offer: {chosen}"))
+    };
+    let codec = codec.as_ref();
 
-    // tell nushell encoding.
-    //
-    //                         1 byte
-    // encoding format: |  content-length  | content    |
+    if !plugin_pipes
+        .protocol_version
+        .supports(plugin_protocol::Capability::Persistent)
     {
-        let encoding = codec.name();
-        let length = encoding.len();
-        let mut encoding_content: Vec<u8> = Vec::with_capacity(length + 1);
-        encoding_content.insert(0, length as u8);
-        encoding_content.extend_from_slice(encoding.as_bytes());
-        stdout_writer
-            .write_all(&encoding_content)
-            .expect("Failed to tell nushell my encoding");
-        stdout_writer
-            .flush()
-            .expect("Failed to tell nushell my encoding when flushing stdout");
+        // Original single-call-then-exit behavior, preserved exactly for plugins/hosts that
+        // didn't negotiate persistence: nushell spawns one process per call and closes stdin
+        // once it's written, so there's nothing to loop over.
+        trace_pipe!("Reading plugin call from nushell...");
+        let plugin_call = codec.decode_call(&mut stdin_reader);
+        trace_pipe!("Read plugin call from nushell");
+
+        let outcome = match plugin_call {
+            Err(err) => DispatchOutcome::Response(PluginResponse::Error(err.into())),
+            Ok(plugin_call) => dispatch_call(
+                plugin,
+                &plugin_pipes,
+                plugin_call,
+                &mut stdin_reader,
+                &mut stdout_writer,
+                codec,
+            ),
+        };
+        match outcome {
+            // A non-persistent host has no reason to send `Goodbye`; there's no response to give
+            // back, so just exit.
+            DispatchOutcome::Goodbye => {}
+            // `dispatch_call` already wrote the stream directly to `stdout_writer`.
+            DispatchOutcome::Streamed => {}
+            DispatchOutcome::Response(response) => {
+                codec
+                    .encode_response(&response, &mut stdout_writer)
+                    .expect("Error encoding response");
+            }
+        }
+        return;
     }
 
-    trace_pipe!("Reading plugin call from nushell...");
-
-    let plugin_call = codec.decode_call(&mut stdin_reader);
-
-    trace_pipe!("Read plugin call from nushell");
+    trace_pipe!("Entering persistent plugin call loop...");
+    loop {
+        // Every call in this loop is tagged with a request id (see `write_framed_with_id`), since
+        // nushell may have several concurrent calls sharing this one pair of pipes against a
+        // `PluginKind::LongLived` plugin and needs the same id echoed back to tell their responses
+        // apart. `dispatch_call`'s `DispatchOutcome::Streamed` case doesn't fit that - it assumes
+        // exclusive use of the pipe for the length of the stream - so a plugin that wants to be
+        // `LongLived` shouldn't return `PluginPipelineData::OutputStream` from `run`.
+        let (id, framed_call) = match read_framed_with_id(&mut stdin_reader) {
+            Ok(Some(framed)) => framed,
+            Ok(None) => {
+                trace_pipe!("Nushell closed the pipe, exiting persistent loop");
+                break;
+            }
+            Err(err) => {
+                eprintln!("Error reading framed plugin call: {err}");
+                break;
+            }
+        };
 
-    match plugin_call {
-        Err(err) => {
-            let response = PluginResponse::Error(err.into());
-            codec
-                .encode_response(&response, &mut stdout_writer)
-                .expect("Error encoding response");
-        }
-        Ok(plugin_call) => {
-            match plugin_call {
-                // Sending the signature back to nushell to create the declaration definition
-                PluginCall::Signature => {
-                    let response = PluginResponse::Signature(plugin.signature());
-                    codec
-                        .encode_response(&response, &mut stdout_writer)
-                        .expect("Error encoding response");
-                }
-                PluginCall::CallInfo(call_info) => {
-                    let input = match call_info.input {
-                        CallInput::Value(value) => Ok(PluginPipelineData::Value(value)),
-                        CallInput::Data(plugin_data) => {
-                            bincode::deserialize::<Box<dyn CustomValue>>(&plugin_data.data)
-                                .map(|custom_value| {
-                                    Value::custom_value(custom_value, plugin_data.span)
-                                })
-                                .map_err(|err| ShellError::PluginFailedToDecode {
-                                    msg: err.to_string(),
-                                })
-                                .map(PluginPipelineData::Value)
-                        }
-                        CallInput::Pipe(pipe, dt) => Ok(PluginPipelineData::ExternalStream(
-                            pipe.into_reader(),
-                            dt,
-                            call_info.call.head.into(),
-                        )),
-                    };
-
-                    let value = match input {
-                        Ok(input) => plugin.run(&call_info.name, &call_info.call, input),
-                        Err(err) => Err(err.into()),
-                    };
-
-                    let response = match value {
-                        Ok(value) => {
-                            let span = value.span();
-                            match value {
-                                Value::CustomValue { val, .. } => match bincode::serialize(&val) {
-                                    Ok(data) => {
-                                        let name = val.value_string();
-                                        PluginResponse::PluginData(name, PluginData { data, span })
-                                    }
-                                    Err(err) => PluginResponse::Error(
-                                        ShellError::PluginFailedToEncode {
-                                            msg: err.to_string(),
-                                        }
-                                        .into(),
-                                    ),
-                                },
-                                value => PluginResponse::Value(Box::new(value)),
-                            }
-                        }
-                        Err(err) => PluginResponse::Error(err),
-                    };
-                    codec
-                        .encode_response(&response, &mut stdout_writer)
-                        .expect("Error encoding response");
-                }
-                PluginCall::CollapseCustomValue(plugin_data) => {
-                    let response = bincode::deserialize::<Box<dyn CustomValue>>(&plugin_data.data)
-                        .map_err(|err| ShellError::PluginFailedToDecode {
-                            msg: err.to_string(),
-                        })
-                        .and_then(|val| val.to_base_value(plugin_data.span))
-                        .map(Box::new)
-                        .map_err(LabeledError::from)
-                        .map_or_else(PluginResponse::Error, PluginResponse::Value);
+        let mut call_reader = std::io::Cursor::new(framed_call);
+        let plugin_call = codec.decode_call(&mut call_reader);
+
+        let outcome = match plugin_call {
+            Err(err) => DispatchOutcome::Response(PluginResponse::Error(err.into())),
+            Ok(plugin_call) => dispatch_call(
+                plugin,
+                &plugin_pipes,
+                plugin_call,
+                &mut stdin_reader,
+                &mut stdout_writer,
+                codec,
+            ),
+        };
 
-                    codec
-                        .encode_response(&response, &mut stdout_writer)
-                        .expect("Error encoding response");
-                }
+        let response = match outcome {
+            DispatchOutcome::Goodbye => {
+                trace_pipe!("Received Goodbye from nushell, exiting persistent loop");
+                break;
             }
-        }
+            // `dispatch_call` already wrote the stream directly to `stdout_writer`, as a header
+            // and length-prefixed items without the request id this loop adds (see above).
+            DispatchOutcome::Streamed => continue,
+            DispatchOutcome::Response(response) => response,
+        };
+
+        let mut encoded_response = Vec::new();
+        codec
+            .encode_response(&response, &mut encoded_response)
+            .expect("Error encoding response");
+        write_framed_with_id(&mut stdout_writer, id, &encoded_response)
+            .expect("Error writing framed response");
     }
 }
 
-fn print_help(plugin: &mut impl Plugin, encoder: impl PluginCodec) {
+fn print_help(plugin: &mut impl Plugin, registry: &CodecRegistry) {
     println!("Nushell Plugin");
-    println!("Encoder: {}", encoder.name());
+    println!(
+        "Encoders: {}",
+        registry.names().collect::<Vec<_>>().join(", ")
+    );
+
+    let locale = active_locale();
+    let catalog = plugin.localizations();
 
     let mut help = String::new();
 
-    plugin.signature().iter().for_each(|signature| {
-        let res = write!(help, "\nCommand: {}", signature.sig.name)
-            .and_then(|_| writeln!(help, "\nUsage:\n > {}", signature.sig.usage))
-            .and_then(|_| {
-                if !signature.sig.extra_usage.is_empty() {
-                    writeln!(help, "\nExtra usage:\n > {}", signature.sig.extra_usage)
-                } else {
-                    Ok(())
-                }
-            })
-            .and_then(|_| {
-                let flags = get_flags_section(None, &signature.sig, |v| format!("{:#?}", v));
-                write!(help, "{flags}")
-            })
-            .and_then(|_| writeln!(help, "\nParameters:"))
-            .and_then(|_| {
-                signature
-                    .sig
-                    .required_positional
-                    .iter()
-                    .try_for_each(|positional| {
-                        writeln!(
-                            help,
-                            "  {} <{}>: {}",
-                            positional.name, positional.shape, positional.desc
-                        )
-                    })
-            })
-            .and_then(|_| {
-                signature
-                    .sig
-                    .optional_positional
-                    .iter()
-                    .try_for_each(|positional| {
+    plugin
+        .signature()
+        .into_iter()
+        .map(|sig| localize_signature(sig, &catalog, locale.as_deref()))
+        .for_each(|signature| {
+            let res = write!(help, "\nCommand: {}", signature.sig.name)
+                .and_then(|_| writeln!(help, "\nUsage:\n > {}", signature.sig.usage))
+                .and_then(|_| {
+                    if !signature.sig.extra_usage.is_empty() {
+                        writeln!(help, "\nExtra usage:\n > {}", signature.sig.extra_usage)
+                    } else {
+                        Ok(())
+                    }
+                })
+                .and_then(|_| {
+                    let flags = get_flags_section(None, &signature.sig, |v| format!("{:#?}", v));
+                    write!(help, "{flags}")
+                })
+                .and_then(|_| writeln!(help, "\nParameters:"))
+                .and_then(|_| {
+                    signature
+                        .sig
+                        .required_positional
+                        .iter()
+                        .try_for_each(|positional| {
+                            writeln!(
+                                help,
+                                "  {} <{}>: {}",
+                                positional.name, positional.shape, positional.desc
+                            )
+                        })
+                })
+                .and_then(|_| {
+                    signature
+                        .sig
+                        .optional_positional
+                        .iter()
+                        .try_for_each(|positional| {
+                            writeln!(
+                                help,
+                                "  (optional) {} <{}>: {}",
+                                positional.name, positional.shape, positional.desc
+                            )
+                        })
+                })
+                .and_then(|_| {
+                    if let Some(rest_positional) = &signature.sig.rest_positional {
                         writeln!(
                             help,
-                            "  (optional) {} <{}>: {}",
-                            positional.name, positional.shape, positional.desc
+                            "  ...{} <{}>: {}",
+                            rest_positional.name, rest_positional.shape, rest_positional.desc
                         )
-                    })
-            })
-            .and_then(|_| {
-                if let Some(rest_positional) = &signature.sig.rest_positional {
-                    writeln!(
-                        help,
-                        "  ...{} <{}>: {}",
-                        rest_positional.name, rest_positional.shape, rest_positional.desc
-                    )
-                } else {
-                    Ok(())
-                }
-            })
-            .and_then(|_| writeln!(help, "======================"));
+                    } else {
+                        Ok(())
+                    }
+                })
+                .and_then(|_| writeln!(help, "======================"));
 
-        if res.is_err() {
-            println!("{res:?}")
-        }
-    });
+            if res.is_err() {
+                println!("{res:?}")
+            }
+        });
 
     println!("{help}")
 }
 
+/// Negotiates a codec with a freshly-spawned plugin: reads the comma-joined list of codec names it
+/// advertises over `child_stdout`, picks the first one `registry` also recognizes, writes that
+/// name back over `stdin_writer` so the plugin knows what to use, and returns the matching codec.
 pub fn get_plugin_encoding(
+    stdin_writer: &mut impl std::io::Write,
     child_stdout: &mut impl std::io::BufRead,
-) -> Result<EncodingType, ShellError> {
-    let mut length_buf = [0u8; 1];
-    child_stdout
-        .read_exact(&mut length_buf)
-        .map_err(|e| ShellError::PluginFailedToLoad {
-            msg: format!("unable to get encoding from plugin: {e}"),
-        })?;
-
-    let mut buf = vec![0u8; length_buf[0] as usize];
-    child_stdout
-        .read_exact(&mut buf)
+    registry: &CodecRegistry,
+) -> Result<Arc<dyn PluginCodec>, ShellError> {
+    let offered = read_framed(child_stdout)
         .map_err(|e| ShellError::PluginFailedToLoad {
             msg: format!("unable to get encoding from plugin: {e}"),
+        })?
+        .ok_or_else(|| ShellError::PluginFailedToLoad {
+            msg: "plugin closed its pipe before advertising a codec".into(),
         })?;
+    let offered = String::from_utf8_lossy(&offered);
+
+    let chosen_name = offered
+        .split(',')
+        .find(|name| registry.get(name).is_some())
+        .ok_or_else(|| {
+            let offered = offered.into_owned();
+            ShellError::PluginFailedToLoad {
+                msg: format!("plugin offered no codec we support: {offered}"),
+            }
+        })?
+        .to_string();
 
-    EncodingType::try_from_bytes(&buf).ok_or_else(|| {
-        let encoding_for_debug = String::from_utf8_lossy(&buf);
+    write_framed(stdin_writer, chosen_name.as_bytes()).map_err(|e| {
         ShellError::PluginFailedToLoad {
-            msg: format!("get unsupported plugin encoding: {encoding_for_debug}"),
+            msg: format!("unable to choose plugin encoding: {e}"),
         }
-    })
+    })?;
+
+    Ok(registry
+        .get(&chosen_name)
+        .expect("chosen_name was just matched against this registry"))
 }
 
 pub struct CommandBuilder<'a, C: AsRef<OsStr>> {
@@ -6,25 +6,54 @@ use std::io::Read;
 
 pub use evaluated_call::EvaluatedCall;
 use nu_pipes::{
-    unidirectional::{PipeRead, UnOpenedPipe},
-    PipeReader,
+    unidirectional::{PipeRead, PipeWrite, UnOpenedPipe},
+    PipeFd, PipeReader,
+};
+use nu_protocol::{
+    engine::Closure, PluginSignature, ShellError, Span, Spanned, StreamDataType, Value,
 };
-use nu_protocol::{PluginSignature, ShellError, Span, Value};
 pub use plugin_custom_value::PluginCustomValue;
 pub use plugin_data::PluginData;
 use serde::{Deserialize, Serialize};
 
+/// Whether a plugin's process is spawned fresh for every call, or kept alive and reused across
+/// calls. Set via `PluginSignature::plugin_kind`; defaults to [`PluginKind::OneShot`] for plugins
+/// that don't opt in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PluginKind {
+    /// A fresh process is spawned, handshaked, and torn down for every call. The historical
+    /// behavior, and the only one that supports per-call streaming pipes (see
+    /// [`CallInfo::output_pipe`]), since those rely on file descriptor inheritance at spawn time.
+    #[default]
+    OneShot,
+    /// The process is spawned once and kept alive in a pool keyed by `(filename, shell)`, reused
+    /// across calls, and evicted after sitting idle - see `call_plugin_persistent` in
+    /// `nu_plugin::plugin`. Concurrent calls against the same process are multiplexed over its
+    /// one pair of pipes by tagging each request with an id and demultiplexing responses by that
+    /// same id, rather than each call getting pipes of its own.
+    LongLived,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CallInfo {
     pub name: String,
     pub call: EvaluatedCall,
     pub input: CallInput,
+    /// The write end of a dedicated pipe the plugin can stream its output over as raw bytes,
+    /// instead of returning a single buffered value or streaming it item-by-item over the regular
+    /// response channel. Only present when this command's `PluginSignature` set
+    /// `supports_pipelined_output`; the plugin is free to ignore it and respond normally instead.
+    /// See [`PluginResponse::StreamPiped`].
+    pub output_pipe: Option<PipeFd<PipeWrite>>,
 }
 
 #[derive(Debug)]
 pub enum PluginPipelineData {
     Value(Value),
     ExternalStream(PipeReader, Option<Span>),
+    /// Output the plugin wants to hand back lazily, one item at a time, instead of as a single
+    /// buffered [`Value`]. See [`OutputStream`].
+    OutputStream(OutputStream),
 }
 
 impl From<PluginPipelineData> for Value {
@@ -34,6 +63,24 @@ impl From<PluginPipelineData> for Value {
 }
 
 impl PluginPipelineData {
+    /// Wraps a lazily-produced stream of values as plugin output, for `serve_plugin` to send back
+    /// to Nushell item-by-item via [`PluginResponse::Stream`] instead of collecting it into one
+    /// buffered [`Value`] up front.
+    pub fn output_stream(stream: impl Iterator<Item = Value> + Send + 'static, span: Span) -> Self {
+        PluginPipelineData::OutputStream(OutputStream::List(Box::new(stream), span))
+    }
+
+    /// Wraps a lazily-produced stream of raw bytes as plugin output, for `serve_plugin` to send
+    /// back to Nushell chunk-by-chunk via [`PluginResponse::Stream`] instead of collecting it into
+    /// one buffered [`Value`] up front.
+    pub fn output_byte_stream(
+        stream: impl Iterator<Item = Result<Vec<u8>, ShellError>> + Send + 'static,
+        datatype: StreamDataType,
+        span: Span,
+    ) -> Self {
+        PluginPipelineData::OutputStream(OutputStream::Bytes(Box::new(stream), datatype, span))
+    }
+
     pub fn into_value(self) -> Value {
         match self {
             PluginPipelineData::Value(value) => value,
@@ -58,6 +105,60 @@ impl PluginPipelineData {
                     ),
                 }
             }
+            // Not normally what a plugin returns from `run`, but `PluginPipelineData` is also
+            // used for input, so this has to handle it somehow: collect it the same way the
+            // non-lazy callers of `into_value` elsewhere already expect.
+            PluginPipelineData::OutputStream(OutputStream::List(iter, span)) => {
+                Value::list(iter.collect(), span)
+            }
+            PluginPipelineData::OutputStream(OutputStream::Bytes(iter, datatype, span)) => {
+                let mut vec = Vec::new();
+                for chunk in iter {
+                    match chunk {
+                        Ok(bytes) => vec.extend(bytes),
+                        Err(err) => return Value::error(err, span),
+                    }
+                }
+                match datatype {
+                    StreamDataType::Binary => Value::binary(vec, span),
+                    StreamDataType::Text => Value::string(String::from_utf8_lossy(&vec), span),
+                }
+            }
+        }
+    }
+}
+
+/// A plugin's lazily-produced output, wrapped in [`PluginPipelineData::OutputStream`] and returned
+/// from [`Plugin::run`](crate::Plugin::run) so `serve_plugin` can send it back to Nushell one item
+/// at a time - as a [`PluginResponse::Stream`] header followed by one message per item and a
+/// [`PluginResponse::StreamEnd`] - instead of collecting it into memory first. Mirrors the
+/// `List`/`Bytes` split Nushell itself draws between
+/// [`ListStream`](nu_protocol::ListStream)/[`RawStream`](nu_protocol::RawStream).
+pub enum OutputStream {
+    List(Box<dyn Iterator<Item = Value> + Send + 'static>, Span),
+    Bytes(
+        Box<dyn Iterator<Item = Result<Vec<u8>, ShellError>> + Send + 'static>,
+        StreamDataType,
+        Span,
+    ),
+}
+
+impl std::fmt::Debug for OutputStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputStream::List(..) => f.debug_tuple("List").finish(),
+            OutputStream::Bytes(..) => f.debug_tuple("Bytes").finish(),
+        }
+    }
+}
+
+impl OutputStream {
+    /// The [`StreamDataType`] to advertise in the stream's [`PluginResponse::Stream`] header:
+    /// `None` for a stream of structured values, `Some` for a stream of raw bytes.
+    pub(crate) fn header_data_type(&self) -> Option<StreamDataType> {
+        match self {
+            OutputStream::List(..) => None,
+            OutputStream::Bytes(_, datatype, _) => Some(*datatype),
         }
     }
 }
@@ -67,6 +168,23 @@ pub enum CallInput {
     Value(Value),
     Data(PluginData),
     Pipe(UnOpenedPipe<PipeRead>),
+    /// Like [`CallInput::Pipe`], but the plugin is given a path to open itself instead of an
+    /// inherited file descriptor, because it was set up via
+    /// [`nu_pipes::named::create_named_pipe_writer`] rather than [`nu_pipes::unidirectional::pipe`].
+    /// Used when `PluginSignature::requires_named_pipe_rendezvous` is set - for a plugin spawned
+    /// under a sandbox or a wrapper shell that scrubs every inherited fd but 0/1/2, so handing it
+    /// an fd at spawn time wouldn't survive to see `run`.
+    NamedPipe(String, StreamDataType),
+}
+
+/// The span to attach to the collapsed value, for a [`PluginCall::CollapseCustomValuePiped`]
+/// call. The opaque custom value bytes themselves aren't carried by this message: the plugin
+/// already has the read end of a data pipe from its handshake and streams them from there
+/// instead, so a multi-gigabyte custom value never has to be fully materialized as a
+/// `PluginData` blob on the wire.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PluginDataPipe {
+    pub span: Span,
 }
 
 // Information sent to the plugin
@@ -75,6 +193,50 @@ pub enum PluginCall {
     Signature,
     CallInfo(CallInfo),
     CollapseCustomValue(PluginData),
+    /// Same as [`PluginCall::CollapseCustomValue`], but the custom value's bytes are streamed
+    /// over the plugin's data pipe rather than embedded inline, for constant-memory collapse of
+    /// large custom values. Only sent to plugins whose protocol version advertises
+    /// [`plugin_protocol::Capability::Pipes`](nu_protocol::plugin_protocol::Capability::Pipes).
+    CollapseCustomValuePiped(PluginDataPipe),
+    /// Tells a persistent plugin (one whose protocol version advertises
+    /// [`plugin_protocol::Capability::Persistent`](nu_protocol::plugin_protocol::Capability::Persistent))
+    /// that Nushell is done with it, so it should stop its call loop and exit instead of waiting
+    /// for another message or for stdin to close.
+    Goodbye,
+    /// Nushell's answer to a [`PluginResponse::EngineCall`] the plugin sent earlier in the same
+    /// call. The plugin is blocked waiting for this, so `call_plugin` sends it as soon as the
+    /// request has been serviced rather than waiting for anything else.
+    EngineCallResponse(EngineCallResponse),
+}
+
+/// A request a plugin sends back to Nushell mid-call, asking it to do something the plugin can't
+/// do on its own - evaluate a closure the caller passed in, or read config/environment state that
+/// only Nushell's [`EngineState`](nu_protocol::engine::EngineState)/
+/// [`Stack`](nu_protocol::engine::Stack) know about. Sent as a [`PluginResponse::EngineCall`] over
+/// the same pipe the plugin would otherwise use to send its final response; `call_plugin` answers
+/// with an [`EngineCallResponse`] wrapped in [`PluginCall::EngineCallResponse`] and keeps waiting
+/// for the plugin's real response instead of treating the engine call as the end of the exchange.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum EngineCall {
+    /// Evaluate `closure` with `args` bound to its parameters, using the caller's `EngineState`/
+    /// `Stack`, and return the resulting value.
+    EvalClosure {
+        closure: Spanned<Closure>,
+        args: Vec<Value>,
+    },
+    /// Fetch Nushell's current configuration, as a value.
+    GetConfig,
+    /// Look up an environment variable by name in the caller's environment.
+    GetEnvVar(String),
+}
+
+/// Nushell's answer to an [`EngineCall`]: either the value the plugin asked for, or an error if
+/// servicing the request failed (e.g. the closure itself errored). An environment variable that
+/// isn't set is not an error: it comes back as [`Value::Nothing`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum EngineCallResponse {
+    Value(Value),
+    Error(LabeledError),
 }
 
 /// An error message with debugging information that can be passed to Nushell from the plugin
@@ -177,4 +339,25 @@ pub enum PluginResponse {
     Signature(Vec<PluginSignature>),
     Value(Box<Value>),
     PluginData(String, PluginData),
+    /// Not a final response at all - a request the plugin is making of Nushell mid-call. See
+    /// [`EngineCall`] for what it can ask for. `call_plugin` answers it with a
+    /// [`PluginCall::EngineCallResponse`] and keeps waiting for the plugin's actual response.
+    EngineCall(EngineCall),
+    /// The start of a streamed response: the plugin is about to send its output as a sequence of
+    /// [`PluginResponse::StreamValue`]/[`PluginResponse::StreamBytes`] messages terminated by
+    /// [`PluginResponse::StreamEnd`], rather than as a single buffered [`PluginResponse::Value`].
+    /// `Some(datatype)` means the stream is raw bytes of that [`StreamDataType`]; `None` means a
+    /// stream of structured values.
+    Stream(Option<StreamDataType>),
+    /// One item of a [`PluginResponse::Stream`] of structured values.
+    StreamValue(Value),
+    /// One chunk of a [`PluginResponse::Stream`] of raw bytes.
+    StreamBytes(Vec<u8>),
+    /// Marks the end of a [`PluginResponse::Stream`]. Nothing more will follow for this call.
+    StreamEnd,
+    /// The plugin wrote its entire output, as raw bytes of the given [`StreamDataType`], to the
+    /// dedicated pipe it was given via [`CallInfo::output_pipe`] instead of returning it inline or
+    /// streaming it as [`PluginResponse::StreamBytes`] messages over this response channel. Only
+    /// ever sent for a call whose `CallInfo` provided an output pipe.
+    StreamPiped(StreamDataType),
 }
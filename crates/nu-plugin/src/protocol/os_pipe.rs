@@ -1,18 +1,64 @@
 use std::{
-    io::{Read, Write},
+    io::{BufRead, BufReader, BufWriter, Read, Write},
     thread::JoinHandle,
 };
 
+use cfb8::cipher::{KeyIvInit, StreamCipher};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use nu_protocol::{CustomValue, PipelineData, ShellError, Span, Value};
 use serde::{Deserialize, Serialize};
 
 use super::CallInput;
 
+/// Default capacity (in bytes) of the `BufReader`/`BufWriter` wrapping a raw [`OsPipe`], chosen to
+/// match `nu_pipes::PIPE_BUFFER_CAPACITY`.
+pub const DEFAULT_PIPE_BUFFER_CAPACITY: usize = 1024 * 8;
+
+/// Whether a [`BufferedPipeWriter`]/[`BufferedPipeReader`] pair zlib-compresses frames, and above
+/// what size. Stored on [`OsPipe`] itself so the mode travels with the pipe across the
+/// serialize/deserialize boundary used to hand a pipe off to a plugin process.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PipeCompression {
+    /// Frames are never compressed: each is written raw, behind a `0` header.
+    Disabled,
+    /// Frames at least `threshold` bytes are zlib-deflated; smaller frames are written raw.
+    Threshold(usize),
+}
+
+impl Default for PipeCompression {
+    fn default() -> Self {
+        PipeCompression::Disabled
+    }
+}
+
+/// Symmetric key material for an encrypted [`OsPipe`] channel, established once by
+/// [`OsPipe::create_with_encryption`]'s handshake and carried along with the rest of `OsPipe`
+/// across the serialize/deserialize boundary used to hand the pipe off to a plugin process.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PipeEncryption {
+    /// Frames cross the pipe in plaintext, exactly as before encryption support existed.
+    Disabled,
+    /// Frames are encrypted with AES-128 in CFB8 mode under this key/IV.
+    Enabled { key: [u8; 16], iv: [u8; 16] },
+}
+
+impl Default for PipeEncryption {
+    fn default() -> Self {
+        PipeEncryption::Disabled
+    }
+}
+
 #[cfg(windows)]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct OsPipe {
     pub span: Span,
 
+    #[serde(default)]
+    pub compression: PipeCompression,
+
+    #[serde(default)]
+    pub encryption: PipeEncryption,
+
     #[serde(with = "windows_handle_serialization")]
     read_handle: Option<windows::Win32::Foundation::HANDLE>,
 
@@ -25,30 +71,61 @@ pub struct OsPipe {
 pub struct OsPipe {
     pub span: Span,
 
+    #[serde(default)]
+    pub compression: PipeCompression,
+
+    #[serde(default)]
+    pub encryption: PipeEncryption,
+
     read_fd: libc::c_int,
     write_fd: libc::c_int,
 }
 
 impl OsPipe {
+    /// Creates a pipe with compression and encryption disabled.
     pub fn create(span: Span) -> Result<Self, PipeError> {
+        Self::create_with_options(span, PipeCompression::Disabled, false)
+    }
+
+    /// Creates a pipe that zlib-compresses frames at or above `compression`'s threshold.
+    pub fn create_with_compression(
+        span: Span,
+        compression: PipeCompression,
+    ) -> Result<Self, PipeError> {
+        Self::create_with_options(span, compression, false)
+    }
+
+    /// Creates a pipe whose frames are encrypted end-to-end. See [`negotiate_encryption`] for the
+    /// handshake that derives the AES-128 key.
+    pub fn create_with_encryption(span: Span) -> Result<Self, PipeError> {
+        Self::create_with_options(span, PipeCompression::Disabled, true)
+    }
+
+    pub fn create_with_options(
+        span: Span,
+        compression: PipeCompression,
+        encrypted: bool,
+    ) -> Result<Self, PipeError> {
         #[cfg(unix)]
-        {
+        let mut pipe = {
             use libc::pipe;
 
             let mut fds: [libc::c_int; 2] = [0; 2];
             let result = unsafe { pipe(fds.as_mut_ptr()) };
             if result == 0 {
-                Ok(OsPipe {
+                OsPipe {
                     span,
+                    compression,
+                    encryption: PipeEncryption::Disabled,
                     read_fd: fds[0],
                     write_fd: fds[1],
-                })
+                }
             } else {
-                Err(PipeError::UnexpectedInvalidPipeHandle)
+                return Err(PipeError::UnexpectedInvalidPipeHandle);
             }
-        }
+        };
         #[cfg(windows)]
-        {
+        let mut pipe = {
             use windows::Win32::Security::SECURITY_ATTRIBUTES;
             use windows::Win32::System::Pipes::CreatePipe;
 
@@ -64,16 +141,22 @@ impl OsPipe {
             unsafe { CreatePipe(&mut read_handle, &mut write_handle, Some(&attributes), 0) }
                 .map_err(|e| PipeError::FailedToCreatePipe(OSError(e)))?;
 
-            Ok(OsPipe {
+            OsPipe {
                 span,
+                compression,
+                encryption: PipeEncryption::Disabled,
                 read_handle: Some(read_handle),
                 write_handle: Some(write_handle),
-            })
-        }
+            }
+        };
         #[cfg(not(any(unix, windows)))]
-        {
-            Err(PipeError::UnsupportedPlatform)
+        let mut pipe: OsPipe = return Err(PipeError::UnsupportedPlatform);
+
+        if encrypted {
+            pipe.encryption = negotiate_encryption().map_err(|_| PipeError::HandshakeFailed)?;
         }
+
+        Ok(pipe)
     }
 
     pub fn close(&mut self) -> Result<(), PipeError> {
@@ -84,7 +167,7 @@ impl OsPipe {
             let (read_res, write_res) = unsafe { (close(self.read_fd), close(self.write_fd)) };
 
             if read_res < 0 || write_res < 0 {
-                return Err(PipeError::FailedToClose);
+                return Err(PipeError::FailedToClose(None));
             }
 
             Ok(())
@@ -209,6 +292,317 @@ impl std::io::Write for OsPipe {
     }
 }
 
+/// Writes `value` as an unsigned LEB128 varint, least-significant group first.
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> std::io::Result<()> {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        writer.write_all(&[byte])?;
+        if value == 0 {
+            return Ok(());
+        }
+    }
+}
+
+/// Reads a varint written by [`write_varint`]. A premature EOF (including one at the very first
+/// byte, meaning the pipe's write end was closed with no more frames coming) surfaces as
+/// `std::io::ErrorKind::UnexpectedEof`, letting callers tell "no more frames" apart from a real
+/// I/O error.
+fn read_varint<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+/// One side's contribution to the encryption handshake: a fresh nonce plus an ephemeral X25519
+/// public key. `create_with_options` owns both the reader and writer end of the pipe up front
+/// (they're a single `pipe(2)`/`CreatePipe` call away, not two independent processes dialing in),
+/// so rather than exchanging `Hello`s over the pipe itself - which would need its own bootstrap
+/// framing before any key exists - both sides of the exchange are generated and combined here.
+struct Hello {
+    nonce: [u8; 16],
+    public_key: x25519_dalek::PublicKey,
+}
+
+fn random_nonce(rng: &mut impl rand::RngCore) -> [u8; 16] {
+    let mut nonce = [0u8; 16];
+    rng.fill_bytes(&mut nonce);
+    nonce
+}
+
+/// Runs the [`Hello`] handshake and derives the AES-128 key/IV for an encrypted [`OsPipe`]: each
+/// side's ephemeral X25519 keypair is combined via Diffie-Hellman, and the shared secret, both
+/// nonces and the writer's public key are hashed with SHA-256 to produce a 16-byte key and
+/// 16-byte IV.
+fn negotiate_encryption() -> Result<PipeEncryption, ()> {
+    use sha2::{Digest, Sha256};
+    use x25519_dalek::{EphemeralSecret, PublicKey};
+
+    let mut rng = rand::thread_rng();
+
+    let writer_secret = EphemeralSecret::random_from_rng(&mut rng);
+    let writer_hello = Hello {
+        nonce: random_nonce(&mut rng),
+        public_key: PublicKey::from(&writer_secret),
+    };
+
+    let reader_secret = EphemeralSecret::random_from_rng(&mut rng);
+    let reader_hello = Hello {
+        nonce: random_nonce(&mut rng),
+        public_key: PublicKey::from(&reader_secret),
+    };
+
+    let shared_secret = writer_secret.diffie_hellman(&reader_hello.public_key);
+
+    let mut hasher = Sha256::new();
+    hasher.update(shared_secret.as_bytes());
+    hasher.update(writer_hello.nonce);
+    hasher.update(reader_hello.nonce);
+    hasher.update(writer_hello.public_key.as_bytes());
+    let digest = hasher.finalize();
+
+    let mut key = [0u8; 16];
+    let mut iv = [0u8; 16];
+    key.copy_from_slice(&digest[0..16]);
+    iv.copy_from_slice(&digest[16..32]);
+
+    Ok(PipeEncryption::Enabled { key, iv })
+}
+
+type Aes128Cfb8Enc = cfb8::Encryptor<aes::Aes128>;
+type Aes128Cfb8Dec = cfb8::Decryptor<aes::Aes128>;
+
+fn zlib_compress(payload: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(payload)?;
+    encoder.finish()
+}
+
+fn zlib_decompress(compressed: &[u8], uncompressed_len: usize) -> std::io::Result<Vec<u8>> {
+    let mut decoder = ZlibDecoder::new(compressed);
+    let mut decompressed = Vec::with_capacity(uncompressed_len);
+    decoder.read_to_end(&mut decompressed)?;
+    if decompressed.len() != uncompressed_len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "decompressed frame size {} did not match the {} advertised in its header",
+                decompressed.len(),
+                uncompressed_len
+            ),
+        ));
+    }
+    Ok(decompressed)
+}
+
+/// Adapts a `&mut W` so every byte written through it is first run through an AES-128-CFB8
+/// keystream, if one is configured. CFB8 self-synchronizes on ciphertext bytes, so as long as
+/// every byte that crosses the pipe (header varints included) passes through here in order, the
+/// matching [`DecryptingReader`] on the other end stays in lockstep.
+struct EncryptingWriter<'a, W: Write> {
+    inner: &'a mut W,
+    encryptor: Option<&'a mut Aes128Cfb8Enc>,
+}
+
+impl<W: Write> Write for EncryptingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.encryptor.as_deref_mut() {
+            Some(encryptor) => {
+                let mut ciphertext = buf.to_vec();
+                encryptor.apply_keystream(&mut ciphertext);
+                self.inner.write_all(&ciphertext)?;
+                Ok(buf.len())
+            }
+            None => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The read-side counterpart of [`EncryptingWriter`].
+struct DecryptingReader<'a, R: Read> {
+    inner: &'a mut R,
+    decryptor: Option<&'a mut Aes128Cfb8Dec>,
+}
+
+impl<R: Read> Read for DecryptingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if let Some(decryptor) = self.decryptor.as_deref_mut() {
+            decryptor.apply_keystream(&mut buf[..n]);
+        }
+        Ok(n)
+    }
+}
+
+/// Buffers writes to an [`OsPipe`] and length-delimits each payload with a varint byte count, so a
+/// [`BufferedPipeReader`] on the other end can recover message boundaries instead of depending on
+/// `read_to_end`/EOF. This turns a stream of many small `ExternalStream` chunks into a handful of
+/// large `write` syscalls.
+///
+/// When constructed with [`PipeCompression::Threshold`], frames at or above the threshold are
+/// zlib-deflated before being framed; see [`Self::write_frame`] for the wire format. When the pipe
+/// was created with [`PipeEncryption::Enabled`], every byte written - framing header included - is
+/// additionally encrypted with AES-128-CFB8.
+pub struct BufferedPipeWriter {
+    inner: BufWriter<OsPipe>,
+    compression: PipeCompression,
+    encryptor: Option<Aes128Cfb8Enc>,
+}
+
+impl BufferedPipeWriter {
+    /// Creates a `BufferedPipeWriter` with [`DEFAULT_PIPE_BUFFER_CAPACITY`] and the pipe's own
+    /// [`PipeCompression`]/[`PipeEncryption`] mode.
+    pub fn new(pipe: OsPipe) -> Self {
+        Self::with_capacity(DEFAULT_PIPE_BUFFER_CAPACITY, pipe)
+    }
+
+    /// Creates a `BufferedPipeWriter` with a caller-chosen buffer capacity, using the pipe's own
+    /// [`PipeCompression`]/[`PipeEncryption`] mode.
+    pub fn with_capacity(capacity: usize, pipe: OsPipe) -> Self {
+        let compression = pipe.compression;
+        let encryptor = match pipe.encryption {
+            PipeEncryption::Enabled { key, iv } => {
+                Some(Aes128Cfb8Enc::new((&key).into(), (&iv).into()))
+            }
+            PipeEncryption::Disabled => None,
+        };
+        Self {
+            inner: BufWriter::with_capacity(capacity, pipe),
+            compression,
+            encryptor,
+        }
+    }
+
+    /// Writes one framed message. The frame may sit in the internal buffer until it fills up or
+    /// [`Self::flush`] is called - callers streaming many small chunks should flush once after the
+    /// source is exhausted, not after every frame.
+    ///
+    /// A raw frame is a `0` header, a varint byte count, then the payload; every frame is raw with
+    /// compression disabled, and so is a payload shorter than the threshold with
+    /// [`PipeCompression::Threshold`]. A payload at or above the threshold is framed as
+    /// the real uncompressed length, the zlib-deflated byte count, then the deflated bytes, so the
+    /// reader knows how much compressed data to read before inflating. If encryption is enabled,
+    /// the entire frame (header and all) is run through the AES-128-CFB8 keystream before it
+    /// reaches the pipe.
+    pub fn write_frame(&mut self, payload: &[u8]) -> std::io::Result<()> {
+        let mut writer = EncryptingWriter {
+            inner: &mut self.inner,
+            encryptor: self.encryptor.as_mut(),
+        };
+
+        match self.compression {
+            PipeCompression::Threshold(threshold)
+                if !payload.is_empty() && payload.len() >= threshold =>
+            {
+                let compressed = zlib_compress(payload)?;
+                write_varint(&mut writer, payload.len() as u64)?;
+                write_varint(&mut writer, compressed.len() as u64)?;
+                writer.write_all(&compressed)
+            }
+            _ => {
+                write_varint(&mut writer, 0)?;
+                write_varint(&mut writer, payload.len() as u64)?;
+                writer.write_all(payload)
+            }
+        }
+    }
+
+    /// Flushes any buffered frames to the underlying pipe.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        Write::flush(&mut self.inner)
+    }
+}
+
+/// Buffers reads from an [`OsPipe`] and recovers the frames written by a [`BufferedPipeWriter`],
+/// transparently inflating any that were zlib-compressed and decrypting any that were
+/// AES-128-CFB8-encrypted.
+pub struct BufferedPipeReader {
+    inner: BufReader<OsPipe>,
+    decryptor: Option<Aes128Cfb8Dec>,
+}
+
+impl BufferedPipeReader {
+    /// Creates a `BufferedPipeReader` with [`DEFAULT_PIPE_BUFFER_CAPACITY`].
+    pub fn new(pipe: OsPipe) -> Self {
+        Self::with_capacity(DEFAULT_PIPE_BUFFER_CAPACITY, pipe)
+    }
+
+    /// Creates a `BufferedPipeReader` with a caller-chosen buffer capacity.
+    pub fn with_capacity(capacity: usize, pipe: OsPipe) -> Self {
+        let decryptor = match pipe.encryption {
+            PipeEncryption::Enabled { key, iv } => {
+                Some(Aes128Cfb8Dec::new((&key).into(), (&iv).into()))
+            }
+            PipeEncryption::Disabled => None,
+        };
+        Self {
+            inner: BufReader::with_capacity(capacity, pipe),
+            decryptor,
+        }
+    }
+
+    /// Reads the next framed message, or `Ok(None)` once the writer's pipe end has closed with no
+    /// further frames. A `0` header means the bytes that follow are raw; any other header is the
+    /// uncompressed length of a zlib-deflated frame, which is inflated and length-checked before
+    /// being returned.
+    pub fn read_frame(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        let mut reader = DecryptingReader {
+            inner: &mut self.inner,
+            decryptor: self.decryptor.as_mut(),
+        };
+
+        let header = match read_varint(&mut reader) {
+            Ok(header) => header,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        if header == 0 {
+            let len = read_varint(&mut reader)?;
+            let mut payload = vec![0u8; len as usize];
+            reader.read_exact(&mut payload)?;
+            Ok(Some(payload))
+        } else {
+            let uncompressed_len = header as usize;
+            let compressed_len = read_varint(&mut reader)? as usize;
+            let mut compressed = vec![0u8; compressed_len];
+            reader.read_exact(&mut compressed)?;
+            Ok(Some(zlib_decompress(&compressed, uncompressed_len)?))
+        }
+    }
+}
+
+impl Read for BufferedPipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl BufRead for BufferedPipeReader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct StreamCustomValue {
     pub span: Span,
@@ -227,8 +621,11 @@ impl CustomValue for StreamCustomValue {
     }
 
     fn to_base_value(&self, span: Span) -> Result<Value, ShellError> {
-        let val = Vec::new();
-        _ = self.os_pipe.clone().read_to_end(&mut val.clone())?;
+        let mut reader = BufferedPipeReader::new(self.os_pipe.clone());
+        let mut val = Vec::new();
+        while let Some(frame) = reader.read_frame()? {
+            val.extend_from_slice(&frame);
+        }
         Ok(Value::binary(val, span))
     }
 
@@ -260,31 +657,168 @@ pub enum PipeError {
     FailedToCreatePipe(OSError),
     UnsupportedPlatform,
     FailedToClose(Option<OSError>),
+    /// The AES-128 key exchange in [`negotiate_encryption`] couldn't derive a shared key.
+    HandshakeFailed,
+    /// A frame couldn't be decrypted with the pipe's negotiated key (for example because the two
+    /// ends disagree on whether encryption is enabled at all).
+    DecryptFailed,
+}
+
+impl PipeError {
+    /// The [`OSErrorCategory`] behind this failure, so a caller can decide whether it's worth
+    /// retrying without matching on `OSError`'s platform-specific inner type itself.
+    pub fn category(&self) -> OSErrorCategory {
+        match self {
+            PipeError::FailedToCreatePipe(error) => error.category(),
+            PipeError::FailedToClose(Some(error)) => error.category(),
+            PipeError::UnexpectedInvalidPipeHandle => OSErrorCategory::InvalidHandle,
+            PipeError::InvalidPipeName(_)
+            | PipeError::UnsupportedPlatform
+            | PipeError::FailedToClose(None)
+            | PipeError::HandshakeFailed
+            | PipeError::DecryptFailed => OSErrorCategory::Other,
+        }
+    }
 }
 
 impl From<PipeError> for ShellError {
     fn from(error: PipeError) -> Self {
-        match error {
-            PipeError::InvalidPipeName(name) => ShellError::IncorrectValue {
+        if let PipeError::InvalidPipeName(name) = &error {
+            return ShellError::IncorrectValue {
                 msg: format!("Invalid pipe name: {}", name),
                 val_span: Span::unknown(),
                 call_span: Span::unknown(),
-            },
-            PipeError::UnexpectedInvalidPipeHandle => {
-                ShellError::IOError("Unexpected invalid pipe handle".to_string())
-            }
+            };
+        }
+
+        let category = error.category();
+        let msg = match error {
+            PipeError::InvalidPipeName(_) => unreachable!("handled above"),
+            PipeError::UnexpectedInvalidPipeHandle => "unexpected invalid pipe handle".to_string(),
             PipeError::FailedToCreatePipe(error) => {
-                ShellError::IOError(format!("Failed to create pipe: {}", error.0.to_string()))
+                format!("failed to create pipe: {}", error.0)
             }
-            PipeError::UnsupportedPlatform => {
-                ShellError::IOError("Unsupported platform for pipes".to_string())
+            PipeError::UnsupportedPlatform => "unsupported platform for pipes".to_string(),
+            PipeError::FailedToClose(Some(error)) => {
+                format!("failed to close pipe: {}", error.0)
             }
-            PipeError::FailedToClose(e) => match e {
-                Some(e) => {
-                    ShellError::IOError(format!("Failed to close pipe: {}", e.0.to_string()))
-                }
-                None => ShellError::IOError("Failed to close pipe".to_string()),
-            },
+            PipeError::FailedToClose(None) => "failed to close pipe".to_string(),
+            PipeError::HandshakeFailed => {
+                "failed to negotiate an encrypted pipe channel".to_string()
+            }
+            PipeError::DecryptFailed => "failed to decrypt a pipe frame".to_string(),
+        };
+
+        ShellError::GenericError {
+            error: category.label().to_string(),
+            msg,
+            span: None,
+            help: Some(category.help().to_string()),
+            inner: vec![],
+        }
+    }
+}
+
+/// Coarse classification of the OS condition behind a pipe failure, independent of whether it came
+/// from a raw errno (`std::io::ErrorKind`) on Unix or an HRESULT (`windows::core::Error`) on
+/// Windows - so callers like `CallInput::pipe`'s writer thread can decide whether a failure is
+/// worth retrying without matching on platform-specific error types themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OSErrorCategory {
+    /// The reader end has gone away (`EPIPE` / `ERROR_BROKEN_PIPE`); nothing written after this
+    /// will ever be read.
+    BrokenPipe,
+    /// The calling process doesn't have permission to perform the operation.
+    PermissionDenied,
+    /// The call was interrupted by a signal before it could complete; safe to retry as-is.
+    Interrupted,
+    /// The operation would block on a non-blocking handle; safe to retry once it's ready.
+    WouldBlock,
+    /// The fd/HANDLE itself is no longer valid (already closed, or never opened).
+    InvalidHandle,
+    /// Anything else.
+    Other,
+}
+
+impl OSErrorCategory {
+    /// Short label suitable for `ShellError::GenericError`'s `error` field.
+    fn label(self) -> &'static str {
+        match self {
+            OSErrorCategory::BrokenPipe => "broken pipe",
+            OSErrorCategory::PermissionDenied => "permission denied",
+            OSErrorCategory::Interrupted => "interrupted",
+            OSErrorCategory::WouldBlock => "would block",
+            OSErrorCategory::InvalidHandle => "invalid pipe handle",
+            OSErrorCategory::Other => "pipe error",
+        }
+    }
+
+    /// Longer explanation suitable for `ShellError::GenericError`'s `help` field.
+    fn help(self) -> &'static str {
+        match self {
+            OSErrorCategory::BrokenPipe => {
+                "the other end of the pipe was closed before this side finished writing"
+            }
+            OSErrorCategory::PermissionDenied => {
+                "the current process doesn't have permission to access this pipe"
+            }
+            OSErrorCategory::Interrupted => "the operation was interrupted and can be retried",
+            OSErrorCategory::WouldBlock => {
+                "the pipe is non-blocking and wasn't ready; retry once it is"
+            }
+            OSErrorCategory::InvalidHandle => {
+                "the pipe's file descriptor/handle is no longer valid"
+            }
+            OSErrorCategory::Other => "an unexpected OS error occurred while using this pipe",
+        }
+    }
+
+    /// Whether a caller can reasonably retry the same operation as-is - true only for conditions
+    /// that are transient by nature.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            OSErrorCategory::Interrupted | OSErrorCategory::WouldBlock
+        )
+    }
+
+    /// Classifies a raw [`std::io::Error`], as produced by the Unix side of [`OsPipe`]'s
+    /// `Read`/`Write` impls.
+    fn from_io_error(error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::BrokenPipe => OSErrorCategory::BrokenPipe,
+            std::io::ErrorKind::PermissionDenied => OSErrorCategory::PermissionDenied,
+            std::io::ErrorKind::Interrupted => OSErrorCategory::Interrupted,
+            std::io::ErrorKind::WouldBlock => OSErrorCategory::WouldBlock,
+            #[cfg(unix)]
+            _ if error.raw_os_error() == Some(libc::EBADF) => OSErrorCategory::InvalidHandle,
+            _ => OSErrorCategory::Other,
+        }
+    }
+
+    /// Classifies a Windows HRESULT, as produced by the Windows side of [`OsPipe`]'s
+    /// `Read`/`Write` impls.
+    #[cfg(windows)]
+    fn from_windows_error(error: &windows::core::Error) -> Self {
+        use windows::core::HRESULT;
+        use windows::Win32::Foundation::{
+            ERROR_ACCESS_DENIED, ERROR_BROKEN_PIPE, ERROR_INVALID_HANDLE, ERROR_IO_PENDING,
+            ERROR_OPERATION_ABORTED,
+        };
+
+        let code = error.code();
+        if code == HRESULT::from_win32(ERROR_BROKEN_PIPE.0) {
+            OSErrorCategory::BrokenPipe
+        } else if code == HRESULT::from_win32(ERROR_ACCESS_DENIED.0) {
+            OSErrorCategory::PermissionDenied
+        } else if code == HRESULT::from_win32(ERROR_INVALID_HANDLE.0) {
+            OSErrorCategory::InvalidHandle
+        } else if code == HRESULT::from_win32(ERROR_OPERATION_ABORTED.0) {
+            OSErrorCategory::Interrupted
+        } else if code == HRESULT::from_win32(ERROR_IO_PENDING.0) {
+            OSErrorCategory::WouldBlock
+        } else {
+            OSErrorCategory::Other
         }
     }
 }
@@ -295,6 +829,19 @@ pub struct OSError(
     #[cfg(not(windows))] std::io::Error,
 );
 
+impl OSError {
+    pub fn category(&self) -> OSErrorCategory {
+        #[cfg(windows)]
+        {
+            OSErrorCategory::from_windows_error(&self.0)
+        }
+        #[cfg(not(windows))]
+        {
+            OSErrorCategory::from_io_error(&self.0)
+        }
+    }
+}
+
 #[cfg(windows)]
 impl From<windows::core::Error> for OSError {
     fn from(error: windows::core::Error) -> Self {
@@ -372,12 +919,42 @@ impl CallInput {
                     let os_pipe = os_pipe.clone();
 
                     std::thread::spawn(move || {
-                        let mut os_pipe = os_pipe;
+                        let mut writer = BufferedPipeWriter::new(os_pipe);
                         let stdout = stdout;
 
-                        for e in stdout.stream {
-                            let _ = os_pipe.write(e.unwrap().as_slice());
+                        'frames: for e in stdout.stream {
+                            let Ok(bytes) = e else { continue };
+
+                            loop {
+                                match writer.write_frame(bytes.as_slice()) {
+                                    Ok(()) => break,
+                                    // Transient - the other side just wasn't ready yet, try the
+                                    // same frame again rather than dropping it.
+                                    Err(err)
+                                        if OSErrorCategory::from_io_error(&err).is_retryable() =>
+                                    {
+                                        continue
+                                    }
+                                    // Anything else (most commonly a broken pipe) means the
+                                    // reader is gone; there's no point attempting the rest of the
+                                    // stream.
+                                    Err(err) => {
+                                        eprintln!(
+                                            "Error writing pipe frame ({:?}): {err}",
+                                            OSErrorCategory::from_io_error(&err)
+                                        );
+                                        break 'frames;
+                                    }
+                                }
+                            }
                         }
+
+                        // The writer only buffers - without an explicit flush here, frames
+                        // written after the last automatic flush would sit in the `BufWriter`
+                        // forever once this thread (and the `JoinHandle` it's wrapped in)
+                        // finishes, leaving the reader blocked waiting for data that was never
+                        // actually written to the pipe.
+                        let _ = writer.flush();
                     })
                 };
 
@@ -0,0 +1,172 @@
+//! A typed message channel layered on top of [`encoder::PipeChunk`]'s fixed 256-byte framing.
+//!
+//! `PipeChunk`/`ReadToPipeChunk`/`WriteFromPipeChunk` only move one 256-byte blob (or an `End`
+//! sentinel) at a time. [`Sender`]/[`Receiver`] add a length-prefixed frame on top of that so
+//! arbitrary `Serialize`/`DeserializeOwned` values can cross the pipe: a message is encoded as its
+//! big-endian byte length (a `u64`) followed by its bincode payload, then that byte stream is
+//! split into 256-byte `PipeChunk::Data` blobs (the last one zero-padded - the length prefix tells
+//! the receiver exactly how many of those bytes are real). `PipeChunk::End` is reserved as a
+//! sentinel that closes the channel rather than any message, so [`Receiver::recv`] can tell "no
+//! more messages" apart from a peer that died mid-frame.
+
+use std::marker::PhantomData;
+
+use nu_protocol::{ShellError, Span};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::encoder::{PipeChunk, ReadToPipeChunk, WriteFromPipeChunk};
+use super::misc::PipeError;
+use super::{HandleReader, HandleWriter, OsPipe};
+
+/// Creates a connected [`Sender`]/[`Receiver`] pair backed by a fresh [`OsPipe`].
+pub fn channel<T: Serialize + DeserializeOwned>(
+    span: Span,
+) -> Result<(Sender<T>, Receiver<T>), PipeError> {
+    let pipe = OsPipe::create(span)?;
+    let (reader, writer) = pipe.rw();
+
+    Ok((
+        Sender {
+            writer,
+            _marker: PhantomData,
+        },
+        Receiver {
+            reader,
+            _marker: PhantomData,
+        },
+    ))
+}
+
+/// The sending half of a typed [`channel`].
+pub struct Sender<T> {
+    writer: HandleWriter,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize> Sender<T> {
+    /// Serializes `message` and writes it as a length-prefixed, chunked frame.
+    pub fn send(&mut self, message: &T) -> Result<(), ChannelError> {
+        let payload = bincode::serialize(message).map_err(ChannelError::Serialize)?;
+        let len = payload.len() as u64;
+
+        let mut framed = Vec::with_capacity(8 + payload.len());
+        framed.extend_from_slice(&len.to_be_bytes());
+        framed.extend_from_slice(&payload);
+
+        for chunk in framed.chunks(256) {
+            let mut data = [0u8; 256];
+            data[..chunk.len()].copy_from_slice(chunk);
+            self.writer
+                .write_from_pipe_chunk(PipeChunk::Data(Box::new(data)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the `End` sentinel, telling the [`Receiver`] no further messages are coming.
+    pub fn close(mut self) -> Result<(), ChannelError> {
+        self.writer.write_from_pipe_chunk(PipeChunk::End)?;
+        Ok(())
+    }
+}
+
+/// The receiving half of a typed [`channel`].
+pub struct Receiver<T> {
+    reader: HandleReader,
+    _marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Receiver<T> {
+    /// Reads the next length-prefixed, chunked frame and deserializes it.
+    ///
+    /// Returns `Ok(None)` once the sender sends the `End` sentinel. A pipe that closes mid-frame
+    /// (after some but not all of a message's chunks) without ever sending `End` is reported as
+    /// [`ChannelError::TruncatedFrame`] rather than treated as a clean end - only an `End` chunk
+    /// between messages counts as one.
+    pub fn recv(&mut self) -> Result<Option<T>, ChannelError> {
+        let mut buf = Vec::new();
+
+        // Pull chunks until we have the 8-byte length prefix buffered, bailing out early if the
+        // very first chunk we see is the `End` sentinel.
+        while buf.len() < 8 {
+            match self.reader.read_to_pipe_chunk() {
+                Ok(PipeChunk::End) if buf.is_empty() => return Ok(None),
+                Ok(PipeChunk::End) => return Err(ChannelError::TruncatedFrame),
+                Ok(PipeChunk::Data(data)) => buf.extend_from_slice(data.as_ref()),
+                Err(err) if buf.is_empty() => return Err(ChannelError::Io(err)),
+                Err(_) => return Err(ChannelError::TruncatedFrame),
+            }
+        }
+
+        let len = u64::from_be_bytes(buf[..8].try_into().expect("checked above")) as usize;
+
+        while buf.len() < 8 + len {
+            match self.reader.read_to_pipe_chunk() {
+                Ok(PipeChunk::Data(data)) => buf.extend_from_slice(data.as_ref()),
+                Ok(PipeChunk::End) | Err(_) => return Err(ChannelError::TruncatedFrame),
+            }
+        }
+
+        bincode::deserialize(&buf[8..8 + len])
+            .map(Some)
+            .map_err(ChannelError::Deserialize)
+    }
+}
+
+/// An error that can occur while sending or receiving framed messages over a typed [`channel`].
+#[derive(Debug)]
+pub enum ChannelError {
+    /// The pipe ended (or sent `End`) in the middle of a frame, i.e. after some but not all of a
+    /// message's chunks.
+    TruncatedFrame,
+    /// Encoding the message with bincode failed.
+    Serialize(bincode::Error),
+    /// Decoding the received bytes with bincode failed.
+    Deserialize(bincode::Error),
+    /// The underlying pipe returned an I/O error.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ChannelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelError::TruncatedFrame => write!(f, "pipe ended in the middle of a frame"),
+            ChannelError::Serialize(e) => write!(f, "failed to serialize channel message: {e}"),
+            ChannelError::Deserialize(e) => {
+                write!(f, "failed to deserialize channel message: {e}")
+            }
+            ChannelError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ChannelError {}
+
+impl From<std::io::Error> for ChannelError {
+    fn from(e: std::io::Error) -> Self {
+        ChannelError::Io(e)
+    }
+}
+
+impl From<ChannelError> for ShellError {
+    fn from(e: ChannelError) -> Self {
+        ShellError::IOError(e.to_string())
+    }
+}
+
+#[test]
+fn test_channel_send_recv() {
+    let (mut tx, mut rx) = channel::<String>(Span::unknown()).unwrap();
+
+    tx.send(&"hello world".to_string()).unwrap();
+    tx.send(&"a second, longer message spanning more than one chunk".repeat(10))
+        .unwrap();
+    tx.close().unwrap();
+
+    assert_eq!(rx.recv().unwrap(), Some("hello world".to_string()));
+    assert_eq!(
+        rx.recv().unwrap(),
+        Some("a second, longer message spanning more than one chunk".repeat(10))
+    );
+    assert_eq!(rx.recv().unwrap(), None);
+}
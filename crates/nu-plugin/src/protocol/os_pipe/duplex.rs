@@ -0,0 +1,93 @@
+//! A full-duplex connection between two endpoints of the same pipe.
+//!
+//! [`super::OsPipe`] is strictly one-directional: a read end and a write end. A two-way
+//! conversation needs two of them, one per direction, which means juggling two handles (and two
+//! serialized blobs) per connection. [`DuplexPipe`] bundles that pair instead: [`Self::endpoint_a`]
+//! and [`Self::endpoint_b`] each hand back a [`DuplexEndpoint`] that is both readable and
+//! writable, wired straight through to the other endpoint, the way a real bidirectional socket
+//! would be. It's backed by `socketpair(2)` (via `UnixStream::pair`) on unix and a pair of
+//! crossed-over anonymous pipes on Windows - see `pipe_impl::create_duplex_pair`. Like `OsPipe`,
+//! a [`DuplexEndpoint`] is `Serialize`/`Deserialize`, so either side can be handed to a child
+//! process across a `PipelineData`/plugin boundary.
+
+use serde::{Deserialize, Serialize};
+
+use super::misc::PipeError;
+use super::{pipe_impl, Handle, HandleReader, HandleWriter, StreamEncoding};
+
+/// One full-duplex side of a [`DuplexPipe`]: readable and writable at once. See the module docs
+/// for how this differs from [`super::OsPipe`]'s one-directional handles.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplexEndpoint {
+    read_handle: Handle,
+    write_handle: Handle,
+    encoding: StreamEncoding,
+}
+
+impl DuplexEndpoint {
+    /// Returns a combined reader/writer for this endpoint, same as [`OsPipe::rw`](super::OsPipe::rw).
+    pub fn rw(&self) -> (HandleReader, HandleWriter) {
+        (
+            HandleReader::new(self.read_handle, self.encoding),
+            HandleWriter::new(self.write_handle, self.encoding),
+        )
+    }
+}
+
+/// A connected pair of full-duplex endpoints. See the module docs for the difference from
+/// [`super::OsPipe`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplexPipe {
+    a: DuplexEndpoint,
+    b: DuplexEndpoint,
+}
+
+impl DuplexPipe {
+    /// Creates a new connected pair of full-duplex endpoints.
+    pub fn create() -> Result<Self, PipeError> {
+        let ((a_read, a_write), (b_read, b_write)) = pipe_impl::create_duplex_pair()?;
+
+        Ok(DuplexPipe {
+            a: DuplexEndpoint {
+                read_handle: a_read,
+                write_handle: a_write,
+                encoding: StreamEncoding::Raw,
+            },
+            b: DuplexEndpoint {
+                read_handle: b_read,
+                write_handle: b_write,
+                encoding: StreamEncoding::Raw,
+            },
+        })
+    }
+
+    /// One side of the connection. Whatever is written on [`Self::endpoint_b`] is what this side
+    /// reads, and vice versa.
+    pub fn endpoint_a(&self) -> DuplexEndpoint {
+        self.a
+    }
+
+    /// The other side of the connection.
+    pub fn endpoint_b(&self) -> DuplexEndpoint {
+        self.b
+    }
+}
+
+#[test]
+fn test_duplex_pipe_roundtrip() {
+    use std::io::{Read, Write};
+
+    let pipe = DuplexPipe::create().unwrap();
+    let (mut a_reader, mut a_writer) = pipe.endpoint_a().rw();
+    let (mut b_reader, mut b_writer) = pipe.endpoint_b().rw();
+
+    a_writer.write_all(b"ping").unwrap();
+    let mut buf = [0u8; 4];
+    b_reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+
+    b_writer.write_all(b"pong").unwrap();
+    let mut buf = [0u8; 4];
+    a_reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"pong");
+}
@@ -8,7 +8,7 @@ pub enum PipeChunk {
     End,
 }
 
-trait ReadToPipeChunk {
+pub(super) trait ReadToPipeChunk {
     fn read_to_pipe_chunk(&mut self) -> std::io::Result<PipeChunk>;
 }
 
@@ -23,7 +23,7 @@ impl<R: std::io::Read> ReadToPipeChunk for R {
     }
 }
 
-trait WriteFromPipeChunk {
+pub(super) trait WriteFromPipeChunk {
     fn write_from_pipe_chunk(&mut self, chunk: PipeChunk) -> std::io::Result<()>;
 }
 
@@ -24,6 +24,7 @@ pub enum PipeError {
     FailedToRead(Handle, std::io::Error),
     FailedToWrite(Handle, std::io::Error),
     FailedSetNamedPipeHandleState(Handle, OSError),
+    FailedToSetNonblocking(Handle, OSError),
 }
 
 #[allow(dead_code)]
@@ -63,6 +64,10 @@ impl From<PipeError> for std::io::Error {
                 std::io::ErrorKind::Other,
                 format!("Failed to set named pipe handle state: {:?}", error),
             ),
+            PipeError::FailedToSetNonblocking(_, error) => std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("Failed to set pipe handle to non-blocking: {:?}", error),
+            ),
         }
     }
 }
@@ -92,6 +97,10 @@ impl From<PipeError> for ShellError {
                 "Failed to set named pipe handle state {:?}: {:?}",
                 v, e
             )),
+            PipeError::FailedToSetNonblocking(v, e) => ShellError::IOError(format!(
+                "Failed to set pipe handle {:?} to non-blocking: {:?}",
+                v, e
+            )),
         }
     }
 }
@@ -118,6 +127,13 @@ impl std::fmt::Display for PipeError {
             PipeError::FailedSetNamedPipeHandleState(v, e) => {
                 write!(f, "Failed to set named pipe handle state {:?}: {:?}", v, e)
             }
+            PipeError::FailedToSetNonblocking(v, e) => {
+                write!(
+                    f,
+                    "Failed to set pipe handle {:?} to non-blocking: {:?}",
+                    v, e
+                )
+            }
         }
     }
 }
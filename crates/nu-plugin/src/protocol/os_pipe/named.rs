@@ -0,0 +1,83 @@
+//! Named, addressable pipes.
+//!
+//! [`super::OsPipe::create`] only ever produces handles meant to be inherited by a child process
+//! at spawn time - crossing a fork means serializing them (see `test_serialized_pipe`), which only
+//! works because the child already inherited the same underlying handles. [`NamedPipe`] is for the
+//! case where there's no such parent/child relationship at all: a long-lived nu service that other,
+//! independently launched nu invocations attach to by a shared name instead of handle inheritance.
+//!
+//! On unix this is backed by a unix domain socket bound to a filesystem path; on Windows, by a
+//! `\\.\pipe\<name>` named pipe (`CreateNamedPipeW` server side, `WaitNamedPipeW` +
+//! `CreateFileW` client side). Both are full-duplex over a single underlying handle, unlike
+//! `OsPipe`'s two separate unidirectional pipes, so [`Self::rw`] hands out a `HandleReader` over the
+//! pipe's own handle and a `HandleWriter` over a duplicate of it - the same reason a full-duplex unix domain
+//! socket needs a `try_clone`d `UnixStream` to hand a reader and a writer to separate owners.
+
+use super::misc::PipeError;
+use super::{pipe_impl, Handle, HandleReader, HandleWriter, StreamEncoding};
+
+/// A pipe reachable by name instead of by handle inheritance. See the module docs for the
+/// difference from [`super::OsPipe`].
+pub struct NamedPipe {
+    read_handle: Handle,
+    write_handle: Handle,
+    encoding: StreamEncoding,
+}
+
+impl NamedPipe {
+    /// Listens at `name` and blocks until exactly one peer [`connect`](Self::connect)s to it.
+    pub fn bind(name: &str) -> Result<Self, PipeError> {
+        let (read_handle, write_handle) = pipe_impl::bind_named(name)?;
+        Ok(NamedPipe {
+            read_handle,
+            write_handle,
+            encoding: StreamEncoding::Raw,
+        })
+    }
+
+    /// Connects to a peer already [`bind`](Self::bind)ing at `name`.
+    pub fn connect(name: &str) -> Result<Self, PipeError> {
+        let (read_handle, write_handle) = pipe_impl::connect_named(name)?;
+        Ok(NamedPipe {
+            read_handle,
+            write_handle,
+            encoding: StreamEncoding::Raw,
+        })
+    }
+
+    /// Returns a `HandleReader`/`HandleWriter` pair for this pipe, same as
+    /// [`OsPipe::rw`](super::OsPipe::rw).
+    pub fn rw(&self) -> (HandleReader, HandleWriter) {
+        (
+            HandleReader::new(self.read_handle, self.encoding),
+            HandleWriter::new(self.write_handle, self.encoding),
+        )
+    }
+}
+
+#[test]
+fn test_named_pipe_roundtrip() {
+    use std::io::{Read, Write};
+
+    let name = format!("nu-named-pipe-test-{}", std::process::id());
+    let server = std::thread::spawn({
+        let name = name.clone();
+        move || {
+            let pipe = NamedPipe::bind(&name).unwrap();
+            let (_, mut writer) = pipe.rw();
+            writer.write_all(b"hello world").unwrap();
+        }
+    });
+
+    // `bind` blocks in `accept`/`ConnectNamedPipe`, so give the server thread a moment to start
+    // listening before we try to connect.
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    let pipe = NamedPipe::connect(&name).unwrap();
+    let (mut reader, _) = pipe.rw();
+    let mut buf = [0u8; 11];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello world");
+
+    server.join().unwrap();
+}
@@ -1,28 +1,103 @@
-use std::io::Read;
-#[cfg(unix)]
-use std::process::Command;
+use std::{io::Read, sync::OnceLock};
 
 use log::trace;
-use nu_protocol::{CustomValue, ShellError, Span, Spanned, StreamDataType, Value};
+use nu_protocol::{
+    CustomValue, PipelineData, RawStream, ShellError, Span, Spanned, StreamDataType, Value,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::OsPipe;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+/// Size of each chunk pulled off the underlying pipe when streaming. Kept well below the
+/// buffered reader's own capacity so downstream consumers (`| lines`, `| first`, ...) start
+/// seeing values as they arrive instead of waiting for the whole stream.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct StreamCustomValue {
     pub span: Span,
     pub os_pipe: OsPipe,
+    /// Caches a full read of the pipe, so that [`CustomValue`] methods that must return a
+    /// borrowed or fully materialized value (`to_base_value`, `as_string`, `as_binary`) only
+    /// drain the pipe once. Prefer [`StreamCustomValue::into_pipeline_data`] when a lazily
+    /// streamed result is acceptable instead.
+    #[serde(skip, default)]
+    data: OnceLock<Vec<u8>>,
 }
 
 impl StreamCustomValue {
     pub fn new(os_pipe: OsPipe, span: Span) -> Self {
-        Self { span, os_pipe }
+        Self {
+            span,
+            os_pipe,
+            data: OnceLock::new(),
+        }
+    }
+
+    /// Turns the underlying pipe into a lazily-read nushell stream instead of buffering it.
+    /// Each chunk is pulled off the pipe only as the consumer asks for it, so a command like
+    /// `| lines` or `| first` can start producing output before the whole stream has arrived.
+    /// Text streams are decoded lazily by [`RawStream`] itself, which already buffers an
+    /// incomplete UTF-8 sequence at a chunk boundary in its `leftover` field until the next
+    /// chunk completes it.
+    pub fn into_pipeline_data(self) -> PipelineData {
+        let span = self.span;
+        let datatype = self.os_pipe.datatype;
+        let mut reader = self.os_pipe.open_read();
+
+        let chunks = std::iter::from_fn(move || {
+            let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+            match reader.read(&mut buf) {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some(Ok(buf))
+                }
+                Err(e) => Some(Err(ShellError::IOError(e.to_string()))),
+            }
+        });
+
+        let mut stdout = RawStream::new(Box::new(chunks), None, span, None);
+        stdout.datatype = datatype;
+
+        PipelineData::ExternalStream {
+            stdout: Some(stdout),
+            stderr: None,
+            exit_code: None,
+            span,
+            metadata: None,
+            trim_end_newline: false,
+        }
+    }
+
+    /// Fully drains the pipe into memory, once, caching the result for subsequent calls.
+    /// Only used by the [`CustomValue`] methods that are required to hand back a fully
+    /// collected value; prefer [`StreamCustomValue::into_pipeline_data`] for streaming.
+    fn read_all(&self) -> Result<&Vec<u8>, ShellError> {
+        if let Some(cached) = self.data.get() {
+            return Ok(cached);
+        }
+
+        let mut reader = self.os_pipe.open_read();
+        let mut vec = Vec::new();
+        reader
+            .read_to_end(&mut vec)
+            .map_err(|e| ShellError::IOError(e.to_string()))?;
+
+        Ok(self.data.get_or_init(|| vec))
     }
 }
 
 impl CustomValue for StreamCustomValue {
     fn clone_value(&self, span: Span) -> Value {
-        Value::custom_value(Box::new(self.clone()), span)
+        Value::custom_value(
+            Box::new(Self {
+                span,
+                os_pipe: self.os_pipe.clone(),
+                data: OnceLock::new(),
+            }),
+            span,
+        )
     }
 
     fn value_string(&self) -> String {
@@ -42,13 +117,11 @@ impl CustomValue for StreamCustomValue {
             self.typetag_name(),
             self.os_pipe
         );
-        let mut reader = self.os_pipe.reader();
-        let mut vec = Vec::new();
-        _ = reader.read_to_end(&mut vec)?;
+        let vec = self.read_all()?;
 
         match self.os_pipe.datatype {
-            StreamDataType::Binary => Ok(Value::binary(vec, span)),
-            StreamDataType::Text => Ok(Value::string(String::from_utf8_lossy(&vec), span)),
+            StreamDataType::Binary => Ok(Value::binary(vec.clone(), span)),
+            StreamDataType::Text => Ok(Value::string(String::from_utf8_lossy(vec), span)),
         }
     }
 
@@ -74,83 +147,10 @@ impl CustomValue for StreamCustomValue {
     }
 
     fn as_string(&self) -> Result<String, ShellError> {
-        // trace!("{}::as_string for {:?}", self.typetag_name(), self.os_pipe);
-
-        #[cfg(all(unix, debug_assertions))]
-        {
-            let pid = std::process::id();
-            let res_self = Command::new("ps")
-                .arg("-o")
-                .arg("comm=")
-                .arg("-p")
-                .arg(pid.to_string())
-                .output();
-            let self_name = match res_self {
-                Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
-                Err(_) => "".to_string(),
-            };
-            trace!("plugin::self: {} {:?}", pid, self_name);
-            let ppid = std::os::unix::process::parent_id();
-            let res_parent = Command::new("ps")
-                .arg("-o")
-                .arg("comm=")
-                .arg("-p")
-                .arg(ppid.to_string())
-                .output();
-            let parent_name = match res_parent {
-                Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
-                Err(_) => "".to_string(),
-            };
-            trace!("plugin::parent: {} {:?}", ppid, parent_name);
-            let open_fds = Command::new("lsof")
-                .arg("-p")
-                .arg(pid.to_string())
-                .output()
-                .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
-                .unwrap_or_else(|_| "".to_string());
-            trace!("plugin::open fds: \n{}", open_fds);
-            // get permissions and other info for read_fd
-            let info = unsafe { libc::fcntl(self.os_pipe.read_handle.into(), libc::F_GETFL) };
-            if info < 0 {
-                trace!("plugin::fcntl failed: {}", std::io::Error::last_os_error());
-            } else {
-                let acc_mode = match info & libc::O_ACCMODE {
-                    libc::O_RDONLY => "read-only".to_string(),
-                    libc::O_WRONLY => "write-only".to_string(),
-                    libc::O_RDWR => "read-write".to_string(),
-                    e => format!("unknown access mode {}", e),
-                };
-                trace!("plugin::read_fd::access mode: {}", acc_mode);
-            }
-            let info = unsafe { libc::fcntl(self.os_pipe.write_handle.into(), libc::F_GETFL) };
-            if info < 0 {
-                trace!("plugin::fcntl failed: {}", std::io::Error::last_os_error());
-            } else {
-                let acc_mode = match info & libc::O_ACCMODE {
-                    libc::O_RDONLY => "read-only".to_string(),
-                    libc::O_WRONLY => "write-only".to_string(),
-                    libc::O_RDWR => "read-write".to_string(),
-                    e => format!("unknown access mode {}", e),
-                };
-                trace!("plugin::write_fd::access mode: {}", acc_mode);
-            }
-        }
-        // self.os_pipe.close_write()?;
-        let mut reader = self.os_pipe.reader();
-        let mut vec = Vec::new();
-        let time0 = std::time::Instant::now();
-        _ = reader.read_to_end(&mut vec)?;
-        let time1 = std::time::Instant::now();
-        let string = String::from_utf8_lossy(&vec);
-        let time2 = std::time::Instant::now();
-        eprintln!(
-            "plugin::as_string: {} bytes, read: {} ms, decode: {} ms",
-            vec.len(),
-            (time1 - time0).as_micros() as f64 / 1000.0,
-            (time2 - time1).as_micros() as f64 / 1000.0
-        );
-        self.os_pipe.close_read()?;
-        Ok(string.to_string())
+        trace!("{}::as_string for {:?}", self.typetag_name(), self.os_pipe);
+
+        let vec = self.read_all()?;
+        Ok(String::from_utf8_lossy(vec).to_string())
     }
 
     fn as_spanned_string(&self) -> Result<nu_protocol::Spanned<String>, ShellError> {
@@ -159,4 +159,8 @@ impl CustomValue for StreamCustomValue {
             span: self.span,
         })
     }
+
+    fn as_binary(&self) -> Result<&[u8], ShellError> {
+        self.read_all().map(Vec::as_slice)
+    }
 }
@@ -86,3 +86,83 @@ pub fn write_handle(handle: Handle, buf: &[u8]) -> std::io::Result<usize> {
 
     Ok(result as usize)
 }
+
+/// Puts `handle` into non-blocking mode (or back into blocking mode) via `fcntl`'s `O_NONBLOCK`
+/// flag, so `read_handle`/`write_handle` return `EWOULDBLOCK` instead of blocking.
+pub fn set_nonblocking(handle: Handle, nonblocking: bool) -> Result<(), PipeError> {
+    let fd = handle.native();
+
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 {
+        return Err(PipeError::FailedToSetNonblocking(
+            handle,
+            std::io::Error::last_os_error().into(),
+        ));
+    }
+
+    let flags = if nonblocking {
+        flags | libc::O_NONBLOCK
+    } else {
+        flags & !libc::O_NONBLOCK
+    };
+
+    if unsafe { libc::fcntl(fd, libc::F_SETFL, flags) } < 0 {
+        return Err(PipeError::FailedToSetNonblocking(
+            handle,
+            std::io::Error::last_os_error().into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Binds a unix domain socket at `name` and blocks for exactly one peer to
+/// [`connect_named`] to it.
+pub fn bind_named(name: &str) -> Result<(Handle, Handle), PipeError> {
+    use std::os::unix::net::UnixListener;
+
+    // A stale socket file left over from a previous, uncleanly-terminated run would otherwise
+    // make `bind` fail with `EADDRINUSE`.
+    let _ = std::fs::remove_file(name);
+
+    let listener = UnixListener::bind(name).map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+    let (stream, _) = listener
+        .accept()
+        .map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+    handles_from_stream(stream)
+}
+
+/// Connects to a peer [`bind_named`] at `name`.
+pub fn connect_named(name: &str) -> Result<(Handle, Handle), PipeError> {
+    use std::os::unix::net::UnixStream;
+
+    let stream = UnixStream::connect(name).map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+    handles_from_stream(stream)
+}
+
+/// A unix domain socket is one full-duplex fd, unlike `create_pipe`'s two separate unidirectional
+/// ones - so the read and write [`Handle`]s here share the same underlying socket, duplicated via
+/// `try_clone`/`dup(2)` so `HandleReader`/`HandleWriter` can each close their own independently.
+fn handles_from_stream(
+    stream: std::os::unix::net::UnixStream,
+) -> Result<(Handle, Handle), PipeError> {
+    use std::os::fd::IntoRawFd;
+
+    let write_fd = stream
+        .try_clone()
+        .map_err(|e| PipeError::FailedToCreatePipe(e.into()))?
+        .into_raw_fd();
+    let read_fd = stream.into_raw_fd();
+
+    Ok((Handle::Read(read_fd), Handle::Write(write_fd)))
+}
+
+/// Creates a connected pair of full-duplex endpoints via `socketpair(2)` (through
+/// `UnixStream::pair`). Each endpoint is split into its own read/write [`Handle`] pair the same
+/// way [`handles_from_stream`] does for [`bind_named`]/[`connect_named`].
+pub fn create_duplex_pair() -> Result<((Handle, Handle), (Handle, Handle)), PipeError> {
+    use std::os::unix::net::UnixStream;
+
+    let (a, b) = UnixStream::pair().map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+    Ok((handles_from_stream(a)?, handles_from_stream(b)?))
+}
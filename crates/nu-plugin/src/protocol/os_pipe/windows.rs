@@ -114,6 +114,152 @@ impl From<windows::core::Error> for OSError {
     }
 }
 
+/// Puts `handle` into (or out of) `PIPE_NOWAIT` mode via `SetNamedPipeHandleState`, so
+/// `read_handle`/`write_handle` return immediately instead of blocking when there's nothing to
+/// do. Only named pipes support this mode - a plain `CreatePipe` handle will fail here.
+pub fn set_nonblocking(handle: Handle, nonblocking: bool) -> Result<(), PipeError> {
+    use windows::Win32::System::Pipes::{SetNamedPipeHandleState, PIPE_NOWAIT, PIPE_WAIT};
+
+    let mut mode = if nonblocking { PIPE_NOWAIT } else { PIPE_WAIT };
+
+    unsafe { SetNamedPipeHandleState(handle.native(), Some(&mut mode), None, None) }
+        .map_err(|e| PipeError::FailedToSetNonblocking(handle, e.into()))
+}
+
+fn pipe_path(name: &str) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+
+    std::ffi::OsStr::new(&format!(r"\\.\pipe\{name}"))
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect()
+}
+
+/// Creates a `\\.\pipe\<name>` named pipe server and blocks until exactly one peer
+/// [`connect_named`] to it.
+pub fn bind_named(name: &str) -> Result<(Handle, Handle), PipeError> {
+    use windows::Win32::{
+        Foundation::HANDLE,
+        System::Pipes::{
+            ConnectNamedPipe, CreateNamedPipeW, PIPE_ACCESS_DUPLEX, PIPE_READMODE_BYTE,
+            PIPE_TYPE_BYTE, PIPE_WAIT,
+        },
+    };
+
+    let path = pipe_path(name);
+    let handle: HANDLE = unsafe {
+        CreateNamedPipeW(
+            windows::core::PCWSTR(path.as_ptr()),
+            PIPE_ACCESS_DUPLEX,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+            1,
+            4096,
+            4096,
+            0,
+            Some(&DEFAULT_SECURITY_ATTRIBUTES),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(PipeError::FailedToCreatePipe(
+            windows::core::Error::from_win32().into(),
+        ));
+    }
+
+    unsafe { ConnectNamedPipe(handle, None) }
+        .map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+
+    handles_from_duplex(handle)
+}
+
+/// Connects to a peer [`bind_named`] at `name`.
+pub fn connect_named(name: &str) -> Result<(Handle, Handle), PipeError> {
+    use windows::Win32::Storage::FileSystem::{
+        CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_GENERIC_READ, FILE_GENERIC_WRITE, FILE_SHARE_NONE,
+        OPEN_EXISTING,
+    };
+    use windows::Win32::System::Pipes::WaitNamedPipeW;
+
+    let path = pipe_path(name);
+    unsafe { WaitNamedPipeW(windows::core::PCWSTR(path.as_ptr()), 0) }
+        .map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+
+    let handle = unsafe {
+        CreateFileW(
+            windows::core::PCWSTR(path.as_ptr()),
+            (FILE_GENERIC_READ | FILE_GENERIC_WRITE).0,
+            FILE_SHARE_NONE,
+            Some(&DEFAULT_SECURITY_ATTRIBUTES),
+            OPEN_EXISTING,
+            FILE_ATTRIBUTE_NORMAL,
+            None,
+        )
+    }
+    .map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+
+    handles_from_duplex(handle)
+}
+
+/// A named pipe `HANDLE` is already full-duplex (unlike `CreatePipe`'s two separate handles), so
+/// the read and write [`Handle`]s here wrap the same `HANDLE` value and a `DuplicateHandle`d copy
+/// of it, respectively - the same reasoning the unix implementation `dup`s a `UnixStream`'s fd for.
+fn handles_from_duplex(
+    handle: windows::Win32::Foundation::HANDLE,
+) -> Result<(Handle, Handle), PipeError> {
+    use windows::Win32::Foundation::{DuplicateHandle, GetCurrentProcess, DUPLICATE_SAME_ACCESS};
+
+    let process = unsafe { GetCurrentProcess() };
+    let mut write_handle = INVALID_HANDLE_VALUE;
+    unsafe {
+        DuplicateHandle(
+            process,
+            handle,
+            process,
+            &mut write_handle,
+            0,
+            false,
+            DUPLICATE_SAME_ACCESS,
+        )
+    }
+    .map_err(|e| PipeError::FailedToCreatePipe(e.into()))?;
+
+    Ok((Handle::Read(handle), Handle::Write(write_handle)))
+}
+
+/// Windows has no anonymous `socketpair(2)` equivalent, so this composes two ordinary
+/// `CreatePipe` pipes crossed over each other: one endpoint's read handle is the other's write
+/// handle's peer, and vice versa, giving both sides a full-duplex connection out of two
+/// unidirectional ones.
+pub fn create_duplex_pair() -> Result<((Handle, Handle), (Handle, Handle)), PipeError> {
+    let mut a_read = INVALID_HANDLE_VALUE;
+    let mut b_write = INVALID_HANDLE_VALUE;
+    unsafe {
+        CreatePipe(
+            &mut a_read,
+            &mut b_write,
+            Some(&DEFAULT_SECURITY_ATTRIBUTES),
+            0,
+        )
+    }
+    .map_err(|e| PipeError::FailedToCreatePipe(OSError(e)))?;
+
+    let mut b_read = INVALID_HANDLE_VALUE;
+    let mut a_write = INVALID_HANDLE_VALUE;
+    unsafe {
+        CreatePipe(
+            &mut b_read,
+            &mut a_write,
+            Some(&DEFAULT_SECURITY_ATTRIBUTES),
+            0,
+        )
+    }
+    .map_err(|e| PipeError::FailedToCreatePipe(OSError(e)))?;
+
+    Ok((
+        (Handle::Read(a_read), Handle::Write(a_write)),
+        (Handle::Read(b_read), Handle::Write(b_write)),
+    ))
+}
+
 pub mod handle_serialization {
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -1,15 +1,30 @@
+//! A named-pipe transport: unlike [`super::os_pipe::OsPipe`], which is an anonymous pipe that only
+//! survives a single parent -> child handoff at spawn time, a named pipe is addressed by a string
+//! name that any process can `connect` to after the fact. That lets a plugin be launched once and
+//! serviced by multiple, possibly-reconnecting clients, instead of needing a live inherited handle
+//! from the process that originally spawned it.
+//!
+//! On Windows this is backed by `CreateNamedPipe`/`ConnectNamedPipe`; on Unix, a FIFO created with
+//! `mkfifo(2)` at a filesystem path.
+
 use std::io::Read;
 
 use nu_protocol::{CustomValue, ShellError, Span, Value};
 use serde::{Deserialize, Serialize};
 
 trait NamedPipeImpl: Sized {
-    fn create(span: Span) -> Result<Self, PipeError>;
+    /// Creates a fresh named pipe called `name`. On Windows this waits for a peer to connect; a
+    /// Unix FIFO is only created here, and each `read`/`write` opens it anew.
+    fn create(name: String, span: Span) -> Result<Self, PipeError>;
+
+    /// Connects to a named pipe that another process already created with [`Self::create`].
+    fn connect(name: String, span: Span) -> Result<Self, PipeError>;
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct OsPipe {
     pub span: Span,
+    pub name: String,
 
     #[serde(with = "windows_handle_serialization")]
     #[cfg(target_env = "msvc")]
@@ -20,57 +35,180 @@ pub struct OsPipe {
     write_handle: Option<windows::Win32::Foundation::HANDLE>,
 }
 
+/// Validates `name` for the current platform: Windows named pipes live under the reserved
+/// `\\.\pipe\` namespace, while a Unix FIFO is just a filesystem path, so it can't be empty or
+/// contain a NUL byte (which would silently truncate the path `CString` built from it).
+fn validate_pipe_name(name: &str) -> Result<(), PipeError> {
+    if name.is_empty() {
+        return Err(PipeError::InvalidPipeName(name.to_string()));
+    }
+    if name.contains('\0') {
+        return Err(PipeError::InvalidPipeName(name.to_string()));
+    }
+    #[cfg(target_env = "msvc")]
+    {
+        if !name.starts_with(r"\\.\pipe\") {
+            return Err(PipeError::InvalidPipeName(name.to_string()));
+        }
+    }
+    Ok(())
+}
+
 impl NamedPipeImpl for OsPipe {
-    fn create(span: Span) -> Result<Self, PipeError> {
+    fn create(name: String, span: Span) -> Result<Self, PipeError> {
+        validate_pipe_name(&name)?;
+
         #[cfg(target_env = "libc")]
         {
-            use std::libc::mkfifo;
+            use libc::mkfifo;
             use std::os::unix::ffi::OsStrExt;
 
-            let c_name = std::ffi::CString::new(name.as_bytes()).unwrap();
+            let c_name = std::ffi::CString::new(std::ffi::OsStr::new(&name).as_bytes())
+                .map_err(|_| PipeError::InvalidPipeName(name.clone()))?;
             let c_mode = 0o644;
             let result = unsafe { mkfifo(c_name.as_ptr(), c_mode) };
             if result == 0 {
                 Ok(OsPipe { name, span })
             } else {
-                Err(())
+                Err(PipeError::UnexpectedInvalidPipeHandle)
             }
         }
         #[cfg(target_env = "msvc")]
         {
-            use windows::Win32::System::Pipes::CreatePipe;
+            use windows::core::PCWSTR;
+            use windows::Win32::Storage::FileSystem::{
+                FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX,
+            };
+            use windows::Win32::System::Pipes::{
+                CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE, PIPE_WAIT,
+            };
+
+            let wide_name = to_wide_null(&name);
+            let handle = unsafe {
+                CreateNamedPipeW(
+                    PCWSTR(wide_name.as_ptr()),
+                    PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE,
+                    PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+                    1,
+                    DEFAULT_PIPE_BUFFER_SIZE,
+                    DEFAULT_PIPE_BUFFER_SIZE,
+                    0,
+                    None,
+                )
+            };
 
-            let mut read_handle = windows::Win32::Foundation::INVALID_HANDLE_VALUE;
-            let mut write_handle = windows::Win32::Foundation::INVALID_HANDLE_VALUE;
+            if handle.is_invalid() {
+                return Err(PipeError::FailedToCreatePipe(OSError(
+                    windows::core::Error::from_win32(),
+                )));
+            }
 
-            unsafe { CreatePipe(&mut read_handle, &mut write_handle, None, 0) }
+            unsafe { windows::Win32::System::Pipes::ConnectNamedPipe(handle, None) }
                 .map_err(|e| PipeError::FailedToCreatePipe(OSError(e)))?;
 
-            println!("Created pipe.");
+            Ok(OsPipe {
+                span,
+                name,
+                read_handle: Some(handle),
+                write_handle: Some(handle),
+            })
+        }
+    }
+
+    fn connect(name: String, span: Span) -> Result<Self, PipeError> {
+        validate_pipe_name(&name)?;
+
+        #[cfg(target_env = "libc")]
+        {
+            // A FIFO has no separate "connect" step - opening the path (done lazily in `read`/
+            // `write`, since a FIFO open blocks until a peer opens the other end) is all a client
+            // needs to do.
+            Ok(OsPipe { name, span })
+        }
+        #[cfg(target_env = "msvc")]
+        {
+            use windows::core::PCWSTR;
+            use windows::Win32::Foundation::{GENERIC_READ, GENERIC_WRITE};
+            use windows::Win32::Storage::FileSystem::{
+                CreateFileW, FILE_SHARE_MODE, OPEN_EXISTING,
+            };
+
+            let wide_name = to_wide_null(&name);
+            let handle = unsafe {
+                CreateFileW(
+                    PCWSTR(wide_name.as_ptr()),
+                    (GENERIC_READ | GENERIC_WRITE).0,
+                    FILE_SHARE_MODE(0),
+                    None,
+                    OPEN_EXISTING,
+                    Default::default(),
+                    None,
+                )
+            }
+            .map_err(|e| PipeError::FailedToCreatePipe(OSError(e)))?;
 
             Ok(OsPipe {
                 span,
-                read_handle: Some(read_handle),
-                write_handle: Some(write_handle),
+                name,
+                read_handle: Some(handle),
+                write_handle: Some(handle),
             })
         }
     }
 }
 
+#[cfg(target_env = "msvc")]
+const DEFAULT_PIPE_BUFFER_SIZE: u32 = 1024 * 8;
+
+#[cfg(target_env = "msvc")]
+fn to_wide_null(s: &str) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    std::ffi::OsStr::new(s)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect()
+}
+
+impl OsPipe {
+    pub fn close(&mut self) -> Result<(), PipeError> {
+        #[cfg(target_env = "libc")]
+        {
+            // Nothing to close: `read`/`write` open and close the FIFO path per call rather than
+            // holding a long-lived fd, since a FIFO client may come and go across a reconnect.
+            Ok(())
+        }
+        #[cfg(target_env = "msvc")]
+        {
+            use windows::Win32::Foundation::CloseHandle;
+
+            let Some(handle) = self.read_handle else {
+                return Ok(());
+            };
+
+            unsafe { CloseHandle(handle) }
+                .map_err(|e| PipeError::FailedToCreatePipe(OSError(e)))?;
+
+            Ok(())
+        }
+    }
+}
+
 impl std::io::Read for OsPipe {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         #[cfg(target_env = "libc")]
         {
-            use std::libc::{open, read, O_RDONLY};
+            use libc::{open, read, O_RDONLY};
             use std::os::unix::ffi::OsStrExt;
 
-            let c_name = std::ffi::CString::new(self.name.as_bytes()).unwrap();
+            let c_name = std::ffi::CString::new(std::ffi::OsStr::new(&self.name).as_bytes())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
             let fd = unsafe { open(c_name.as_ptr(), O_RDONLY, 0) };
             if fd < 0 {
                 return Err(std::io::Error::last_os_error());
             }
 
             let result = unsafe { read(fd, buf.as_mut_ptr() as *mut _, buf.len()) };
+            unsafe { libc::close(fd) };
             if result < 0 {
                 return Err(std::io::Error::last_os_error());
             }
@@ -96,7 +234,7 @@ impl std::io::Read for OsPipe {
                     None,
                 )
             }
-            .map_err(|e| std::io::Error::from(e))?;
+            .map_err(std::io::Error::from)?;
 
             Ok(bytes_read as usize)
         }
@@ -107,16 +245,18 @@ impl std::io::Write for OsPipe {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         #[cfg(target_env = "libc")]
         {
-            use std::libc::{open, write, O_WRONLY};
+            use libc::{open, write, O_WRONLY};
             use std::os::unix::ffi::OsStrExt;
 
-            let c_name = std::ffi::CString::new(self.name.as_bytes()).unwrap();
+            let c_name = std::ffi::CString::new(std::ffi::OsStr::new(&self.name).as_bytes())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
             let fd = unsafe { open(c_name.as_ptr(), O_WRONLY, 0) };
             if fd < 0 {
                 return Err(std::io::Error::last_os_error());
             }
 
             let result = unsafe { write(fd, buf.as_ptr() as *const _, buf.len()) };
+            unsafe { libc::close(fd) };
             if result < 0 {
                 return Err(std::io::Error::last_os_error());
             }
@@ -142,7 +282,7 @@ impl std::io::Write for OsPipe {
                     None,
                 )
             }
-            .map_err(|e| std::io::Error::from(e))?;
+            .map_err(std::io::Error::from)?;
 
             Ok(bytes_written as usize)
         }
@@ -169,8 +309,8 @@ impl CustomValue for StreamCustomValue {
     }
 
     fn to_base_value(&self, span: Span) -> Result<Value, ShellError> {
-        let val = Vec::new();
-        _ = self.named_pipe.clone().read_to_end(&mut val.clone())?;
+        let mut val = Vec::new();
+        self.named_pipe.clone().read_to_end(&mut val)?;
         Ok(Value::binary(val, span))
     }
 
@@ -185,7 +325,7 @@ impl CustomValue for StreamCustomValue {
 
     #[doc(hidden)]
     fn typetag_deserialize(&self) {
-        todo!()
+        unimplemented!("typetag_deserialize")
     }
 }
 
@@ -299,9 +439,22 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_invalid_pipe_name_rejected() {
+        assert_eq!(
+            validate_pipe_name(""),
+            Err(PipeError::InvalidPipeName("".to_string()))
+        );
+        assert_eq!(
+            validate_pipe_name("bad\0name"),
+            Err(PipeError::InvalidPipeName("bad\0name".to_string()))
+        );
+    }
+
     #[test]
     fn test_pipe() {
-        let mut pipe = OsPipe::create(Span::unknown()).unwrap();
+        let name = "/tmp/nu_plugin_test_pipe".to_string();
+        let mut pipe = OsPipe::create(name, Span::unknown()).unwrap();
         // write hello world to the pipe
         let written = pipe.write("hello world".as_bytes()).unwrap();
 
@@ -313,11 +466,14 @@ mod tests {
 
         assert_eq!(read, 11);
         assert_eq!(buf, "hello world".as_bytes());
+
+        pipe.close().unwrap();
     }
 
     #[test]
     fn test_serialized_pipe() {
-        let mut pipe = OsPipe::create(Span::unknown()).unwrap();
+        let name = "/tmp/nu_plugin_test_serialized_pipe".to_string();
+        let mut pipe = OsPipe::create(name, Span::unknown()).unwrap();
         // write hello world to the pipe
         let written = pipe.write("hello world".as_bytes()).unwrap();
 
@@ -333,5 +489,7 @@ mod tests {
 
         assert_eq!(read, 11);
         assert_eq!(buf, "hello world".as_bytes());
+
+        pipe.close().unwrap();
     }
 }
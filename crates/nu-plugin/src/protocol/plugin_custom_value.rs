@@ -1,12 +1,13 @@
+use std::io::Write;
 use std::path::PathBuf;
 
-use nu_pipes::PipeReader;
+use nu_pipes::{PipeReader, PipeWriter};
 use nu_protocol::{plugin_protocol, CustomValue, ShellError, Value};
 use serde::Serialize;
 
-use crate::plugin::{call_plugin, create_command, get_plugin_encoding};
+use crate::plugin::{call_plugin, create_command, get_plugin_encoding, CodecRegistry};
 
-use super::{PluginCall, PluginData, PluginResponse};
+use super::{PluginCall, PluginData, PluginDataPipe, PluginResponse};
 
 /// An opaque container for a custom value that is handled fully by a plugin
 ///
@@ -47,9 +48,38 @@ impl CustomValue for PluginCustomValue {
         &self,
         span: nu_protocol::Span,
     ) -> Result<nu_protocol::Value, nu_protocol::ShellError> {
-        // We assume here the plugin doesnt support pipe io.
-        let mut plugin_cmd =
-            create_command(&self.filename, self.shell.as_deref(), self.protocol_version);
+        let supports_pipes = self
+            .protocol_version
+            .supports(plugin_protocol::Capability::Pipes);
+
+        let mut plugin_cmd = create_command(
+            &self.filename,
+            self.shell.as_deref(),
+            self.protocol_version,
+            supports_pipes,
+        );
+
+        // When the plugin supports pipe io, stream `self.data` over the dedicated data pipe
+        // instead of inlining it in the call, so collapsing a large custom value doesn't require
+        // holding a second copy of it in memory for the message itself. We start writing before
+        // spawning the child so the write is already in progress (and the pipe's write end is
+        // definitely still open) once the plugin starts reading.
+        let data_writer = supports_pipes.then(|| {
+            let data_pipe = plugin_cmd
+                .data
+                .take()
+                .expect("create_command asked for a data pipe");
+            let data = self.data.clone();
+            std::thread::spawn(move || -> Result<(), ShellError> {
+                let mut writer = data_pipe.into_writer();
+                writer
+                    .write_all(&data)
+                    .map_err(|err| ShellError::IOError(err.to_string()))?;
+                writer
+                    .close()
+                    .map_err(|err| ShellError::IOError(err.error().to_string()))
+            })
+        });
 
         let mut child = plugin_cmd
             .command
@@ -65,13 +95,18 @@ impl CustomValue for PluginCustomValue {
                 inner: vec![],
             })?;
 
-        let plugin_call = PluginCall::CollapseCustomValue(PluginData {
-            data: self.data.clone(),
-            span,
-        });
+        let plugin_call = if supports_pipes {
+            PluginCall::CollapseCustomValuePiped(PluginDataPipe { span })
+        } else {
+            PluginCall::CollapseCustomValue(PluginData {
+                data: self.data.clone(),
+                span,
+            })
+        };
         let encoding = {
+            let mut stdin_writer = PipeWriter::new(&plugin_cmd.stdin);
             let mut stdout_reader = PipeReader::new(&plugin_cmd.stdout);
-            get_plugin_encoding(&mut stdout_reader)?
+            get_plugin_encoding(&mut stdin_writer, &mut stdout_reader, &CodecRegistry::new())?
         };
 
         let response = call_plugin(plugin_cmd, plugin_call, &encoding, span).map_err(|err| {
@@ -87,6 +122,12 @@ impl CustomValue for PluginCustomValue {
             }
         });
 
+        if let Some(handle) = data_writer {
+            if let Ok(Err(err)) = handle.join() {
+                return Err(err);
+            }
+        }
+
         let value = match response {
             Ok(PluginResponse::Value(value)) => Ok(*value),
             Ok(PluginResponse::PluginData(..)) => Err(ShellError::GenericError {
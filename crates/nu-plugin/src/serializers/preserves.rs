@@ -0,0 +1,664 @@
+//! A [`PluginCodec`] that frames `PluginCall`/`PluginResponse`/`PluginData` using the
+//! Preserves canonical binary syntax instead of JSON or MessagePack.
+//!
+//! Preserves' binary form is tag-byte prefixed with length-prefixed payloads: every value
+//! starts with a one-byte tag, collections carry an explicit item count, and integers carry an
+//! explicit sign byte plus a minimal-length big-endian magnitude. That makes frames
+//! self-describing and deterministic, and lets exact integers and byte strings cross the
+//! engine<->plugin boundary without JSON's lossy number handling or base64 inflation.
+//!
+//! [`Serializer`]/[`Deserializer`] implement the full `serde` data model on top of that wire
+//! format, so any `Serialize`/`Deserialize` type -- in particular `PluginCall` and
+//! `PluginResponse` -- can be framed this way with no further glue.
+
+use std::io::{BufRead, Write};
+
+use nu_protocol::ShellError;
+use serde::{
+    de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor},
+    ser::{
+        self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+        SerializeTupleStruct, SerializeTupleVariant,
+    },
+    Deserialize, Serialize,
+};
+
+use crate::plugin::PluginCodec;
+use crate::protocol::{PluginCall, PluginResponse};
+
+const TAG_BOOL_FALSE: u8 = 0x00;
+const TAG_BOOL_TRUE: u8 = 0x01;
+const TAG_INT: u8 = 0x02;
+const TAG_DOUBLE: u8 = 0x03;
+const TAG_STRING: u8 = 0x04;
+const TAG_BYTE_STRING: u8 = 0x05;
+const TAG_NONE: u8 = 0x06;
+const TAG_SOME: u8 = 0x07;
+const TAG_UNIT: u8 = 0x08;
+/// `variant_index: u32` followed by the inner value; used for unit, newtype, tuple and struct
+/// enum variants alike, distinguished only by what follows.
+const TAG_VARIANT: u8 = 0x09;
+const TAG_SEQ: u8 = 0x0A;
+const TAG_MAP: u8 = 0x0B;
+
+/// A [`PluginCodec`] that encodes `PluginCall`/`PluginResponse` as Preserves binary syntax.
+///
+/// This is the Preserves analogue of whatever `JsonSerializer`/`MsgPackSerializer` this
+/// repository's other encodings use; register it with [`CodecRegistry::register_codec`] under
+/// the name `"preserves"` to offer it alongside them during the codec negotiation handshake.
+#[derive(Clone)]
+pub struct PreservesSerializer;
+
+impl PluginCodec for PreservesSerializer {
+    fn name(&self) -> &str {
+        "preserves"
+    }
+
+    fn encode_call(
+        &self,
+        plugin_call: &PluginCall,
+        writer: &mut dyn Write,
+    ) -> Result<(), ShellError> {
+        encode(plugin_call, writer)
+    }
+
+    fn decode_call(&self, reader: &mut dyn BufRead) -> Result<PluginCall, ShellError> {
+        decode(reader)
+    }
+
+    fn encode_response(
+        &self,
+        plugin_response: &PluginResponse,
+        writer: &mut dyn Write,
+    ) -> Result<(), ShellError> {
+        encode(plugin_response, writer)
+    }
+
+    fn decode_response(&self, reader: &mut dyn BufRead) -> Result<PluginResponse, ShellError> {
+        decode(reader)
+    }
+}
+
+fn encode<T: Serialize>(value: &T, writer: &mut impl Write) -> Result<(), ShellError> {
+    let mut buf = Vec::new();
+    value
+        .serialize(&mut Serializer { output: &mut buf })
+        .map_err(|err| ShellError::PluginFailedToEncode {
+            msg: err.to_string(),
+        })?;
+    writer
+        .write_all(&buf)
+        .map_err(|err| ShellError::IOError(err.to_string()))
+}
+
+fn decode<T: for<'de> Deserialize<'de>>(reader: &mut impl BufRead) -> Result<T, ShellError> {
+    // Frames aren't length-delimited up front, so read everything the plugin wrote for this
+    // message; `call_plugin`/`serve_plugin` both close their write end between messages, which
+    // is what makes a read-to-end here correspond to exactly one frame.
+    let mut buf = Vec::new();
+    reader
+        .read_to_end(&mut buf)
+        .map_err(|err| ShellError::IOError(err.to_string()))?;
+    let mut deserializer = Deserializer { input: &buf };
+    T::deserialize(&mut deserializer).map_err(|err| ShellError::PluginFailedToDecode {
+        msg: err.to_string(),
+    })
+}
+
+// ---- error type -------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+// ---- serializer ---------------------------------------------------------------------------
+
+struct Serializer<'a> {
+    output: &'a mut Vec<u8>,
+}
+
+fn write_count(buf: &mut Vec<u8>, count: usize) {
+    buf.extend_from_slice(&(count as u32).to_be_bytes());
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_count(buf, bytes.len());
+    buf.extend_from_slice(bytes);
+}
+
+fn write_int(buf: &mut Vec<u8>, value: i128) {
+    let sign: u8 = if value < 0 { 1 } else { 0 };
+    let magnitude = value.unsigned_abs();
+    let mut bytes = magnitude.to_be_bytes().to_vec();
+    while bytes.len() > 1 && bytes[0] == 0 {
+        bytes.remove(0);
+    }
+    buf.push(sign);
+    buf.push(bytes.len() as u8);
+    buf.extend_from_slice(&bytes);
+}
+
+impl<'a> ser::Serializer for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.output
+            .push(if v { TAG_BOOL_TRUE } else { TAG_BOOL_FALSE });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.output.push(TAG_INT);
+        write_int(self.output, v as i128);
+        Ok(())
+    }
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        self.output.push(TAG_INT);
+        write_int(self.output, v);
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.output.push(TAG_INT);
+        write_int(self.output, v as i128);
+        Ok(())
+    }
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        self.output.push(TAG_INT);
+        write_int(self.output, v as i128);
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.serialize_f64(v as f64)
+    }
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.output.push(TAG_DOUBLE);
+        self.output.extend_from_slice(&v.to_be_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.output.push(TAG_STRING);
+        write_bytes(self.output, v.as_bytes());
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.output.push(TAG_BYTE_STRING);
+        write_bytes(self.output, v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.output.push(TAG_NONE);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        self.output.push(TAG_SOME);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.output.push(TAG_UNIT);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        self.output.push(TAG_VARIANT);
+        self.output.extend_from_slice(&variant_index.to_be_bytes());
+        self.output.push(TAG_UNIT);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.output.push(TAG_VARIANT);
+        self.output.extend_from_slice(&variant_index.to_be_bytes());
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+        self.output.push(TAG_SEQ);
+        write_count(self.output, len.unwrap_or(0));
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self, Error> {
+        self.output.push(TAG_SEQ);
+        write_count(self.output, len);
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self, Error> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self, Error> {
+        self.output.push(TAG_VARIANT);
+        self.output.extend_from_slice(&variant_index.to_be_bytes());
+        self.output.push(TAG_SEQ);
+        write_count(self.output, len);
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+        self.output.push(TAG_MAP);
+        write_count(self.output, len.unwrap_or(0));
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self, Error> {
+        self.output.push(TAG_MAP);
+        write_count(self.output, len);
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self, Error> {
+        self.output.push(TAG_VARIANT);
+        self.output.extend_from_slice(&variant_index.to_be_bytes());
+        self.output.push(TAG_MAP);
+        write_count(self.output, len);
+        Ok(self)
+    }
+}
+
+impl<'a> SerializeSeq for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeTuple for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeTupleStruct for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeTupleVariant for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeMap for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        key.serialize(&mut **self)
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeStruct for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        key.serialize(&mut **self)?;
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<'a> SerializeStructVariant for &mut Serializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        key.serialize(&mut **self)?;
+        value.serialize(&mut **self)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+// ---- deserializer -------------------------------------------------------------------------
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        if self.input.is_empty() {
+            return Err(Error::custom("unexpected end of input"));
+        }
+        let byte = self.input[0];
+        self.input = &self.input[1..];
+        Ok(byte)
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        if self.input.len() < len {
+            return Err(Error::custom("unexpected end of input"));
+        }
+        let (slice, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(slice)
+    }
+
+    fn read_count(&mut self) -> Result<usize, Error> {
+        let raw = self.read_slice(4)?;
+        Ok(u32::from_be_bytes(raw.try_into().unwrap()) as usize)
+    }
+
+    fn read_int(&mut self) -> Result<i128, Error> {
+        let sign = self.read_u8()?;
+        let len = self.read_u8()? as usize;
+        let magnitude_bytes = self.read_slice(len)?;
+        let mut magnitude: u128 = 0;
+        for byte in magnitude_bytes {
+            magnitude = (magnitude << 8) | *byte as u128;
+        }
+        Ok(if sign == 1 {
+            -(magnitude as i128)
+        } else {
+            magnitude as i128
+        })
+    }
+
+    fn read_string(&mut self) -> Result<String, Error> {
+        let len = self.read_count()?;
+        let bytes = self.read_slice(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|err| Error::custom(err.to_string()))
+    }
+
+    fn peek_tag(&self) -> Result<u8, Error> {
+        self.input
+            .first()
+            .copied()
+            .ok_or_else(|| Error::custom("unexpected end of input"))
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.read_u8()? {
+            TAG_BOOL_FALSE => visitor.visit_bool(false),
+            TAG_BOOL_TRUE => visitor.visit_bool(true),
+            TAG_INT => visitor.visit_i128(self.read_int()?),
+            TAG_DOUBLE => {
+                let raw = self.read_slice(8)?;
+                visitor.visit_f64(f64::from_be_bytes(raw.try_into().unwrap()))
+            }
+            TAG_STRING => visitor.visit_string(self.read_string()?),
+            TAG_BYTE_STRING => {
+                let len = self.read_count()?;
+                visitor.visit_byte_buf(self.read_slice(len)?.to_vec())
+            }
+            TAG_NONE => visitor.visit_none(),
+            TAG_SOME => visitor.visit_some(self),
+            TAG_UNIT => visitor.visit_unit(),
+            TAG_SEQ => {
+                let count = self.read_count()?;
+                visitor.visit_seq(CountedAccess {
+                    de: self,
+                    remaining: count,
+                })
+            }
+            TAG_MAP => {
+                let count = self.read_count()?;
+                visitor.visit_map(CountedAccess {
+                    de: self,
+                    remaining: count,
+                })
+            }
+            TAG_VARIANT => Err(Error::custom(
+                "enum variant found where a self-describing value was expected",
+            )),
+            other => Err(Error::custom(format!("unknown tag byte 0x{other:02x}"))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.peek_tag()? {
+            TAG_NONE => {
+                self.read_u8()?;
+                visitor.visit_none()
+            }
+            TAG_SOME => {
+                self.read_u8()?;
+                visitor.visit_some(self)
+            }
+            _ => Err(Error::custom("expected option tag")),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.read_u8()? {
+            TAG_VARIANT => {
+                let raw = self.read_slice(4)?;
+                let index = u32::from_be_bytes(raw.try_into().unwrap());
+                visitor.visit_enum(VariantDeserializer { de: self, index })
+            }
+            other => Err(Error::custom(format!(
+                "expected an enum variant tag, found 0x{other:02x}"
+            ))),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct CountedAccess<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'a, 'de> SeqAccess<'de> for CountedAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+impl<'a, 'de> MapAccess<'de> for CountedAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct VariantDeserializer<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    index: u32,
+}
+
+impl<'a, 'de> EnumAccess<'de> for VariantDeserializer<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Error> {
+        let value = seed.deserialize(de::value::U32Deserializer::new(self.index))?;
+        Ok((value, self))
+    }
+}
+
+impl<'a, 'de> VariantAccess<'de> for VariantDeserializer<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.de.read_u8()? {
+            TAG_UNIT => Ok(()),
+            other => Err(Error::custom(format!(
+                "expected unit variant payload, found tag 0x{other:02x}"
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self.de, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_any(self.de, visitor)
+    }
+}
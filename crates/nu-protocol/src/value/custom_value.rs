@@ -1,4 +1,10 @@
-use std::{cmp::Ordering, fmt, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
 
 use chrono::{DateTime, FixedOffset};
 
@@ -240,4 +246,172 @@ pub trait CustomValue: fmt::Debug + Send + Sync {
             help: None,
         })
     }
+
+    /// Encodes this value into the deterministic, self-describing binary form defined by
+    /// [`encode_canonical_value`]. Equal logical values always produce identical bytes
+    /// (record keys are sorted, every length is explicit), so the result can be hashed,
+    /// cached, or compared across the plugin IPC boundary instead of relying on `typetag`
+    /// serde alone.
+    ///
+    /// The default implementation canonically encodes [`CustomValue::to_base_value`]; override
+    /// it if a type can produce its canonical form without fully materializing first.
+    fn canonical_encode(&self, span: Span) -> Result<Vec<u8>, ShellError> {
+        encode_canonical_value(&self.to_base_value(span)?)
+    }
+}
+
+/// A one-byte type marker used by [`encode_canonical_value`]. The discriminant values are
+/// part of the wire format: changing one is a breaking change.
+#[repr(u8)]
+enum CanonicalTag {
+    Nothing = 0,
+    Bool = 1,
+    Int = 2,
+    Float = 3,
+    Filesize = 4,
+    Duration = 5,
+    Date = 6,
+    String = 7,
+    Binary = 8,
+    List = 9,
+    Record = 10,
+}
+
+/// Encodes a [`Value`] into a deterministic, self-describing binary form: every integer is a
+/// big-endian, minimal-length encoding with an explicit length prefix; every string/binary is
+/// length-prefixed; every record is emitted with its keys sorted lexicographically and
+/// length-prefixed, so two logically equal values (regardless of insertion order) always
+/// produce identical bytes. Ranges, closures, errors, and custom values have no canonical form
+/// and are rejected.
+pub fn encode_canonical_value(value: &Value) -> Result<Vec<u8>, ShellError> {
+    let mut out = Vec::new();
+    write_canonical_value(value, &mut out)?;
+    Ok(out)
+}
+
+fn write_canonical_len_prefixed(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn write_canonical_int(n: i64, out: &mut Vec<u8>) {
+    let be = n.to_be_bytes();
+    let sign_byte = if n < 0 { 0xFF } else { 0x00 };
+
+    let mut start = 0;
+    while start < be.len() - 1 && be[start] == sign_byte {
+        start += 1;
+    }
+    let minimal = &be[start..];
+
+    out.push((n < 0) as u8);
+    out.push(minimal.len() as u8);
+    out.extend_from_slice(minimal);
+}
+
+fn write_canonical_value(value: &Value, out: &mut Vec<u8>) -> Result<(), ShellError> {
+    match value {
+        Value::Nothing { .. } => out.push(CanonicalTag::Nothing as u8),
+        Value::Bool { val, .. } => {
+            out.push(CanonicalTag::Bool as u8);
+            out.push(*val as u8);
+        }
+        Value::Int { val, .. } => {
+            out.push(CanonicalTag::Int as u8);
+            write_canonical_int(*val, out);
+        }
+        Value::Float { val, .. } => {
+            out.push(CanonicalTag::Float as u8);
+            out.extend_from_slice(&val.to_be_bytes());
+        }
+        Value::Filesize { val, .. } => {
+            out.push(CanonicalTag::Filesize as u8);
+            write_canonical_int(val.get(), out);
+        }
+        Value::Duration { val, .. } => {
+            out.push(CanonicalTag::Duration as u8);
+            write_canonical_int(*val, out);
+        }
+        Value::Date { val, .. } => {
+            out.push(CanonicalTag::Date as u8);
+            write_canonical_len_prefixed(val.to_rfc3339().as_bytes(), out);
+        }
+        Value::String { val, .. } => {
+            out.push(CanonicalTag::String as u8);
+            write_canonical_len_prefixed(val.as_bytes(), out);
+        }
+        Value::Binary { val, .. } => {
+            out.push(CanonicalTag::Binary as u8);
+            write_canonical_len_prefixed(val, out);
+        }
+        Value::List { vals, .. } => {
+            out.push(CanonicalTag::List as u8);
+            out.extend_from_slice(&(vals.len() as u64).to_be_bytes());
+            for val in vals {
+                write_canonical_value(val, out)?;
+            }
+        }
+        Value::Record { val, .. } => {
+            out.push(CanonicalTag::Record as u8);
+            let mut entries: Vec<(&String, &Value)> = val.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            out.extend_from_slice(&(entries.len() as u64).to_be_bytes());
+            for (key, val) in entries {
+                write_canonical_len_prefixed(key.as_bytes(), out);
+                write_canonical_value(val, out)?;
+            }
+        }
+        _ => {
+            return Err(ShellError::CantConvert {
+                to_type: "canonical binary encoding".into(),
+                from_type: value.get_type().to_string(),
+                span: value.span(),
+                help: Some(
+                    "ranges, closures, errors, and custom values have no canonical form".into(),
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// A decoder that turns bytes produced by [`CustomValue::canonical_encode`] back into a
+/// [`Value`], registered per [`CustomValue::typetag_name`] via [`register_canonical_decoder`].
+pub type CanonicalDecoder = fn(&[u8], Span) -> Result<Value, ShellError>;
+
+fn canonical_decoder_registry() -> &'static Mutex<HashMap<&'static str, CanonicalDecoder>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<&'static str, CanonicalDecoder>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Registers a decoder for the given [`CustomValue::typetag_name`], so that
+/// [`canonical_decode`] can turn bytes produced by that type's
+/// [`CustomValue::canonical_encode`] back into a [`Value`]. Call this once per custom value
+/// type, typically from the crate that defines it.
+pub fn register_canonical_decoder(type_name: &'static str, decoder: CanonicalDecoder) {
+    canonical_decoder_registry()
+        .lock()
+        .expect("canonical decoder registry poisoned")
+        .insert(type_name, decoder);
+}
+
+/// Decodes `bytes` back into a [`Value`] using the decoder registered for `type_name` via
+/// [`register_canonical_decoder`].
+pub fn canonical_decode(type_name: &str, bytes: &[u8], span: Span) -> Result<Value, ShellError> {
+    let decoder = canonical_decoder_registry()
+        .lock()
+        .expect("canonical decoder registry poisoned")
+        .get(type_name)
+        .copied();
+
+    match decoder {
+        Some(decoder) => decoder(bytes, span),
+        None => Err(ShellError::CantConvert {
+            to_type: "value".into(),
+            from_type: format!("canonical encoding of `{type_name}` (no decoder registered)"),
+            span,
+            help: Some("register one with `register_canonical_decoder`".into()),
+        }),
+    }
 }
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use crate::{record, Record, Span, Value};
+
+/// Converts a Rust value into a nushell [`Value`] at a given [`Span`].
+///
+/// This is the inverse of `FromValue`: where `FromValue` pulls typed data out of a `Value`,
+/// `IntoValue` builds a `Value` (usually a [`Value::Record`]) from a typed Rust value, so command
+/// authors can return structured data - e.g. straight into [`describe`](crate::engine::Command) -
+/// without hand-assembling a [`Record`] column by column.
+///
+/// Most callers won't implement this by hand: `#[derive(IntoValue)]` (from the `nu-derive-value`
+/// crate) generates an impl for a struct or enum from its fields/variants. Implement it manually
+/// only for leaf types that need a custom representation.
+pub trait IntoValue: Sized {
+    fn into_value(self, span: Span) -> Value;
+}
+
+macro_rules! primitive_into_value {
+    ($ty:ty, $ctor:ident) => {
+        impl IntoValue for $ty {
+            fn into_value(self, span: Span) -> Value {
+                Value::$ctor(self.into(), span)
+            }
+        }
+    };
+}
+
+primitive_into_value!(bool, bool);
+primitive_into_value!(i64, int);
+primitive_into_value!(i32, int);
+primitive_into_value!(u32, int);
+primitive_into_value!(f64, float);
+primitive_into_value!(String, string);
+
+impl IntoValue for &str {
+    fn into_value(self, span: Span) -> Value {
+        Value::string(self, span)
+    }
+}
+
+impl IntoValue for Value {
+    fn into_value(self, span: Span) -> Value {
+        self.with_span(span)
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self, span: Span) -> Value {
+        match self {
+            Some(value) => value.into_value(span),
+            None => Value::nothing(span),
+        }
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self, span: Span) -> Value {
+        Value::list(
+            self.into_iter().map(|item| item.into_value(span)).collect(),
+            span,
+        )
+    }
+}
+
+impl<T: IntoValue> IntoValue for HashMap<String, T> {
+    fn into_value(self, span: Span) -> Value {
+        let mut record = Record::new();
+        for (key, value) in self {
+            record.push(key, value.into_value(span));
+        }
+        Value::record(record, span)
+    }
+}
+
+/// Small convenience used by derived `enum` impls for a tagged-union representation: a record of
+/// `{type: "<variant name>", value: <payload>}`, matching how [`describe`](crate::engine::Command)
+/// already tags its own variant records.
+pub fn tagged_enum_value(variant: &str, payload: Value, span: Span) -> Value {
+    Value::record(
+        record!(
+            "type" => Value::string(variant, span),
+            "value" => payload,
+        ),
+        span,
+    )
+}
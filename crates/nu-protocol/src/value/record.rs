@@ -1,12 +1,38 @@
-use std::ops::RangeBounds;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::{Bound, RangeBounds};
 
 use crate::{ShellError, Span, Value};
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "RecordDeserializer")]
 pub struct Record {
     inner: Vec<(String, Value)>,
+    /// Maps a column name to its position in `inner`, so lookups don't need a linear scan over
+    /// wide records (e.g. hundreds of columns from `from json`). `inner` remains the source of
+    /// truth for iteration order; for a duplicate column name, this always points at the
+    /// *first* occurrence, matching what a linear scan over `inner` would find.
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+/// Shadow type used to deserialize a [`Record`] in its original `{ inner: [...] }` wire shape,
+/// then rebuild the lookup index in one sweep via [`From`].
+#[derive(Deserialize)]
+struct RecordDeserializer {
+    inner: Vec<(String, Value)>,
+}
+
+impl From<RecordDeserializer> for Record {
+    fn from(de: RecordDeserializer) -> Self {
+        let index = Record::build_index(&de.inner);
+        Record {
+            inner: de.inner,
+            index,
+        }
+    }
 }
 
 impl Record {
@@ -17,7 +43,22 @@ impl Record {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             inner: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Builds a column-name -> position index, keeping the *first* occurrence of a duplicate
+    /// column name - the same one a linear scan over `inner` would find.
+    fn build_index(inner: &[(String, Value)]) -> HashMap<String, usize> {
+        let mut index = HashMap::with_capacity(inner.len());
+        for (i, (col, _)) in inner.iter().enumerate() {
+            index.entry(col.clone()).or_insert(i);
         }
+        index
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index = Self::build_index(&self.inner);
     }
 
     /// Create a [`Record`] from a `Vec` of columns and a `Vec` of [`Value`]s
@@ -26,6 +67,9 @@ impl Record {
     ///
     /// For perf reasons, this will not validate the rest of the record assumptions:
     /// - unique keys
+    ///
+    /// Use [`Record::from_cols_vals_checked`] instead unless `cols` is already known to be
+    /// duplicate-free and this is on a performance-critical path.
     pub fn from_raw_cols_vals(
         cols: Vec<String>,
         vals: Vec<Value>,
@@ -33,8 +77,9 @@ impl Record {
         creation_site_span: Span,
     ) -> Result<Self, ShellError> {
         if cols.len() == vals.len() {
-            let inner = cols.into_iter().zip(vals).collect();
-            Ok(Self { inner })
+            let inner: Vec<(String, Value)> = cols.into_iter().zip(vals).collect();
+            let index = Self::build_index(&inner);
+            Ok(Self { inner, index })
         } else {
             Err(ShellError::RecordColsValsMismatch {
                 bad_value: input_span,
@@ -43,6 +88,29 @@ impl Record {
         }
     }
 
+    /// Like [`Record::from_raw_cols_vals`], but also rejects a duplicate column name instead of
+    /// silently keeping only the first occurrence reachable through lookups. Prefer this over
+    /// `from_raw_cols_vals` unless you've already guaranteed uniqueness and are on a
+    /// performance-critical path.
+    pub fn from_cols_vals_checked(
+        cols: Vec<String>,
+        vals: Vec<Value>,
+        input_span: Span,
+        creation_site_span: Span,
+    ) -> Result<Self, ShellError> {
+        if cols.len() != vals.len() {
+            return Err(ShellError::RecordColsValsMismatch {
+                bad_value: input_span,
+                creation_site: creation_site_span,
+            });
+        }
+        let mut record = Self::with_capacity(cols.len());
+        for (col, val) in cols.into_iter().zip(vals) {
+            record.try_insert(col, val, input_span)?;
+        }
+        Ok(record)
+    }
+
     pub fn iter(&self) -> Iter {
         self.into_iter()
     }
@@ -65,7 +133,11 @@ impl Record {
     ///
     /// Consider to use [`Record::insert`] instead
     pub fn push(&mut self, col: impl Into<String>, val: Value) {
-        self.inner.push((col.into(), val));
+        let col = col.into();
+        // Preserve first-occurrence semantics for duplicate keys: if `col` is already indexed,
+        // leave it pointing at the earlier position.
+        self.index.entry(col.clone()).or_insert(self.inner.len());
+        self.inner.push((col, val));
     }
 
     /// Insert into the record, replacing preexisting value if found.
@@ -83,24 +155,87 @@ impl Record {
         }
     }
 
+    /// Like [`Record::push`], but reports a conflict instead of silently appending a duplicate
+    /// column name. Prefer this over `push` when the columns being inserted aren't already
+    /// known to be unique.
+    ///
+    /// ```rust
+    /// use nu_protocol::{Record, Span, Value};
+    ///
+    /// let mut rec = Record::new();
+    /// rec.try_insert("a", Value::test_int(1), Span::test_data()).unwrap();
+    /// assert!(rec.try_insert("a", Value::test_int(2), Span::test_data()).is_err());
+    /// assert_eq!(rec.get("a"), Some(&Value::test_int(1)));
+    /// ```
+    pub fn try_insert(
+        &mut self,
+        col: impl Into<String>,
+        val: Value,
+        span: Span,
+    ) -> Result<(), ShellError> {
+        let col = col.into();
+        if self.contains(&col) {
+            Err(ShellError::ColumnDefinedTwice { col, span })
+        } else {
+            self.push(col, val);
+            Ok(())
+        }
+    }
+
+    /// Like [`Extend`], but reports the first duplicate column name instead of silently letting
+    /// it shadow the earlier one. Stops at the first conflict, leaving every pair inserted
+    /// before it in place.
+    pub fn extend_checked<T>(&mut self, iter: T, span: Span) -> Result<(), ShellError>
+    where
+        T: IntoIterator<Item = (String, Value)>,
+    {
+        for (col, val) in iter {
+            self.try_insert(col, val, span)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the given column's corresponding entry for in-place manipulation, mirroring
+    /// [`std::collections::hash_map::Entry`].
+    ///
+    /// ```rust
+    /// use nu_protocol::{Record, Value, Span};
+    ///
+    /// // Build a frequency-count record.
+    /// let mut rec = Record::new();
+    /// for word in ["a", "b", "a", "c", "b", "a"] {
+    ///     rec.entry(word)
+    ///         .and_modify(|v| *v = v.add(Span::test_data(), &Value::int(1, Span::test_data())).unwrap())
+    ///         .or_insert(Value::int(1, Span::test_data()));
+    /// }
+    /// assert_eq!(rec.get("a"), Some(&Value::int(3, Span::test_data())));
+    /// assert_eq!(rec.get("b"), Some(&Value::int(2, Span::test_data())));
+    /// assert_eq!(rec.get("c"), Some(&Value::int(1, Span::test_data())));
+    /// ```
+    pub fn entry(&mut self, col: impl Into<String>) -> Entry<'_> {
+        let col = col.into();
+        match self.index.get(&col) {
+            Some(&idx) => Entry::Occupied(OccupiedEntry { record: self, idx }),
+            None => Entry::Vacant(VacantEntry { record: self, col }),
+        }
+    }
+
     pub fn contains(&self, col: impl AsRef<str>) -> bool {
-        self.columns().any(|k| k == col.as_ref())
+        self.index.contains_key(col.as_ref())
     }
 
     pub fn index_of(&self, col: impl AsRef<str>) -> Option<usize> {
-        self.columns().position(|k| k == col.as_ref())
+        self.index.get(col.as_ref()).copied()
     }
 
     pub fn get(&self, col: impl AsRef<str>) -> Option<&Value> {
-        self.inner
-            .iter()
-            .find_map(|(k, v)| if k == col.as_ref() { Some(v) } else { None })
+        let idx = *self.index.get(col.as_ref())?;
+        self.inner.get(idx).map(|(_, v)| v)
     }
 
     pub fn get_mut(&mut self, col: impl AsRef<str>) -> Option<&mut Value> {
-        self.inner
-            .iter_mut()
-            .find_map(|(k, v)| if k == col.as_ref() { Some(v) } else { None })
+        let idx = *self.index.get(col.as_ref())?;
+        self.inner.get_mut(idx).map(|(_, v)| v)
     }
 
     pub fn get_index(&self, idx: usize) -> Option<(&String, &Value)> {
@@ -114,7 +249,21 @@ impl Record {
     /// Note: makes strong assumption that keys are unique
     pub fn remove(&mut self, col: impl AsRef<str>) -> Option<Value> {
         let idx = self.index_of(col)?;
-        let (_, val) = self.inner.remove(idx);
+        let (removed_col, val) = self.inner.remove(idx);
+        self.index.remove(&removed_col);
+        for stored_idx in self.index.values_mut() {
+            if *stored_idx > idx {
+                *stored_idx -= 1;
+            }
+        }
+        // `removed_col` may have had a duplicate later in `inner`; if so, that duplicate is now
+        // the first (and only tracked) occurrence, matching what a linear scan would now find.
+        if let Some(offset) = self.inner[idx..]
+            .iter()
+            .position(|(k, _)| *k == removed_col)
+        {
+            self.index.entry(removed_col).or_insert(idx + offset);
+        }
         Some(val)
     }
 
@@ -184,6 +333,7 @@ impl Record {
         F: FnMut(&str, &mut Value) -> bool,
     {
         self.inner.retain_mut(|(col, val)| keep(col, val));
+        self.rebuild_index();
     }
 
     /// Truncate record to the first `len` elements.
@@ -208,6 +358,51 @@ impl Record {
     /// ```
     pub fn truncate(&mut self, len: usize) {
         self.inner.truncate(len);
+        // Everything kept is still at its original position, so the affected index entries can
+        // just be pruned rather than fully rebuilt.
+        self.index.retain(|_, idx| *idx < len);
+    }
+
+    /// Sort the record's columns into lexicographic order, carrying each value along with its
+    /// column. Useful for deterministic output, e.g. when rendering to `json` or `table`.
+    ///
+    /// ```rust
+    /// use nu_protocol::{record, Value};
+    ///
+    /// let mut rec = record!(
+    ///     "c" => Value::test_int(3),
+    ///     "a" => Value::test_int(1),
+    ///     "b" => Value::test_int(2),
+    /// );
+    /// rec.sort_cols();
+    /// assert_eq!(rec.columns().map(String::as_str).collect::<String>(), "abc");
+    /// assert_eq!(rec.get("a"), Some(&Value::test_int(1)));
+    /// assert_eq!(rec.get("b"), Some(&Value::test_int(2)));
+    /// assert_eq!(rec.get("c"), Some(&Value::test_int(3)));
+    /// ```
+    pub fn sort_cols(&mut self) {
+        self.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    /// Sort `(column, value)` pairs in place with a custom comparator, using a stable sort so
+    /// pairs that compare equal keep their relative order.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&(String, Value), &(String, Value)) -> Ordering,
+    {
+        self.inner.sort_by(|a, b| compare(a, b));
+        self.rebuild_index();
+    }
+
+    /// Sort `(column, value)` pairs in place by a derived key, using a stable sort so pairs that
+    /// compare equal keep their relative order.
+    pub fn sort_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: Ord,
+        F: FnMut(&(String, Value)) -> K,
+    {
+        self.inner.sort_by_key(|pair| key(pair));
+        self.rebuild_index();
     }
 
     pub fn columns(&self) -> Columns {
@@ -253,25 +448,95 @@ impl Record {
     where
         R: RangeBounds<usize> + Clone,
     {
+        // `Vec::drain` always removes the whole range once the `Drain` is dropped, regardless
+        // of how much of it actually gets iterated - so the resulting index can be computed
+        // upfront from the current `inner`, without waiting on the iterator to finish.
+        let (start, end) = resolve_range(&range, self.inner.len());
+        self.index = self.inner[..start]
+            .iter()
+            .chain(self.inner[end..].iter())
+            .enumerate()
+            .fold(HashMap::new(), |mut index, (i, (col, _))| {
+                index.entry(col.clone()).or_insert(i);
+                index
+            });
         Drain {
             iter: self.inner.drain(range),
         }
     }
+
+    /// Obtain an iterator to remove elements for which `pred` returns true, leaving the rest
+    /// untouched and in order.
+    ///
+    /// Unlike [`Record::drain`], the removed elements don't need to be contiguous. Elements not
+    /// consumed from the iterator will still be removed once it's dropped, matching `Drain`'s
+    /// drop-consumes semantics.
+    ///
+    /// ```rust
+    /// use nu_protocol::{record, Value};
+    ///
+    /// let mut rec = record!(
+    ///     "a" => Value::test_int(1),
+    ///     "_meta_a" => Value::test_int(2),
+    ///     "b" => Value::test_int(3),
+    ///     "_meta_b" => Value::test_int(4),
+    /// );
+    /// let meta: Vec<_> = rec.extract_if(|col, _| col.starts_with("_meta_")).collect();
+    /// assert_eq!(
+    ///     meta,
+    ///     vec![
+    ///         ("_meta_a".into(), Value::test_int(2)),
+    ///         ("_meta_b".into(), Value::test_int(4)),
+    ///     ]
+    /// );
+    /// assert_eq!(rec.columns().map(String::as_str).collect::<String>(), "ab");
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, F>
+    where
+        F: FnMut(&str, &mut Value) -> bool,
+    {
+        ExtractIf {
+            record: self,
+            pred,
+            idx: 0,
+        }
+    }
+}
+
+/// Resolves a `RangeBounds<usize>` against a concrete length, the same way `Vec::drain` does
+/// internally.
+fn resolve_range(range: &impl RangeBounds<usize>, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&n) => n + 1,
+        Bound::Excluded(&n) => n,
+        Bound::Unbounded => len,
+    };
+    (start, end)
 }
 
 impl FromIterator<(String, Value)> for Record {
+    /// Does not check for duplicate keys/columns; on a duplicate, `push`'s first-occurrence
+    /// semantics apply, same as building the `Record` by hand with repeated `push` calls. Use
+    /// [`Record::from_cols_vals_checked`] if the input isn't already known to have unique
+    /// columns.
     fn from_iter<T: IntoIterator<Item = (String, Value)>>(iter: T) -> Self {
-        // TODO: should this check for duplicate keys/columns?
-        Self {
-            inner: iter.into_iter().collect(),
-        }
+        let inner: Vec<(String, Value)> = iter.into_iter().collect();
+        let index = Self::build_index(&inner);
+        Self { inner, index }
     }
 }
 
 impl Extend<(String, Value)> for Record {
+    /// Does not check for duplicate keys/columns; appends blindly via [`Record::push`], same as
+    /// this trait's usual "last write doesn't necessarily win" caveat for multi-maps. Use
+    /// [`Record::extend_checked`] if the input isn't already known to have unique columns.
     fn extend<T: IntoIterator<Item = (String, Value)>>(&mut self, iter: T) {
         for (k, v) in iter {
-            // TODO: should this .insert with a check?
             self.push(k, v)
         }
     }
@@ -497,6 +762,130 @@ impl ExactSizeIterator for Drain<'_> {
     }
 }
 
+/// An iterator that removes and yields the `(String, Value)` pairs of a [`Record`] for which
+/// a predicate returns true, obtained via [`Record::extract_if`].
+pub struct ExtractIf<'a, F> {
+    record: &'a mut Record,
+    pred: F,
+    idx: usize,
+}
+
+impl<F> Iterator for ExtractIf<'_, F>
+where
+    F: FnMut(&str, &mut Value) -> bool,
+{
+    type Item = (String, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < self.record.inner.len() {
+            let (col, val) = &mut self.record.inner[self.idx];
+            if (self.pred)(col, val) {
+                let (removed_col, removed_val) = self.record.inner.remove(self.idx);
+                self.record.index.remove(&removed_col);
+                for stored_idx in self.record.index.values_mut() {
+                    if *stored_idx > self.idx {
+                        *stored_idx -= 1;
+                    }
+                }
+                // `removed_col` may have had a duplicate later in `inner`; if so, that
+                // duplicate is now the first (and only tracked) occurrence, matching what a
+                // linear scan would now find.
+                if let Some(offset) = self.record.inner[self.idx..]
+                    .iter()
+                    .position(|(k, _)| *k == removed_col)
+                {
+                    self.record
+                        .index
+                        .entry(removed_col)
+                        .or_insert(self.idx + offset);
+                }
+                return Some((removed_col, removed_val));
+            }
+            self.idx += 1;
+        }
+        None
+    }
+}
+
+impl<F> Drop for ExtractIf<'_, F>
+where
+    F: FnMut(&str, &mut Value) -> bool,
+{
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+    }
+}
+
+/// A view into a single column of a [`Record`], obtained via [`Record::entry`]. Mirrors
+/// [`std::collections::hash_map::Entry`].
+pub enum Entry<'a> {
+    Occupied(OccupiedEntry<'a>),
+    Vacant(VacantEntry<'a>),
+}
+
+impl<'a> Entry<'a> {
+    /// Ensures the column is present, inserting `val` if it's vacant, then returns a mutable
+    /// reference to its value.
+    pub fn or_insert(self, val: Value) -> &'a mut Value {
+        self.or_insert_with(|| val)
+    }
+
+    /// Like [`Entry::or_insert`], but only evaluates `default` if the column is vacant.
+    pub fn or_insert_with(self, default: impl FnOnce() -> Value) -> &'a mut Value {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` on the current value if the column is occupied, leaving it untouched if vacant.
+    /// Returns `self` unchanged so it can be chained into `or_insert`/`or_insert_with`.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut Value)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// An occupied column entry, returned as part of an [`Entry`].
+pub struct OccupiedEntry<'a> {
+    record: &'a mut Record,
+    idx: usize,
+}
+
+impl<'a> OccupiedEntry<'a> {
+    pub fn get(&self) -> &Value {
+        &self.record.inner[self.idx].1
+    }
+
+    pub fn get_mut(&mut self) -> &mut Value {
+        &mut self.record.inner[self.idx].1
+    }
+
+    /// Converts the entry into a mutable reference with the lifetime of the underlying record.
+    pub fn into_mut(self) -> &'a mut Value {
+        &mut self.record.inner[self.idx].1
+    }
+}
+
+/// A vacant column entry, returned as part of an [`Entry`].
+pub struct VacantEntry<'a> {
+    record: &'a mut Record,
+    col: String,
+}
+
+impl<'a> VacantEntry<'a> {
+    /// Inserts `val`, appending it to the end of the record to preserve insertion order, and
+    /// returns a mutable reference to it.
+    pub fn insert(self, val: Value) -> &'a mut Value {
+        let idx = self.record.inner.len();
+        self.record.index.insert(self.col.clone(), idx);
+        self.record.inner.push((self.col, val));
+        &mut self.record.inner[idx].1
+    }
+}
+
 #[macro_export]
 macro_rules! record {
     // The macro only compiles if the number of columns equals the number of values,
@@ -513,3 +902,311 @@ macro_rules! record {
         $crate::Record::new()
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Record;
+    use crate::{ShellError, Span, Value};
+
+    /// The pre-index behavior of `get`/`index_of`/`contains`: a plain linear scan over `inner`,
+    /// which always finds the *first* match for a duplicate key. The indexed implementation
+    /// must agree with this on every input, including duplicate-key ones.
+    fn linear_index_of(record: &Record, col: &str) -> Option<usize> {
+        record.columns().position(|k| k == col)
+    }
+
+    #[test]
+    fn index_agrees_with_linear_scan_with_duplicates() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+        record.push("a", Value::test_int(3)); // duplicate column name
+        record.push("c", Value::test_int(4));
+
+        for col in ["a", "b", "c", "missing"] {
+            assert_eq!(record.index_of(col), linear_index_of(&record, col));
+            assert_eq!(
+                record.contains(col),
+                linear_index_of(&record, col).is_some()
+            );
+        }
+        assert_eq!(record.get("a"), Some(&Value::test_int(1)));
+    }
+
+    #[test]
+    fn remove_promotes_remaining_duplicate() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+        record.push("a", Value::test_int(3)); // duplicate column name
+
+        let removed = record.remove("a");
+        assert_eq!(removed, Some(Value::test_int(1)));
+        // The second "a" is now the first (and only) occurrence.
+        assert_eq!(record.get("a"), Some(&Value::test_int(3)));
+        assert_eq!(record.index_of("a"), linear_index_of(&record, "a"));
+        assert_eq!(record.index_of("b"), linear_index_of(&record, "b"));
+    }
+
+    #[test]
+    fn remove_shifts_later_indices() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+        record.push("c", Value::test_int(3));
+
+        record.remove("a");
+        assert_eq!(record.index_of("b"), Some(0));
+        assert_eq!(record.index_of("c"), Some(1));
+    }
+
+    #[test]
+    fn truncate_prunes_index() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+        record.push("c", Value::test_int(3));
+
+        record.truncate(1);
+        assert!(record.contains("a"));
+        assert!(!record.contains("b"));
+        assert!(!record.contains("c"));
+    }
+
+    #[test]
+    fn retain_mut_rebuilds_index() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+        record.push("c", Value::test_int(3));
+
+        record.retain_mut(|col, _| col != "b");
+        assert_eq!(record.index_of("a"), linear_index_of(&record, "a"));
+        assert_eq!(record.index_of("c"), linear_index_of(&record, "c"));
+        assert!(!record.contains("b"));
+    }
+
+    #[test]
+    fn drain_rebuilds_index_even_if_not_fully_consumed() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+        record.push("c", Value::test_int(3));
+
+        {
+            let mut drainer = record.drain(0..2);
+            assert_eq!(drainer.next(), Some(("a".into(), Value::test_int(1))));
+            // drop the rest of the drainer without consuming it
+        }
+
+        assert_eq!(record.index_of("c"), Some(0));
+        assert!(!record.contains("a"));
+        assert!(!record.contains("b"));
+    }
+
+    #[test]
+    fn serde_roundtrip_rebuilds_index() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+
+        let json = serde_json::to_string(&record).unwrap();
+        let deserialized: Record = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(deserialized.get("a"), Some(&Value::test_int(1)));
+        assert_eq!(deserialized.get("b"), Some(&Value::test_int(2)));
+        assert_eq!(deserialized.index_of("b"), Some(1));
+    }
+
+    #[test]
+    fn entry_vacant_appends_in_insertion_order() {
+        let mut record = Record::new();
+        record.entry("a").or_insert(Value::test_int(1));
+        record.entry("b").or_insert(Value::test_int(2));
+        // Already occupied, so this is a no-op.
+        record.entry("a").or_insert(Value::test_int(99));
+
+        assert_eq!(
+            record.columns().map(String::as_str).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(record.get("a"), Some(&Value::test_int(1)));
+        assert_eq!(record.get("b"), Some(&Value::test_int(2)));
+    }
+
+    #[test]
+    fn entry_and_modify_only_runs_when_occupied() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+
+        record
+            .entry("a")
+            .and_modify(|v| *v = Value::test_int(42))
+            .or_insert(Value::test_int(0));
+        record
+            .entry("b")
+            .and_modify(|v| *v = Value::test_int(42))
+            .or_insert(Value::test_int(7));
+
+        assert_eq!(record.get("a"), Some(&Value::test_int(42)));
+        assert_eq!(record.get("b"), Some(&Value::test_int(7)));
+    }
+
+    #[test]
+    fn extract_if_splits_matching_elements_leaving_rest_in_order() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("_meta_a", Value::test_int(2));
+        record.push("b", Value::test_int(3));
+        record.push("_meta_b", Value::test_int(4));
+
+        let extracted: Vec<_> = record
+            .extract_if(|col, _| col.starts_with("_meta_"))
+            .collect();
+
+        assert_eq!(
+            extracted,
+            vec![
+                ("_meta_a".into(), Value::test_int(2)),
+                ("_meta_b".into(), Value::test_int(4)),
+            ]
+        );
+        assert_eq!(
+            record.columns().map(String::as_str).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(record.index_of("a"), linear_index_of(&record, "a"));
+        assert_eq!(record.index_of("b"), linear_index_of(&record, "b"));
+    }
+
+    #[test]
+    fn extract_if_removes_unconsumed_matches_on_drop() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.push("_meta_a", Value::test_int(2));
+        record.push("b", Value::test_int(3));
+        record.push("_meta_b", Value::test_int(4));
+
+        {
+            let mut extractor = record.extract_if(|col, _| col.starts_with("_meta_"));
+            assert_eq!(
+                extractor.next(),
+                Some(("_meta_a".into(), Value::test_int(2)))
+            );
+            // drop the rest of the iterator without consuming it
+        }
+
+        assert_eq!(
+            record.columns().map(String::as_str).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(record.index_of("a"), linear_index_of(&record, "a"));
+        assert_eq!(record.index_of("b"), linear_index_of(&record, "b"));
+    }
+
+    #[test]
+    fn sort_cols_orders_columns_and_rebuilds_index() {
+        let mut record = Record::new();
+        record.push("c", Value::test_int(3));
+        record.push("a", Value::test_int(1));
+        record.push("b", Value::test_int(2));
+
+        record.sort_cols();
+
+        assert_eq!(
+            record.columns().map(String::as_str).collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+        assert_eq!(record.get("a"), Some(&Value::test_int(1)));
+        assert_eq!(record.get("b"), Some(&Value::test_int(2)));
+        assert_eq!(record.get("c"), Some(&Value::test_int(3)));
+        for col in ["a", "b", "c"] {
+            assert_eq!(record.index_of(col), linear_index_of(&record, col));
+        }
+    }
+
+    #[test]
+    fn sort_by_key_is_stable_for_equal_keys() {
+        let mut record = Record::new();
+        record.push("bb", Value::test_int(1));
+        record.push("c", Value::test_int(2));
+        record.push("aa", Value::test_int(3));
+
+        record.sort_by_key(|(col, _)| col.len());
+
+        // "bb" and "aa" have the same key (length 2); a stable sort keeps them in their
+        // original relative order instead of swapping them.
+        assert_eq!(
+            record.columns().map(String::as_str).collect::<Vec<_>>(),
+            ["c", "bb", "aa"]
+        );
+    }
+
+    #[test]
+    fn from_cols_vals_checked_rejects_duplicate_columns() {
+        let span = Span::test_data();
+        let cols = vec!["a".to_string(), "a".to_string()];
+        let vals = vec![Value::test_int(1), Value::test_int(2)];
+
+        let err = Record::from_cols_vals_checked(cols, vals, span, span).unwrap_err();
+        assert!(matches!(err, ShellError::ColumnDefinedTwice { .. }));
+    }
+
+    #[test]
+    fn from_raw_cols_vals_preserves_first_occurrence_on_duplicate_columns() {
+        let span = Span::test_data();
+        let cols = vec!["a".to_string(), "a".to_string()];
+        let vals = vec![Value::test_int(1), Value::test_int(2)];
+
+        let record = Record::from_raw_cols_vals(cols, vals, span, span).unwrap();
+        // Today's unchecked behavior: both columns are kept in `inner`, but lookups only ever
+        // reach the first occurrence.
+        assert_eq!(record.len(), 2);
+        assert_eq!(record.get("a"), Some(&Value::test_int(1)));
+    }
+
+    #[test]
+    fn try_insert_rejects_duplicate_column() {
+        let span = Span::test_data();
+        let mut record = Record::new();
+        record.try_insert("a", Value::test_int(1), span).unwrap();
+
+        let err = record
+            .try_insert("a", Value::test_int(2), span)
+            .unwrap_err();
+        assert!(matches!(err, ShellError::ColumnDefinedTwice { .. }));
+        assert_eq!(record.get("a"), Some(&Value::test_int(1)));
+    }
+
+    #[test]
+    fn extend_checked_stops_at_first_conflict() {
+        let span = Span::test_data();
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+
+        let err = record
+            .extend_checked(
+                vec![
+                    ("b".to_string(), Value::test_int(2)),
+                    ("a".to_string(), Value::test_int(3)),
+                ],
+                span,
+            )
+            .unwrap_err();
+
+        assert!(matches!(err, ShellError::ColumnDefinedTwice { .. }));
+        // "b" was inserted before the conflict on "a" was hit.
+        assert_eq!(record.get("b"), Some(&Value::test_int(2)));
+        assert_eq!(record.get("a"), Some(&Value::test_int(1)));
+    }
+
+    #[test]
+    fn extend_preserves_todays_unchecked_behavior_on_duplicate_columns() {
+        let mut record = Record::new();
+        record.push("a", Value::test_int(1));
+        record.extend(vec![("a".to_string(), Value::test_int(2))]);
+
+        assert_eq!(record.len(), 2);
+        assert_eq!(record.get("a"), Some(&Value::test_int(1)));
+    }
+}
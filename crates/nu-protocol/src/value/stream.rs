@@ -13,6 +13,31 @@ pub struct RawStream {
     pub datatype: StreamDataType,
     pub span: Span,
     pub known_size: Option<u64>, // (bytes)
+    /// The text encoding to decode with, if known. `None` means "auto": sniff a BOM off the
+    /// first chunk and fall back to UTF-8 if none is found, which is also exactly what happens
+    /// if this is left `None` and no BOM is ever seen.
+    pub encoding: Option<&'static encoding_rs::Encoding>,
+    bom_checked: bool,
+    /// Incremental decoder for `encoding`, used whenever that encoding isn't UTF-8. `encoding_rs`
+    /// buffers any trailing partial multi-byte sequence inside the decoder itself across calls,
+    /// playing the same role `leftover` plays for the UTF-8 fast path below.
+    decoder: Option<encoding_rs::Decoder>,
+}
+
+/// Detects a byte-order-mark at the start of a byte sequence, returning the encoding it implies
+/// and the length of the BOM itself so the caller can strip it before decoding. Only recognizes
+/// a BOM that lies wholly in the first chunk handed to the stream; one split across the first
+/// two chunks is not detected.
+fn sniff_bom(bytes: &[u8]) -> Option<(&'static encoding_rs::Encoding, usize)> {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        Some((encoding_rs::UTF_8, 3))
+    } else if bytes.starts_with(&[0xFF, 0xFE]) {
+        Some((encoding_rs::UTF_16LE, 2))
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        Some((encoding_rs::UTF_16BE, 2))
+    } else {
+        None
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -36,6 +61,35 @@ impl RawStream {
             datatype: StreamDataType::Text,
             span,
             known_size,
+            encoding: None,
+            bom_checked: false,
+            decoder: None,
+        }
+    }
+
+    /// Creates a `RawStream` that decodes its bytes with `encoding` instead of assuming UTF-8.
+    ///
+    /// Unlike [`RawStream::new`], this skips BOM auto-detection entirely - the caller already
+    /// knows the codepage (e.g. from a known console codepage on an external command), so
+    /// there's nothing to sniff.
+    pub fn with_encoding(
+        stream: Box<dyn Iterator<Item = Result<Vec<u8>, ShellError>> + Send + 'static>,
+        ctrlc: Option<Arc<AtomicBool>>,
+        span: Span,
+        known_size: Option<u64>,
+        encoding: &'static encoding_rs::Encoding,
+    ) -> Self {
+        Self {
+            stream,
+            leftover: vec![],
+            ctrlc,
+            datatype: StreamDataType::Text,
+            span,
+            known_size,
+            encoding: Some(encoding),
+            bom_checked: true,
+            decoder: (encoding != encoding_rs::UTF_8)
+                .then(|| encoding.new_decoder_without_bom_handling()),
         }
     }
 
@@ -78,6 +132,9 @@ impl RawStream {
             datatype: self.datatype,
             span: self.span,
             known_size: self.known_size,
+            encoding: self.encoding,
+            bom_checked: self.bom_checked,
+            decoder: self.decoder,
         }
     }
 
@@ -94,6 +151,43 @@ impl RawStream {
         }
         Ok(())
     }
+
+    /// Splits the stream into lines, built on [`std::io::BufRead::read_until`] over the
+    /// existing `leftover` buffer so it never materializes more than one line at a time. Each
+    /// line is yielded as a `Value::string` with its trailing `\n`/`\r\n` stripped; a line that
+    /// isn't valid UTF-8 is yielded as a `Value::binary` (its line ending stripped the same way) instead of
+    /// erroring, mirroring the lossy-decode fallback `Iterator for RawStream` already uses.
+    pub fn lines(mut self) -> impl Iterator<Item = Result<Value, ShellError>> {
+        use std::io::BufRead;
+
+        std::iter::from_fn(move || {
+            if nu_utils::ctrl_c::was_pressed(&self.ctrlc) {
+                return None;
+            }
+
+            let mut buf = vec![];
+            match self.read_until(b'\n', &mut buf) {
+                Ok(0) => None,
+                Ok(_) => {
+                    let had_newline = buf.last() == Some(&b'\n');
+                    if had_newline {
+                        buf.pop();
+                        if buf.last() == Some(&b'\r') {
+                            buf.pop();
+                        }
+                    }
+
+                    match String::from_utf8(buf) {
+                        Ok(s) => Some(Ok(Value::string(s, self.span))),
+                        Err(err) => Some(Ok(Value::binary(err.into_bytes(), self.span))),
+                    }
+                }
+                Err(_) => Some(Err(ShellError::IOError {
+                    msg: "Error in stream".into(),
+                })),
+            }
+        })
+    }
 }
 impl Debug for RawStream {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -121,8 +215,10 @@ impl Iterator for RawStream {
                 })
             }),
             StreamDataType::Text => {
-                // We *may* be text. We're only going to try utf-8. Other decodings
-                // needs to be taken as binary first, then passed through `decode`.
+                // We *may* be text. By default we're only going to try utf-8, sniffing a BOM off
+                // the first chunk to switch to a different encoding when one's detected, or using
+                // whatever encoding `with_encoding` set up front. Anything else still needs to be
+                // taken as binary first, then passed through `decode`.
                 if let Some(buffer) = self.stream.next() {
                     match buffer {
                         Ok(mut v) => {
@@ -131,6 +227,34 @@ impl Iterator for RawStream {
                                 v.splice(0..0, self.leftover.drain(..));
                             }
 
+                            if !self.bom_checked {
+                                self.bom_checked = true;
+                                if let Some((encoding, bom_len)) = sniff_bom(&v) {
+                                    v.drain(0..bom_len);
+                                    if encoding != encoding_rs::UTF_8 {
+                                        self.encoding = Some(encoding);
+                                        self.decoder =
+                                            Some(encoding.new_decoder_without_bom_handling());
+                                    }
+                                }
+                            }
+
+                            if let Some(decoder) = &mut self.decoder {
+                                let mut out = String::with_capacity(v.len());
+                                let (_, _, had_errors) =
+                                    decoder.decode_to_string(&v, &mut out, false);
+
+                                return if had_errors {
+                                    // The decoder hit malformed input for this encoding - we're
+                                    // definitely binary, so switch to binary and stay there.
+                                    self.datatype = StreamDataType::Binary;
+                                    self.decoder = None;
+                                    Some(Ok(Value::binary(v, self.span)))
+                                } else {
+                                    Some(Ok(Value::string(out, self.span)))
+                                };
+                            }
+
                             match std::str::from_utf8(&v) {
                                 Ok(s) => {
                                     // Great, we have a complete string, let's output it
@@ -174,6 +298,17 @@ impl Iterator for RawStream {
                     self.leftover.clear();
 
                     Some(output)
+                } else if let Some(mut decoder) = self.decoder.take() {
+                    // The underlying stream is done; flush whatever the decoder still had
+                    // buffered internally (e.g. a trailing incomplete multi-byte sequence).
+                    let mut out = String::new();
+                    decoder.decode_to_string(&[], &mut out, true);
+
+                    if out.is_empty() {
+                        None
+                    } else {
+                        Some(Ok(Value::string(out, self.span)))
+                    }
                 } else {
                     None
                 }
@@ -182,6 +317,29 @@ impl Iterator for RawStream {
     }
 }
 
+impl std::io::BufRead for RawStream {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.leftover.is_empty() {
+            match self.stream.next() {
+                Some(Ok(chunk)) => self.leftover = chunk,
+                Some(Err(_)) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        "Error in stream",
+                    ))
+                }
+                None => {}
+            }
+        }
+
+        Ok(&self.leftover)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.leftover.drain(..amt);
+    }
+}
+
 impl std::io::Read for RawStream {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         let mut total_read = 0;
@@ -1,10 +1,120 @@
-use std::io::Read;
+use std::io::{ErrorKind, Read};
 
-use nu_plugin::{EvaluatedCall, LabeledError, Plugin, PluginPipelineData};
+use nu_plugin::{EngineInterface, EvaluatedCall, LabeledError, Plugin, PluginPipelineData};
 use nu_protocol::{record, Category, PluginSignature, Type, Value};
 
 pub struct FileCmd;
 
+/// How many leading bytes of the stream we sniff for a magic number, mirroring the `file(1)`
+/// convention of only ever looking at a small header rather than the whole file.
+const HEADER_LEN: usize = 512;
+
+/// A magic-number signature: `bytes` must appear at `offset` in the header for the match to hold.
+struct Signature {
+    offset: usize,
+    bytes: &'static [u8],
+    mime: &'static str,
+    description: &'static str,
+    extension: &'static str,
+}
+
+/// Known magic numbers, checked in order. Add a new entry here to teach `file` about another
+/// format.
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        offset: 0,
+        bytes: &[0x89, 0x50, 0x4E, 0x47],
+        mime: "image/png",
+        description: "PNG image",
+        extension: "png",
+    },
+    Signature {
+        offset: 0,
+        bytes: &[0xFF, 0xD8, 0xFF],
+        mime: "image/jpeg",
+        description: "JPEG image",
+        extension: "jpg",
+    },
+    Signature {
+        offset: 0,
+        bytes: b"GIF87a",
+        mime: "image/gif",
+        description: "GIF image (87a)",
+        extension: "gif",
+    },
+    Signature {
+        offset: 0,
+        bytes: b"GIF89a",
+        mime: "image/gif",
+        description: "GIF image (89a)",
+        extension: "gif",
+    },
+    Signature {
+        offset: 0,
+        bytes: b"%PDF",
+        mime: "application/pdf",
+        description: "PDF document",
+        extension: "pdf",
+    },
+    Signature {
+        offset: 0,
+        bytes: &[0x50, 0x4B, 0x03, 0x04],
+        mime: "application/zip",
+        description: "Zip archive (or OOXML document: docx/xlsx/pptx)",
+        extension: "zip",
+    },
+    Signature {
+        offset: 0,
+        bytes: &[0x1F, 0x8B],
+        mime: "application/gzip",
+        description: "gzip compressed data",
+        extension: "gz",
+    },
+];
+
+/// The result of sniffing a header: a known magic number, or a best-effort text/binary guess.
+struct Detection {
+    mime: &'static str,
+    description: &'static str,
+    extension: &'static str,
+}
+
+/// Matches `header` against [`SIGNATURES`], falling back to a printable-bytes heuristic for
+/// plain text and `application/octet-stream` for anything else.
+fn detect(header: &[u8]) -> Detection {
+    for sig in SIGNATURES {
+        let end = sig.offset + sig.bytes.len();
+        if header.len() >= end && &header[sig.offset..end] == sig.bytes {
+            return Detection {
+                mime: sig.mime,
+                description: sig.description,
+                extension: sig.extension,
+            };
+        }
+    }
+
+    let is_printable_ascii = |b: &u8| matches!(b, 0x09 | 0x0A | 0x0D | 0x20..=0x7E);
+    if header.iter().all(is_printable_ascii) {
+        Detection {
+            mime: "text/plain",
+            description: "ASCII text",
+            extension: "txt",
+        }
+    } else if std::str::from_utf8(header).is_ok() {
+        Detection {
+            mime: "text/plain",
+            description: "UTF-8 Unicode text",
+            extension: "txt",
+        }
+    } else {
+        Detection {
+            mime: "application/octet-stream",
+            description: "data",
+            extension: "bin",
+        }
+    }
+}
+
 impl Plugin for FileCmd {
     fn signature(&self) -> Vec<PluginSignature> {
         vec![PluginSignature::build("file")
@@ -19,36 +129,125 @@ impl Plugin for FileCmd {
         _name: &str,
         call: &EvaluatedCall,
         input: PluginPipelineData,
-    ) -> Result<Value, LabeledError> {
-        let PluginPipelineData::ExternalStream(val) = input else {
+        _engine: &mut EngineInterface<'_, '_>,
+    ) -> Result<PluginPipelineData, LabeledError> {
+        let PluginPipelineData::ExternalStream(mut pipe, _) = input else {
             return Err(LabeledError {
                 label: "ERROR from plugin".into(),
                 msg: "expected external stream".into(),
                 span: Some(call.head),
             });
         };
-        let mut reader = val.open().map_err(|e| LabeledError {
-            label: "ERROR from plugin".into(),
-            msg: format!("failed to open pipe: {}", e),
-            span: Some(call.head),
-        })?;
-        let mut vec = vec![];
-        loop {
-            let mut buf = [0; 4096];
-            let n = reader.read(&mut buf).map_err(|e| LabeledError {
-                label: "ERROR from plugin".into(),
-                msg: format!("failed to read pipe: {}", e),
-                span: Some(call.head),
-            })?;
-            if n == 0 {
-                break;
+
+        // `Read::read_exact` can't tell us how many bytes actually landed once it hits
+        // `UnexpectedEof`, so a short stream can't be distinguished from a partially-filled
+        // buffer after the fact. Reading one byte at a time sidesteps that: every successful
+        // call advances the count by exactly one, and the first `UnexpectedEof` tells us we've
+        // hit the end of a stream shorter than `HEADER_LEN`.
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        let mut byte = [0u8; 1];
+        let stream_ended = loop {
+            if header.len() == HEADER_LEN {
+                break false;
             }
-            vec.extend_from_slice(&buf[..n]);
-        }
-        let len = vec.len();
-        let record = record!(
-            "size" => Value::int(len as i64, call.head),
+            match pipe.read_exact(&mut byte) {
+                Ok(()) => header.push(byte[0]),
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break true,
+                Err(e) => {
+                    return Err(LabeledError {
+                        label: "ERROR from plugin".into(),
+                        msg: format!("failed to read pipe: {}", e),
+                        span: Some(call.head),
+                    })
+                }
+            }
+        };
+
+        let detection = detect(&header);
+        let mut record = record!(
+            "mime" => Value::string(detection.mime, call.head),
+            "description" => Value::string(detection.description, call.head),
+            "extension" => Value::string(detection.extension, call.head),
         );
-        Ok(Value::record(record, call.head))
+
+        // We only know the full size if the header read already consumed the whole stream;
+        // otherwise reporting it would mean buffering everything, which is exactly what this
+        // command used to do and what this change is meant to avoid.
+        if stream_ended {
+            record.push("size", Value::int(header.len() as i64, call.head));
+        }
+
+        Ok(PluginPipelineData::Value(Value::record(record, call.head)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect;
+
+    #[test]
+    fn detects_png() {
+        let header = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+        let detection = detect(&header);
+        assert_eq!(detection.mime, "image/png");
+        assert_eq!(detection.extension, "png");
+    }
+
+    #[test]
+    fn detects_jpeg() {
+        let header = [0xFF, 0xD8, 0xFF, 0xE0];
+        let detection = detect(&header);
+        assert_eq!(detection.mime, "image/jpeg");
+        assert_eq!(detection.extension, "jpg");
+    }
+
+    #[test]
+    fn detects_gif87a_and_gif89a() {
+        assert_eq!(detect(b"GIF87a...").mime, "image/gif");
+        assert_eq!(detect(b"GIF89a...").mime, "image/gif");
+    }
+
+    #[test]
+    fn detects_pdf() {
+        let detection = detect(b"%PDF-1.7\n");
+        assert_eq!(detection.mime, "application/pdf");
+        assert_eq!(detection.extension, "pdf");
+    }
+
+    #[test]
+    fn detects_zip_and_ooxml() {
+        let header = [0x50, 0x4B, 0x03, 0x04, 0x14, 0x00];
+        let detection = detect(&header);
+        assert_eq!(detection.mime, "application/zip");
+        assert_eq!(detection.extension, "zip");
+    }
+
+    #[test]
+    fn detects_gzip() {
+        let header = [0x1F, 0x8B, 0x08, 0x00];
+        let detection = detect(&header);
+        assert_eq!(detection.mime, "application/gzip");
+        assert_eq!(detection.extension, "gz");
+    }
+
+    #[test]
+    fn detects_ascii_text() {
+        let detection = detect(b"hello, world!\n");
+        assert_eq!(detection.mime, "text/plain");
+        assert_eq!(detection.description, "ASCII text");
+    }
+
+    #[test]
+    fn detects_utf8_text_with_non_ascii() {
+        let detection = detect("héllo, wörld!".as_bytes());
+        assert_eq!(detection.mime, "text/plain");
+        assert_eq!(detection.description, "UTF-8 Unicode text");
+    }
+
+    #[test]
+    fn falls_back_to_octet_stream_for_unknown_binary() {
+        let header = [0x00, 0x01, 0x02, 0xFF, 0xFE, 0x00, 0x00, 0x00];
+        let detection = detect(&header);
+        assert_eq!(detection.mime, "application/octet-stream");
     }
 }
@@ -0,0 +1,746 @@
+use nu_plugin::{EvaluatedCall, LabeledError};
+use nu_protocol::{PluginExample, Span, Value};
+
+pub const CMD_NAME: &str = "from preserves";
+pub const TO_CMD_NAME: &str = "to preserves";
+
+/// Record key used to round-trip a Preserves *symbol* through a nushell [`Value`], since nushell
+/// has no symbol type of its own. A record with only this key decodes back to a symbol instead
+/// of a string.
+const SYMBOL_KEY: &str = "_preserves_symbol";
+/// Record key used to round-trip a Preserves *set*. Its value is always a `Value::List`.
+const SET_KEY: &str = "_preserves_set";
+/// Record keys used to round-trip a Preserves *record* (a label plus ordered fields).
+const RECORD_LABEL_KEY: &str = "_preserves_label";
+const RECORD_FIELDS_KEY: &str = "_preserves_fields";
+
+const TAG_FALSE: u8 = 0x00;
+const TAG_TRUE: u8 = 0x01;
+const TAG_DOUBLE: u8 = 0x02;
+const TAG_INT: u8 = 0x03;
+const TAG_STRING: u8 = 0x04;
+const TAG_BYTE_STRING: u8 = 0x05;
+const TAG_SYMBOL: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x07;
+const TAG_SET: u8 = 0x08;
+const TAG_DICTIONARY: u8 = 0x09;
+const TAG_RECORD: u8 = 0x0A;
+
+pub fn examples() -> Vec<PluginExample> {
+    vec![PluginExample {
+        example: "0x[07 00 00 00 02 04 00 00 00 01 61 04 00 00 00 01 62] | from preserves".into(),
+        description: "Decode a binary-encoded Preserves sequence of two strings".into(),
+        result: Some(Value::test_list(vec![
+            Value::test_string("a"),
+            Value::test_string("b"),
+        ])),
+    }]
+}
+
+pub fn to_examples() -> Vec<PluginExample> {
+    vec![
+        PluginExample {
+            example: "{a: 1, b: 2} | to preserves --text".into(),
+            description: "Encode a record as readable Preserves text".into(),
+            result: Some(Value::test_string("{a: 1, b: 2}")),
+        },
+        PluginExample {
+            example: "[1 2 3] | to preserves --text".into(),
+            description: "Encode a list as a Preserves sequence".into(),
+            result: Some(Value::test_string("[1 2 3]")),
+        },
+    ]
+}
+
+pub fn from_preserves_call(call: &EvaluatedCall, value: &Value) -> Result<Value, LabeledError> {
+    let span = call.head;
+    let bytes = match value {
+        Value::Binary { val, .. } => val.clone(),
+        Value::String { val, .. } => val.clone().into_bytes(),
+        other => {
+            return Err(LabeledError {
+                label: "Unsupported input for `from preserves`".into(),
+                msg: format!("expected binary or string input, got {}", other.get_type()),
+                span: Some(span),
+            })
+        }
+    };
+
+    // The binary form is always tag-byte prefixed, and every tag byte we emit is below 0x20;
+    // the text form never starts with a control byte once leading whitespace is skipped, so the
+    // first non-whitespace byte is enough to tell the two syntaxes apart.
+    match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+        None => Ok(Value::nothing(span)),
+        Some(b) if *b <= TAG_RECORD => {
+            let mut pos = 0;
+            decode_value(&bytes, &mut pos, span)
+        }
+        Some(_) => {
+            let text = String::from_utf8(bytes).map_err(|err| LabeledError {
+                label: "Invalid Preserves text".into(),
+                msg: err.to_string(),
+                span: Some(span),
+            })?;
+            parse_text(&text, span)
+        }
+    }
+}
+
+pub fn to_preserves_call(call: &EvaluatedCall, value: &Value) -> Result<Value, LabeledError> {
+    let span = call.head;
+    let text = call.has_flag("text").map_err(|err| LabeledError {
+        label: "Error reading `--text`".into(),
+        msg: err.to_string(),
+        span: Some(span),
+    })?;
+
+    if text {
+        Ok(Value::string(value_to_text(value, span)?, span))
+    } else {
+        Ok(Value::binary(encode_value(value, span)?, span))
+    }
+}
+
+fn unsupported(value: &Value, span: Span) -> LabeledError {
+    LabeledError {
+        label: "Unsupported value for Preserves".into(),
+        msg: format!(
+            "{} cannot be represented as a Preserves value",
+            value.get_type()
+        ),
+        span: Some(span),
+    }
+}
+
+// ---- binary encoding -------------------------------------------------------------------
+
+fn encode_value(value: &Value, span: Span) -> Result<Vec<u8>, LabeledError> {
+    let mut buf = Vec::new();
+    match value {
+        Value::Bool { val, .. } => buf.push(if *val { TAG_TRUE } else { TAG_FALSE }),
+        Value::Int { val, .. } => {
+            buf.push(TAG_INT);
+            write_int(&mut buf, *val);
+        }
+        Value::Float { val, .. } => {
+            buf.push(TAG_DOUBLE);
+            buf.extend_from_slice(&val.to_be_bytes());
+        }
+        Value::String { val, .. } => {
+            buf.push(TAG_STRING);
+            write_bytes(&mut buf, val.as_bytes());
+        }
+        Value::Binary { val, .. } => {
+            buf.push(TAG_BYTE_STRING);
+            write_bytes(&mut buf, val);
+        }
+        Value::List { vals, .. } => {
+            buf.push(TAG_SEQUENCE);
+            write_count(&mut buf, vals.len());
+            for item in vals {
+                buf.extend(encode_value(item, span)?);
+            }
+        }
+        Value::Record { val, .. } => {
+            if let Some(name) = as_symbol(val) {
+                buf.push(TAG_SYMBOL);
+                write_bytes(&mut buf, name.as_bytes());
+            } else if let Some(items) = as_set(val) {
+                let mut encoded = items
+                    .iter()
+                    .map(|item| encode_value(item, span))
+                    .collect::<Result<Vec<_>, _>>()?;
+                encoded.sort();
+                buf.push(TAG_SET);
+                write_count(&mut buf, encoded.len());
+                for item in encoded {
+                    buf.extend(item);
+                }
+            } else if let Some((label, fields)) = as_record(val) {
+                buf.push(TAG_RECORD);
+                buf.extend(encode_value(label, span)?);
+                write_count(&mut buf, fields.len());
+                for field in fields {
+                    buf.extend(encode_value(field, span)?);
+                }
+            } else {
+                let mut entries = val
+                    .iter()
+                    .map(|(key, val)| {
+                        let key_bytes = encode_value(&Value::string(key.clone(), span), span)?;
+                        let val_bytes = encode_value(val, span)?;
+                        Ok((key_bytes, val_bytes))
+                    })
+                    .collect::<Result<Vec<_>, LabeledError>>()?;
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                buf.push(TAG_DICTIONARY);
+                write_count(&mut buf, entries.len());
+                for (key_bytes, val_bytes) in entries {
+                    buf.extend(key_bytes);
+                    buf.extend(val_bytes);
+                }
+            }
+        }
+        _ => return Err(unsupported(value, span)),
+    }
+    Ok(buf)
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_count(buf, bytes.len());
+    buf.extend_from_slice(bytes);
+}
+
+fn write_count(buf: &mut Vec<u8>, count: usize) {
+    buf.extend_from_slice(&(count as u32).to_be_bytes());
+}
+
+fn write_int(buf: &mut Vec<u8>, value: i64) {
+    let sign: u8 = if value < 0 { 1 } else { 0 };
+    let magnitude = value.unsigned_abs();
+    let mut bytes = magnitude.to_be_bytes().to_vec();
+    while bytes.len() > 1 && bytes[0] == 0 {
+        bytes.remove(0);
+    }
+    buf.push(sign);
+    buf.push(bytes.len() as u8);
+    buf.extend_from_slice(&bytes);
+}
+
+fn as_symbol(record: &nu_protocol::Record) -> Option<&str> {
+    if record.len() == 1 {
+        record.get(SYMBOL_KEY).and_then(|v| v.as_str().ok())
+    } else {
+        None
+    }
+}
+
+fn as_set(record: &nu_protocol::Record) -> Option<&[Value]> {
+    if record.len() == 1 {
+        record.get(SET_KEY).and_then(|v| v.as_list().ok())
+    } else {
+        None
+    }
+}
+
+fn as_record(record: &nu_protocol::Record) -> Option<(&Value, &[Value])> {
+    if record.len() == 2 {
+        let label = record.get(RECORD_LABEL_KEY)?;
+        let fields = record
+            .get(RECORD_FIELDS_KEY)
+            .and_then(|v| v.as_list().ok())?;
+        Some((label, fields))
+    } else {
+        None
+    }
+}
+
+// ---- binary decoding -------------------------------------------------------------------
+
+fn decode_value(bytes: &[u8], pos: &mut usize, span: Span) -> Result<Value, LabeledError> {
+    let tag = read_u8(bytes, pos, span)?;
+    match tag {
+        TAG_FALSE => Ok(Value::bool(false, span)),
+        TAG_TRUE => Ok(Value::bool(true, span)),
+        TAG_DOUBLE => {
+            let raw = read_slice(bytes, pos, 8, span)?;
+            Ok(Value::float(
+                f64::from_be_bytes(raw.try_into().unwrap()),
+                span,
+            ))
+        }
+        TAG_INT => Ok(Value::int(read_int(bytes, pos, span)?, span)),
+        TAG_STRING => {
+            let raw = read_bytes(bytes, pos, span)?;
+            let text = String::from_utf8(raw).map_err(|err| LabeledError {
+                label: "Invalid Preserves string".into(),
+                msg: err.to_string(),
+                span: Some(span),
+            })?;
+            Ok(Value::string(text, span))
+        }
+        TAG_BYTE_STRING => Ok(Value::binary(read_bytes(bytes, pos, span)?, span)),
+        TAG_SYMBOL => {
+            let raw = read_bytes(bytes, pos, span)?;
+            let name = String::from_utf8(raw).map_err(|err| LabeledError {
+                label: "Invalid Preserves symbol".into(),
+                msg: err.to_string(),
+                span: Some(span),
+            })?;
+            Ok(symbol_value(name, span))
+        }
+        TAG_SEQUENCE => {
+            let count = read_count(bytes, pos, span)?;
+            let mut vals = Vec::with_capacity(count);
+            for _ in 0..count {
+                vals.push(decode_value(bytes, pos, span)?);
+            }
+            Ok(Value::list(vals, span))
+        }
+        TAG_SET => {
+            let count = read_count(bytes, pos, span)?;
+            let mut vals = Vec::with_capacity(count);
+            for _ in 0..count {
+                vals.push(decode_value(bytes, pos, span)?);
+            }
+            Ok(set_value(vals, span))
+        }
+        TAG_DICTIONARY => {
+            let count = read_count(bytes, pos, span)?;
+            let mut record = nu_protocol::Record::new();
+            for _ in 0..count {
+                let key = decode_value(bytes, pos, span)?;
+                let val = decode_value(bytes, pos, span)?;
+                record.insert(dictionary_key(key, span)?, val);
+            }
+            Ok(Value::record(record, span))
+        }
+        TAG_RECORD => {
+            let label = decode_value(bytes, pos, span)?;
+            let count = read_count(bytes, pos, span)?;
+            let mut fields = Vec::with_capacity(count);
+            for _ in 0..count {
+                fields.push(decode_value(bytes, pos, span)?);
+            }
+            Ok(record_value(label, fields, span))
+        }
+        other => Err(LabeledError {
+            label: "Invalid Preserves binary data".into(),
+            msg: format!("unknown tag byte 0x{other:02x}"),
+            span: Some(span),
+        }),
+    }
+}
+
+fn dictionary_key(key: Value, span: Span) -> Result<String, LabeledError> {
+    match key {
+        Value::String { val, .. } => Ok(val),
+        Value::Record { val, .. } => {
+            as_symbol(&val)
+                .map(str::to_string)
+                .ok_or_else(|| LabeledError {
+                    label: "Unsupported Preserves dictionary key".into(),
+                    msg: "only strings and symbols can be used as dictionary keys".into(),
+                    span: Some(span),
+                })
+        }
+        other => Err(LabeledError {
+            label: "Unsupported Preserves dictionary key".into(),
+            msg: format!("{} cannot be used as a dictionary key", other.get_type()),
+            span: Some(span),
+        }),
+    }
+}
+
+fn symbol_value(name: String, span: Span) -> Value {
+    let mut record = nu_protocol::Record::new();
+    record.insert(SYMBOL_KEY, Value::string(name, span));
+    Value::record(record, span)
+}
+
+fn set_value(items: Vec<Value>, span: Span) -> Value {
+    let mut record = nu_protocol::Record::new();
+    record.insert(SET_KEY, Value::list(items, span));
+    Value::record(record, span)
+}
+
+fn record_value(label: Value, fields: Vec<Value>, span: Span) -> Value {
+    let mut record = nu_protocol::Record::new();
+    record.insert(RECORD_LABEL_KEY, label);
+    record.insert(RECORD_FIELDS_KEY, Value::list(fields, span));
+    Value::record(record, span)
+}
+
+fn read_u8(bytes: &[u8], pos: &mut usize, span: Span) -> Result<u8, LabeledError> {
+    let byte = *bytes.get(*pos).ok_or_else(|| truncated(span))?;
+    *pos += 1;
+    Ok(byte)
+}
+
+fn read_slice<'a>(
+    bytes: &'a [u8],
+    pos: &mut usize,
+    len: usize,
+    span: Span,
+) -> Result<&'a [u8], LabeledError> {
+    let end = pos.checked_add(len).ok_or_else(|| truncated(span))?;
+    let slice = bytes.get(*pos..end).ok_or_else(|| truncated(span))?;
+    *pos = end;
+    Ok(slice)
+}
+
+fn read_count(bytes: &[u8], pos: &mut usize, span: Span) -> Result<usize, LabeledError> {
+    let raw = read_slice(bytes, pos, 4, span)?;
+    Ok(u32::from_be_bytes(raw.try_into().unwrap()) as usize)
+}
+
+fn read_bytes(bytes: &[u8], pos: &mut usize, span: Span) -> Result<Vec<u8>, LabeledError> {
+    let len = read_count(bytes, pos, span)?;
+    Ok(read_slice(bytes, pos, len, span)?.to_vec())
+}
+
+fn read_int(bytes: &[u8], pos: &mut usize, span: Span) -> Result<i64, LabeledError> {
+    let sign = read_u8(bytes, pos, span)?;
+    let len = read_u8(bytes, pos, span)? as usize;
+    let magnitude_bytes = read_slice(bytes, pos, len, span)?;
+    let mut magnitude: u64 = 0;
+    for byte in magnitude_bytes {
+        magnitude = (magnitude << 8) | *byte as u64;
+    }
+    Ok(if sign == 1 {
+        -(magnitude as i64)
+    } else {
+        magnitude as i64
+    })
+}
+
+fn truncated(span: Span) -> LabeledError {
+    LabeledError {
+        label: "Invalid Preserves binary data".into(),
+        msg: "unexpected end of input".into(),
+        span: Some(span),
+    }
+}
+
+// ---- text encoding ----------------------------------------------------------------------
+
+fn value_to_text(value: &Value, span: Span) -> Result<String, LabeledError> {
+    let mut out = String::new();
+    write_text(value, span, &mut out)?;
+    Ok(out)
+}
+
+fn write_text(value: &Value, span: Span, out: &mut String) -> Result<(), LabeledError> {
+    match value {
+        Value::Bool { val, .. } => out.push_str(if *val { "#t" } else { "#f" }),
+        Value::Int { val, .. } => out.push_str(&val.to_string()),
+        Value::Float { val, .. } => {
+            let text = val.to_string();
+            out.push_str(&text);
+            if !text.contains('.') && !text.contains('e') && !text.contains('E') {
+                out.push_str(".0");
+            }
+        }
+        Value::String { val, .. } => out.push_str(&quote_string(val)),
+        Value::Binary { val, .. } => {
+            out.push_str("#\"");
+            out.push_str(&to_hex(val));
+            out.push('"');
+        }
+        Value::List { vals, .. } => {
+            out.push('[');
+            for (index, item) in vals.iter().enumerate() {
+                if index > 0 {
+                    out.push(' ');
+                }
+                write_text(item, span, out)?;
+            }
+            out.push(']');
+        }
+        Value::Record { val, .. } => {
+            if let Some(name) = as_symbol(val) {
+                out.push_str(&quote_symbol(name));
+            } else if let Some(items) = as_set(val) {
+                out.push_str("#{");
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push(' ');
+                    }
+                    write_text(item, span, out)?;
+                }
+                out.push('}');
+            } else if let Some((label, fields)) = as_record(val) {
+                out.push('<');
+                write_text(label, span, out)?;
+                for field in fields {
+                    out.push(' ');
+                    write_text(field, span, out)?;
+                }
+                out.push('>');
+            } else {
+                out.push('{');
+                for (index, (key, item)) in val.iter().enumerate() {
+                    if index > 0 {
+                        out.push_str(", ");
+                    }
+                    out.push_str(&quote_symbol(key));
+                    out.push_str(": ");
+                    write_text(item, span, out)?;
+                }
+                out.push('}');
+            }
+        }
+        _ => return Err(unsupported(value, span)),
+    }
+    Ok(())
+}
+
+fn quote_string(val: &str) -> String {
+    format!("\"{}\"", val.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quotes a Preserves symbol with `|...|` unless it's already a valid bare identifier.
+fn quote_symbol(name: &str) -> String {
+    let is_plain = !name.is_empty()
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    if is_plain {
+        name.to_string()
+    } else {
+        format!("|{}|", name.replace('\\', "\\\\").replace('|', "\\|"))
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// ---- text decoding ----------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum PToken {
+    Ident(String),
+    QuotedString(String),
+    QuotedSymbol(String),
+    HexBytes(Vec<u8>),
+    BoolTrue,
+    BoolFalse,
+    SetOpen,
+    Symbol(char),
+}
+
+fn parse_text(input: &str, span: Span) -> Result<Value, LabeledError> {
+    let tokens = tokenize_text(input, span)?;
+    let mut parser = PreservesParser {
+        tokens: &tokens,
+        pos: 0,
+        span,
+    };
+    let value = parser.parse_value()?;
+    Ok(value)
+}
+
+fn tokenize_text(input: &str, span: Span) -> Result<Vec<PToken>, LabeledError> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() || c == ',' => {
+                chars.next();
+            }
+            '#' => {
+                chars.next();
+                match chars.peek() {
+                    Some('t') => {
+                        chars.next();
+                        tokens.push(PToken::BoolTrue);
+                    }
+                    Some('f') => {
+                        chars.next();
+                        tokens.push(PToken::BoolFalse);
+                    }
+                    Some('{') => {
+                        chars.next();
+                        tokens.push(PToken::SetOpen);
+                    }
+                    Some('"') => {
+                        chars.next();
+                        let mut hex = String::new();
+                        for c in chars.by_ref() {
+                            if c == '"' {
+                                break;
+                            }
+                            hex.push(c);
+                        }
+                        let bytes = decode_hex(&hex, span)?;
+                        tokens.push(PToken::HexBytes(bytes));
+                    }
+                    other => {
+                        return Err(LabeledError {
+                            label: "Invalid Preserves text".into(),
+                            msg: format!("unexpected `#{other:?}`"),
+                            span: Some(span),
+                        })
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => {
+                            if let Some(escaped) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        Some('"') | None => break,
+                        Some(c) => value.push(c),
+                    }
+                }
+                tokens.push(PToken::QuotedString(value));
+            }
+            '|' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => {
+                            if let Some(escaped) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        Some('|') | None => break,
+                        Some(c) => value.push(c),
+                    }
+                }
+                tokens.push(PToken::QuotedSymbol(value));
+            }
+            '<' | '>' | '{' | '}' | '[' | ']' | ':' => {
+                chars.next();
+                tokens.push(PToken::Symbol(c));
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "<>{}[]:,\"|#".contains(c) {
+                        break;
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+                tokens.push(PToken::Ident(value));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn decode_hex(hex: &str, span: Span) -> Result<Vec<u8>, LabeledError> {
+    let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if hex.len() % 2 != 0 {
+        return Err(LabeledError {
+            label: "Invalid Preserves byte string".into(),
+            msg: "hex byte string must have an even number of digits".into(),
+            span: Some(span),
+        });
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|err| LabeledError {
+                label: "Invalid Preserves byte string".into(),
+                msg: err.to_string(),
+                span: Some(span),
+            })
+        })
+        .collect()
+}
+
+struct PreservesParser<'a> {
+    tokens: &'a [PToken],
+    pos: usize,
+    span: Span,
+}
+
+impl<'a> PreservesParser<'a> {
+    fn peek(&self) -> Option<&PToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&PToken> {
+        let tok = self.tokens.get(self.pos);
+        self.pos += 1;
+        tok
+    }
+
+    fn unexpected(&self, expected: &str) -> LabeledError {
+        LabeledError {
+            label: "Invalid Preserves text".into(),
+            msg: format!("expected {expected}, found {:?}", self.tokens.get(self.pos)),
+            span: Some(self.span),
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), LabeledError> {
+        match self.next() {
+            Some(PToken::Symbol(c)) if *c == symbol => Ok(()),
+            _ => Err(self.unexpected(&format!("`{symbol}`"))),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, LabeledError> {
+        let span = self.span;
+        match self.next().cloned() {
+            Some(PToken::BoolTrue) => Ok(Value::bool(true, span)),
+            Some(PToken::BoolFalse) => Ok(Value::bool(false, span)),
+            Some(PToken::HexBytes(bytes)) => Ok(Value::binary(bytes, span)),
+            Some(PToken::QuotedString(s)) => Ok(Value::string(s, span)),
+            Some(PToken::QuotedSymbol(s)) => Ok(symbol_value(s, span)),
+            Some(PToken::SetOpen) => {
+                let mut items = vec![];
+                while !matches!(self.peek(), Some(PToken::Symbol('}'))) {
+                    items.push(self.parse_value()?);
+                }
+                self.expect_symbol('}')?;
+                Ok(set_value(items, span))
+            }
+            Some(PToken::Symbol('[')) => {
+                let mut items = vec![];
+                while !matches!(self.peek(), Some(PToken::Symbol(']'))) {
+                    items.push(self.parse_value()?);
+                }
+                self.expect_symbol(']')?;
+                Ok(Value::list(items, span))
+            }
+            Some(PToken::Symbol('{')) => {
+                let mut record = nu_protocol::Record::new();
+                while !matches!(self.peek(), Some(PToken::Symbol('}'))) {
+                    let key = match self.next().cloned() {
+                        Some(PToken::Ident(s)) => s,
+                        Some(PToken::QuotedString(s)) => s,
+                        Some(PToken::QuotedSymbol(s)) => s,
+                        _ => return Err(self.unexpected("a dictionary key")),
+                    };
+                    self.expect_symbol(':')?;
+                    let value = self.parse_value()?;
+                    record.insert(key, value);
+                }
+                self.expect_symbol('}')?;
+                Ok(Value::record(record, span))
+            }
+            Some(PToken::Symbol('<')) => {
+                let label = self.parse_value()?;
+                let mut fields = vec![];
+                while !matches!(self.peek(), Some(PToken::Symbol('>'))) {
+                    fields.push(self.parse_value()?);
+                }
+                self.expect_symbol('>')?;
+                Ok(record_value(label, fields, span))
+            }
+            Some(PToken::Ident(s)) => Ok(parse_ident_value(&s, span)),
+            _ => Err(self.unexpected("a value")),
+        }
+    }
+}
+
+fn parse_ident_value(ident: &str, span: Span) -> Value {
+    if let Ok(int) = ident.parse::<i64>() {
+        return Value::int(int, span);
+    }
+    if ident.contains('.') || ident.contains('e') || ident.contains('E') {
+        if let Ok(float) = ident.parse::<f64>() {
+            return Value::float(float, span);
+        }
+    }
+    symbol_value(ident.to_string(), span)
+}
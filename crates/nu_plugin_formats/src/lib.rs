@@ -1,7 +1,7 @@
 mod from;
 
-use from::{eml, ics, ini, vcf};
-use nu_plugin::{EvaluatedCall, LabeledError, Plugin, PluginPipelineData};
+use from::{eml, ics, ini, preserves, vcf};
+use nu_plugin::{EngineInterface, EvaluatedCall, LabeledError, Plugin, PluginPipelineData};
 use nu_protocol::{Category, PluginSignature, SyntaxShape, Type, Value};
 
 pub struct FromCmds;
@@ -39,6 +39,22 @@ impl Plugin for FromCmds {
                 .plugin_examples(ini::examples())
                 .supports_pipelined_input(true)
                 .category(Category::Formats),
+            PluginSignature::build(preserves::CMD_NAME)
+                .input_output_types(vec![(Type::Binary, Type::Any)])
+                .usage("Parse a Preserves document (binary or text syntax) into a value.")
+                .plugin_examples(preserves::examples())
+                .supports_pipelined_input(true)
+                .category(Category::Formats),
+            PluginSignature::build(preserves::TO_CMD_NAME)
+                .input_output_types(vec![(Type::Any, Type::Binary)])
+                .switch(
+                    "text",
+                    "Emit the human-readable Preserves text syntax instead of the compact binary syntax",
+                    Some('t'),
+                )
+                .usage("Convert a value into a Preserves document.")
+                .plugin_examples(preserves::to_examples())
+                .category(Category::Formats),
         ]
     }
 
@@ -47,7 +63,15 @@ impl Plugin for FromCmds {
         name: &str,
         call: &EvaluatedCall,
         input: PluginPipelineData,
-    ) -> Result<Value, LabeledError> {
+        _engine: &mut EngineInterface<'_, '_>,
+    ) -> Result<PluginPipelineData, LabeledError> {
+        // `to preserves` converts a value rather than parsing a stream, so it doesn't need the
+        // external-stream input the `from *` commands below require.
+        if name == preserves::TO_CMD_NAME {
+            let value = input.into_value();
+            return preserves::to_preserves_call(call, &value).map(PluginPipelineData::Value);
+        }
+
         if !matches!(input, PluginPipelineData::ExternalStream(_, _)) {
             return Err(LabeledError {
                 label: "Plugin call with wrong input type".into(),
@@ -63,11 +87,13 @@ impl Plugin for FromCmds {
             ics::CMD_NAME => ics::from_ics_call(call, &value),
             vcf::CMD_NAME => vcf::from_vcf_call(call, &value),
             ini::CMD_NAME => ini::from_ini_call(call, &value),
+            preserves::CMD_NAME => preserves::from_preserves_call(call, &value),
             _ => Err(LabeledError {
                 label: "Plugin call with wrong name signature".into(),
                 msg: "the signature used to call the plugin does not match any name in the plugin signature vector".into(),
                 span: Some(call.head),
             }),
         }
+        .map(PluginPipelineData::Value)
     }
 }
@@ -1,6 +1,14 @@
-use nu_plugin::{serve_plugin, MsgPackSerializer};
+use nu_plugin::{serve_plugin, CodecRegistry, MsgPackSerializer, PreservesSerializer};
 use nu_plugin_formats_v1::FromCmds;
 
 fn main() {
-    serve_plugin(&mut FromCmds, MsgPackSerializer {})
+    serve_plugin(
+        &mut FromCmds,
+        // msgpack is registered first, so it stays the default nushell falls back to if it
+        // doesn't recognize anything else we offer; preserves is offered alongside it so either
+        // side can pick the self-describing, diffable format when that's more useful.
+        CodecRegistry::new()
+            .with_codec("msgpack", MsgPackSerializer {})
+            .with_codec("preserves", PreservesSerializer {}),
+    )
 }
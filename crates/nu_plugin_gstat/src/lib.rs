@@ -0,0 +1,164 @@
+mod nu;
+
+use git2::{Repository, RepositoryState, Status};
+use nu_plugin::LabeledError;
+use nu_protocol::{record, Record, Span, Spanned, Value};
+use std::path::PathBuf;
+
+pub struct GStat;
+
+impl Default for GStat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GStat {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn gstat(
+        &mut self,
+        input_value: &Value,
+        path: Option<Spanned<String>>,
+        span: Span,
+    ) -> Result<Value, LabeledError> {
+        let repo_path = match path {
+            Some(p) => PathBuf::from(p.item),
+            None => match input_value.coerce_string() {
+                Ok(s) if !s.is_empty() => PathBuf::from(s),
+                _ => std::env::current_dir().map_err(|e| LabeledError {
+                    label: "Unable to determine current directory".into(),
+                    msg: e.to_string(),
+                    span: Some(span),
+                })?,
+            },
+        };
+
+        let mut repo = match Repository::discover(&repo_path) {
+            Ok(repo) => repo,
+            Err(e) => {
+                return Ok(Value::record(
+                    record! {
+                        "repo_name" => Value::string(repo_path.to_string_lossy(), span),
+                        "branch" => Value::string("", span),
+                        "state" => Value::string("not a repo", span),
+                        "is_clean" => Value::bool(true, span),
+                        "ahead" => Value::int(0, span),
+                        "behind" => Value::int(0, span),
+                        "stashes" => Value::int(0, span),
+                        "upstream" => Value::string("", span),
+                        "error" => Value::string(e.message(), span),
+                    },
+                    span,
+                ))
+            }
+        };
+
+        let head = repo.head().ok();
+        let branch_name = head
+            .as_ref()
+            .and_then(|h| h.shorthand())
+            .unwrap_or("HEAD (detached)")
+            .to_string();
+
+        let repo_name = repo
+            .workdir()
+            .unwrap_or_else(|| repo.path())
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let is_clean = repo
+            .statuses(None)
+            .map(|statuses| {
+                statuses
+                    .iter()
+                    .all(|s| s.status() == Status::CURRENT || s.status() == Status::IGNORED)
+            })
+            .unwrap_or(true);
+
+        let state = repository_state_name(repo.state());
+        let stashes = count_stashes(&mut repo);
+        let (upstream, ahead, behind) = upstream_divergence(&repo);
+
+        Ok(Value::record(
+            record! {
+                "repo_name" => Value::string(repo_name, span),
+                "branch" => Value::string(branch_name, span),
+                "state" => Value::string(state, span),
+                "is_clean" => Value::bool(is_clean, span),
+                "ahead" => Value::int(ahead, span),
+                "behind" => Value::int(behind, span),
+                "stashes" => Value::int(stashes, span),
+                "upstream" => Value::string(upstream, span),
+            },
+            span,
+        ))
+    }
+}
+
+/// Maps `git2`'s in-progress-operation enum to the short strings `starship` and similar prompts
+/// use (`merge`, `rebase`, `cherry-pick`, `bisect`, `revert`), collapsing the operation's
+/// sub-states (e.g. `RebaseInteractive`/`RebaseMerge`) into their parent name.
+fn repository_state_name(state: RepositoryState) -> &'static str {
+    match state {
+        RepositoryState::Clean => "clean",
+        RepositoryState::Merge => "merge",
+        RepositoryState::Revert | RepositoryState::RevertSequence => "revert",
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => "cherry-pick",
+        RepositoryState::Bisect => "bisect",
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => "rebase",
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => "am",
+    }
+}
+
+/// Counts stashed entries via `git2`'s stash walk. A repo with no stash (or one we can't open,
+/// e.g. a bare repo) simply reports zero rather than erroring the whole command.
+fn count_stashes(repo: &mut Repository) -> i64 {
+    let mut count = 0i64;
+    let _ = repo.stash_foreach(|_, _, _| {
+        count += 1;
+        true
+    });
+    count
+}
+
+/// Resolves the current branch's upstream (if any) and how far `HEAD` and the upstream have
+/// diverged. Returns `("", 0, 0)` for a detached HEAD or a branch with no upstream configured,
+/// rather than treating either as an error.
+fn upstream_divergence(repo: &Repository) -> (String, i64, i64) {
+    let Ok(head) = repo.head() else {
+        return (String::new(), 0, 0);
+    };
+    let Some(branch_name) = head.shorthand() else {
+        return (String::new(), 0, 0);
+    };
+    let Ok(local_branch) = repo.find_branch(branch_name, git2::BranchType::Local) else {
+        return (String::new(), 0, 0);
+    };
+    let Ok(upstream) = local_branch.upstream() else {
+        return (String::new(), 0, 0);
+    };
+    let upstream_name = upstream
+        .name()
+        .ok()
+        .flatten()
+        .unwrap_or("")
+        .to_string();
+
+    let (Some(local_oid), Some(upstream_oid)) = (
+        head.target(),
+        upstream.get().target(),
+    ) else {
+        return (upstream_name, 0, 0);
+    };
+
+    match repo.graph_ahead_behind(local_oid, upstream_oid) {
+        Ok((ahead, behind)) => (upstream_name, ahead as i64, behind as i64),
+        Err(_) => (upstream_name, 0, 0),
+    }
+}
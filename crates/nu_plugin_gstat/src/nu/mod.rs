@@ -1,5 +1,5 @@
 use crate::GStat;
-use nu_plugin::{EvaluatedCall, LabeledError, Plugin, PluginPipelineData};
+use nu_plugin::{EngineInterface, EvaluatedCall, LabeledError, Plugin, PluginPipelineData};
 use nu_protocol::{Category, PluginSignature, Spanned, SyntaxShape, Value};
 
 impl Plugin for GStat {
@@ -15,13 +15,15 @@ impl Plugin for GStat {
         name: &str,
         call: &EvaluatedCall,
         input: PluginPipelineData,
-    ) -> Result<Value, LabeledError> {
+        _engine: &mut EngineInterface<'_, '_>,
+    ) -> Result<PluginPipelineData, LabeledError> {
         if name != "gstat" {
-            return Ok(Value::nothing(call.head));
+            return Ok(PluginPipelineData::Value(Value::nothing(call.head)));
         }
 
         let repo_path: Option<Spanned<String>> = call.opt(0)?;
         // eprintln!("input value: {:#?}", &input);
         self.gstat(&input.into_value(), repo_path, call.head)
+            .map(PluginPipelineData::Value)
     }
 }